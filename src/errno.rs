@@ -60,6 +60,15 @@ pub fn errno() -> i32 {
     }
 }
 
+/// Sets the platform-specific value of errno
+#[cfg(not(target_os = "redox"))]
+pub(crate) fn set_errno(errno: i32) {
+    // Safe because errno is a thread-local variable
+    unsafe {
+        *errno_location() = errno as c_int;
+    }
+}
+
 impl Errno {
     pub fn last() -> Self {
         last()
@@ -2,10 +2,14 @@ use crate::errno::Errno;
 use libc::{self, c_char, c_int, c_uint, size_t, ssize_t};
 use std::ffi::OsString;
 #[cfg(not(target_os = "redox"))]
+use std::mem;
+#[cfg(not(target_os = "redox"))]
 use std::os::raw;
 use std::os::unix::ffi::OsStringExt;
 use std::os::unix::io::RawFd;
 use crate::sys::stat::Mode;
+#[cfg(not(target_os = "redox"))]
+use crate::unistd::{Pid, Whence};
 use crate::{NixPath, Result};
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -186,6 +190,109 @@ pub fn openat<P: ?Sized + NixPath>(
     Errno::result(fd)
 }
 
+#[cfg(target_os = "linux")]
+libc_bitflags!(
+    /// Restrictions on how [`openat2`](fn.openat2.html) resolves each
+    /// component of a path.
+    pub struct ResolveFlags: u64 {
+        /// Don't cross mount points, including bind mounts.
+        RESOLVE_NO_XDEV;
+        /// Don't follow "magic links", like the ones in `/proc/[pid]/fd`.
+        RESOLVE_NO_MAGICLINKS;
+        /// Don't follow any symbolic link, failing with `ELOOP` instead.
+        /// This implies `RESOLVE_NO_MAGICLINKS`.
+        RESOLVE_NO_SYMLINKS;
+        /// Fail with `EXDEV` if the path, or any symbolic link in it,
+        /// resolves outside of the directory `dirfd`, for example through
+        /// `..` or an absolute path.
+        RESOLVE_BENEATH;
+        /// Resolve the path as if `dirfd` was the root directory of the
+        /// process, so that `..` and absolute symbolic links can't escape it.
+        RESOLVE_IN_ROOT;
+        /// Only resolve the path from the kernel's lookup cache, failing
+        /// with `EAGAIN` if it would have to block.  Since Linux 5.12.
+        RESOLVE_CACHED;
+    }
+);
+
+// libc only defines `open_how` when built with Rust 1.40 or newer, because
+// it's `#[non_exhaustive]` there, so it isn't available at nix's minimum
+// supported Rust version.
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+struct open_how {
+    flags: u64,
+    mode: u64,
+    resolve: u64,
+}
+
+/// How [`openat2`](fn.openat2.html) opens a file.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::fcntl::{OFlag, OpenHow, ResolveFlags};
+/// # use nix::sys::stat::Mode;
+/// let how = OpenHow::new()
+///     .flags(OFlag::O_CREAT | OFlag::O_WRONLY | OFlag::O_CLOEXEC)
+///     .mode(Mode::S_IRUSR | Mode::S_IWUSR)
+///     .resolve(ResolveFlags::RESOLVE_BENEATH | ResolveFlags::RESOLVE_NO_SYMLINKS);
+/// ```
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct OpenHow(open_how);
+
+#[cfg(target_os = "linux")]
+impl OpenHow {
+    /// Open the file read-only, with no mode and no resolve restrictions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the flags of the file, like the `oflag` argument of
+    /// [`openat`](fn.openat.html).  Unlike `openat`, unknown flags are an
+    /// error.
+    pub fn flags(mut self, flags: OFlag) -> Self {
+        self.0.flags = flags.bits() as u64;
+        self
+    }
+
+    /// Set the mode of the file, which may only be given with `O_CREAT` or
+    /// `O_TMPFILE`.
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.0.mode = u64::from(mode.bits());
+        self
+    }
+
+    /// Set the restrictions on resolving the path.
+    pub fn resolve(mut self, resolve: ResolveFlags) -> Self {
+        self.0.resolve = resolve.bits();
+        self
+    }
+}
+
+/// Open a file relative to the directory `dirfd`, like
+/// [`openat`](fn.openat.html), but with restrictions on how the path is
+/// resolved.
+///
+/// With `RESOLVE_BENEATH` or `RESOLVE_IN_ROOT`, the kernel checks each path
+/// component as it resolves it, so unlike checking the path before opening
+/// it, a concurrent rename or symbolic link can't make it escape `dirfd`.
+///
+/// Fails with `ENOSYS` before Linux 5.6, and with `E2BIG` or `EINVAL` if the
+/// kernel doesn't know some of the flags.
+///
+/// See also [openat2(2)](http://man7.org/linux/man-pages/man2/openat2.2.html)
+#[cfg(target_os = "linux")]
+pub fn openat2<P: ?Sized + NixPath>(dirfd: RawFd, path: &P, how: OpenHow) -> Result<RawFd> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(libc::SYS_openat2, dirfd, cstr.as_ptr(), &how.0 as *const open_how,
+                      mem::size_of::<open_how>())
+    })?;
+    Errno::result(res).map(|fd| fd as RawFd)
+}
+
 #[cfg(not(target_os = "redox"))]
 pub fn renameat<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
     old_dirfd: Option<RawFd>,
@@ -206,6 +313,236 @@ pub fn renameat<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
     Errno::result(res).map(drop)
 }
 
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+libc_bitflags! {
+    /// Flags for [`renameat2`](fn.renameat2.html).
+    pub struct RenameFlags: u32 {
+        /// Atomically exchange the old and new paths, which must both exist.
+        RENAME_EXCHANGE;
+        /// Fail with `EEXIST` instead of replacing the new path.
+        RENAME_NOREPLACE;
+        /// Leave a whiteout object at the old path, for overlay and union
+        /// file systems.  Requires the `CAP_MKNOD` capability.
+        RENAME_WHITEOUT;
+    }
+}
+
+/// Rename a file like [`renameat`](fn.renameat.html), with extra `flags`.
+///
+/// `RENAME_NOREPLACE` renames without clobbering an existing file, without
+/// the race of checking that it doesn't exist first.  `RENAME_EXCHANGE`
+/// atomically swaps two files, so that each path always refers to one of
+/// them.  Fails with `EINVAL` if the file system doesn't support `flags`.
+///
+/// See also [renameat2(2)](http://man7.org/linux/man-pages/man2/rename.2.html)
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn renameat2<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
+    old_dirfd: Option<RawFd>,
+    old_path: &P1,
+    new_dirfd: Option<RawFd>,
+    new_path: &P2,
+    flags: RenameFlags,
+) -> Result<()> {
+    let res = old_path.with_nix_path(|old_cstr| {
+        new_path.with_nix_path(|new_cstr| unsafe {
+            libc::renameat2(
+                at_rawfd(old_dirfd),
+                old_cstr.as_ptr(),
+                at_rawfd(new_dirfd),
+                new_cstr.as_ptr(),
+                flags.bits(),
+            )
+        })
+    })??;
+    Errno::result(res).map(drop)
+}
+
+/// Create an unnamed temporary file in the directory `dir`.
+///
+/// The file is opened with `O_TMPFILE`, so it has no name and disappears when
+/// closed, unless [`link_tmpfile`](fn.link_tmpfile.html) gives it one.  This
+/// allows writing a file completely before publishing it atomically, so that
+/// readers never see it half-written, even after a crash.  `oflag` must
+/// include `O_WRONLY` or `O_RDWR`, and must not include `O_EXCL` if the file
+/// is to be linked.
+///
+/// Fails with `EOPNOTSUPP` or `EISDIR` if the file system of `dir` doesn't
+/// support `O_TMPFILE`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::fcntl::{link_tmpfile, open_tmpfile, OFlag};
+/// # use nix::sys::stat::Mode;
+/// # use nix::unistd::{close, fsync, write};
+/// let fd = open_tmpfile(None, "/etc", OFlag::O_WRONLY | OFlag::O_CLOEXEC,
+///                       Mode::from_bits_truncate(0o644)).unwrap();
+/// write(fd, b"new contents").unwrap();
+/// fsync(fd).unwrap();
+/// link_tmpfile(fd, None, "/etc/myapp.conf").unwrap();
+/// close(fd).unwrap();
+/// ```
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn open_tmpfile<P: ?Sized + NixPath>(
+    dirfd: Option<RawFd>,
+    dir: &P,
+    oflag: OFlag,
+    mode: Mode,
+) -> Result<RawFd> {
+    openat(at_rawfd(dirfd), dir, oflag | OFlag::O_TMPFILE, mode)
+}
+
+/// Give a name to the file open as `fd`, like a file created with
+/// [`open_tmpfile`](fn.open_tmpfile.html).
+///
+/// Like `link`, this fails with `EEXIST` if `newpath` already exists.  To
+/// replace an existing file atomically, link to a temporary name and then
+/// [`renameat`](fn.renameat.html) it.
+///
+/// Linking a file descriptor with `AT_EMPTY_PATH` requires the
+/// `CAP_DAC_READ_SEARCH` capability, so without it this links the file
+/// through `/proc/self/fd` instead, which needs `/proc` to be mounted.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn link_tmpfile<P: ?Sized + NixPath>(
+    fd: RawFd,
+    newdirfd: Option<RawFd>,
+    newpath: &P,
+) -> Result<()> {
+    newpath.with_nix_path(|cstr| {
+        let res = unsafe {
+            libc::linkat(fd, b"\0".as_ptr() as *const c_char, at_rawfd(newdirfd),
+                         cstr.as_ptr(), libc::AT_EMPTY_PATH)
+        };
+        match Errno::result(res) {
+            Err(crate::Error::Sys(Errno::ENOENT)) => {
+                let proc_path = format!("/proc/self/fd/{}\0", fd);
+                let res = unsafe {
+                    libc::linkat(libc::AT_FDCWD, proc_path.as_ptr() as *const c_char,
+                                 at_rawfd(newdirfd), cstr.as_ptr(),
+                                 libc::AT_SYMLINK_FOLLOW)
+                };
+                Errno::result(res).map(drop)
+            },
+            res => res.map(drop),
+        }
+    })?
+}
+
+/// The largest file handle that the kernel creates.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const MAX_HANDLE_SZ: usize = 128;
+
+// libc 0.2.150 has neither `file_handle` nor `MAX_HANDLE_SZ`.  Later versions
+// only have them on Linux, and declare `f_handle` as a flexible array member,
+// so this copy also saves building a buffer large enough for any handle.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+struct file_handle {
+    handle_bytes: c_uint,
+    handle_type: c_int,
+    f_handle: [u8; MAX_HANDLE_SZ],
+}
+
+/// An opaque handle to a file, returned by
+/// [`name_to_handle_at`](fn.name_to_handle_at.html).
+///
+/// A file handle stays valid while the file exists, even if it is renamed,
+/// and can be reopened by any process with
+/// [`open_by_handle_at`](fn.open_by_handle_at.html).  Its type and bytes can
+/// be stored or sent to another process, and turned back into a
+/// `FileHandle` with [`new`](#method.new).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FileHandle {
+    handle_type: c_int,
+    handle: Vec<u8>,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl FileHandle {
+    /// Rebuild a file handle from its type and bytes.
+    ///
+    /// Fails with `EINVAL` if `handle` is longer than any handle the kernel
+    /// creates.
+    pub fn new(handle_type: c_int, handle: &[u8]) -> Result<Self> {
+        if handle.len() > MAX_HANDLE_SZ {
+            return Err(crate::Error::invalid_argument());
+        }
+        Ok(FileHandle { handle_type, handle: handle.to_vec() })
+    }
+
+    /// The type of the handle, which depends on the file system.
+    pub fn handle_type(&self) -> c_int {
+        self.handle_type
+    }
+
+    /// The bytes of the handle, which only the file system understands.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.handle
+    }
+}
+
+/// Get a handle to the file `path`, relative to `dirfd`, and the id of the
+/// mount that it's on.
+///
+/// Unless `AT_SYMLINK_FOLLOW` is set in `flags`, a symbolic link isn't
+/// followed and the handle refers to the link itself.  With `AT_EMPTY_PATH`
+/// and an empty `path`, the handle refers to the file `dirfd`.
+///
+/// The mount id matches the one in `/proc/self/mountinfo`, which tells which
+/// mount point to open for [`open_by_handle_at`](fn.open_by_handle_at.html).
+/// Fails with `EOPNOTSUPP` if the file system doesn't support file handles.
+///
+/// See also [name_to_handle_at(2)](http://man7.org/linux/man-pages/man2/name_to_handle_at.2.html)
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn name_to_handle_at<P: ?Sized + NixPath>(
+    dirfd: Option<RawFd>,
+    path: &P,
+    flags: AtFlags,
+) -> Result<(FileHandle, c_int)> {
+    let mut fh = file_handle {
+        handle_bytes: MAX_HANDLE_SZ as c_uint,
+        handle_type: 0,
+        f_handle: [0; MAX_HANDLE_SZ],
+    };
+    let mut mount_id: c_int = 0;
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::syscall(libc::SYS_name_to_handle_at, at_rawfd(dirfd), cstr.as_ptr(),
+                      &mut fh as *mut file_handle, &mut mount_id as *mut c_int,
+                      flags.bits())
+    })?;
+    Errno::result(res)?;
+    let handle = FileHandle {
+        handle_type: fh.handle_type,
+        handle: fh.f_handle[..fh.handle_bytes as usize].to_vec(),
+    };
+    Ok((handle, mount_id))
+}
+
+/// Open the file referred to by `handle`, which is on the same file system
+/// as the file `mount_fd`, usually the mount point itself.
+///
+/// `flags` are the same as for [`open`](fn.open.html), except for
+/// `O_CREAT`.  `O_PATH` allows opening a symbolic link.  This requires the
+/// `CAP_DAC_READ_SEARCH` capability, and fails with `ESTALE` if the file was
+/// deleted.
+///
+/// See also [open_by_handle_at(2)](http://man7.org/linux/man-pages/man2/open_by_handle_at.2.html)
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn open_by_handle_at(mount_fd: RawFd, handle: &FileHandle, flags: OFlag) -> Result<RawFd> {
+    let mut fh = file_handle {
+        handle_bytes: handle.handle.len() as c_uint,
+        handle_type: handle.handle_type,
+        f_handle: [0; MAX_HANDLE_SZ],
+    };
+    fh.f_handle[..handle.handle.len()].copy_from_slice(&handle.handle);
+    let res = unsafe {
+        libc::syscall(libc::SYS_open_by_handle_at, mount_fd, &mut fh as *mut file_handle,
+                      flags.bits())
+    };
+    Errno::result(res).map(|fd| fd as RawFd)
+}
+
 fn wrap_readlink_result(mut v: Vec<u8>, len: ssize_t) -> Result<OsString> {
     unsafe { v.set_len(len as usize) }
     v.shrink_to_fit();
@@ -309,6 +646,9 @@ libc_bitflags!(
         F_SEAL_GROW;
         /// The file contents cannot be modified.
         F_SEAL_WRITE;
+        /// The file contents cannot be modified through new writable
+        /// mappings or writes, but existing writable mappings keep working.
+        F_SEAL_FUTURE_WRITE;
     }
 );
 
@@ -323,7 +663,10 @@ libc_bitflags!(
 #[cfg(not(target_os = "redox"))]
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub enum FcntlArg<'a> {
+    /// Duplicate the file descriptor, using the lowest free one that is
+    /// greater than or equal to the argument.
     F_DUPFD(RawFd),
+    /// Like `F_DUPFD`, but sets `FD_CLOEXEC` on the new file descriptor.
     F_DUPFD_CLOEXEC(RawFd),
     F_GETFD,
     F_SETFD(FdFlag), // FD_FLAGS
@@ -338,14 +681,22 @@ pub enum FcntlArg<'a> {
     F_OFD_SETLKW(&'a libc::flock),
     #[cfg(any(target_os = "linux", target_os = "android"))]
     F_OFD_GETLK(&'a mut libc::flock),
+    /// Add seals to a file created with `MFD_ALLOW_SEALING`.  Seals can't be
+    /// removed.
     #[cfg(any(target_os = "android", target_os = "linux"))]
     F_ADD_SEALS(SealFlag),
+    /// Get the seals of a file, which `SealFlag::from_bits_truncate` turns
+    /// back into a `SealFlag`.
     #[cfg(any(target_os = "android", target_os = "linux"))]
     F_GET_SEALS,
     #[cfg(any(target_os = "macos", target_os = "ios"))]
     F_FULLFSYNC,
+    /// Get the capacity of a pipe, in bytes.
     #[cfg(any(target_os = "linux", target_os = "android"))]
     F_GETPIPE_SZ,
+    /// Set the capacity of a pipe to at least the argument, in bytes, and
+    /// return the actual capacity.  Unprivileged processes are limited by
+    /// `/proc/sys/fs/pipe-max-size`.
     #[cfg(any(target_os = "linux", target_os = "android"))]
     F_SETPIPE_SZ(c_int),
     // TODO: Rest of flags
@@ -401,16 +752,149 @@ pub fn fcntl(fd: RawFd, arg: FcntlArg) -> Result<c_int> {
     Errno::result(res)
 }
 
+/// The type of a byte-range lock, see [`FlockRange`](struct.FlockRange.html).
+#[cfg(not(target_os = "redox"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LockType {
+    /// A shared lock, which other processes can also take
+    Read,
+    /// An exclusive lock
+    Write,
+    /// No lock.  Setting it removes any lock on the range, and `F_GETLK`
+    /// reports it if the lock could be taken.
+    Unlock,
+}
+
+/// A byte range to lock with the `F_SETLK`, `F_SETLKW` and `F_GETLK` commands
+/// of [`fcntl`](fn.fcntl.html), or their `F_OFD_*` counterparts on Linux.
+///
+/// The commands take the underlying `libc::flock`, which `as_ref` and
+/// `as_mut` return.
+///
+/// Locks set with `F_SETLK` belong to the process, and are released when it
+/// closes *any* descriptor of the file.  Open file description locks, set with
+/// `F_OFD_SETLK`, belong to the open file instead, so they work as expected
+/// between threads and are only released when the last descriptor of the
+/// open file is closed.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::fcntl::{fcntl, FcntlArg, FlockRange, LockType};
+/// # use std::os::unix::io::AsRawFd;
+/// # let file = tempfile::tempfile().unwrap();
+/// // Lock the first 100 bytes of the file
+/// let lock = FlockRange::new(LockType::Write, nix::unistd::Whence::SeekSet, 0, 100);
+/// fcntl(file.as_raw_fd(), FcntlArg::F_SETLKW(lock.as_ref())).unwrap();
+/// ```
+#[cfg(not(target_os = "redox"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct FlockRange(libc::flock);
+
+#[cfg(not(target_os = "redox"))]
+impl FlockRange {
+    /// Creates a lock of `len` bytes, starting `start` bytes from `whence`.
+    /// A `len` of 0 extends the range to the end of the file, however large
+    /// it grows.
+    pub fn new(lock_type: LockType, whence: Whence, start: libc::off_t, len: libc::off_t)
+        -> FlockRange
+    {
+        let mut flock: libc::flock = unsafe { mem::zeroed() };
+        flock.l_type = match lock_type {
+            LockType::Read => libc::F_RDLCK as _,
+            LockType::Write => libc::F_WRLCK as _,
+            LockType::Unlock => libc::F_UNLCK as _,
+        };
+        flock.l_whence = whence as _;
+        flock.l_start = start;
+        flock.l_len = len;
+        FlockRange(flock)
+    }
+
+    /// Creates a lock of the whole file.
+    pub fn whole_file(lock_type: LockType) -> FlockRange {
+        FlockRange::new(lock_type, Whence::SeekSet, 0, 0)
+    }
+
+    /// The type of the lock
+    pub fn lock_type(&self) -> LockType {
+        let l_type: c_int = self.0.l_type.into();
+        if l_type == libc::F_RDLCK as c_int {
+            LockType::Read
+        } else if l_type == libc::F_WRLCK as c_int {
+            LockType::Write
+        } else {
+            LockType::Unlock
+        }
+    }
+
+    /// Offset of the start of the range.  After `F_GETLK`, it is always
+    /// relative to the start of the file.
+    pub fn start(&self) -> libc::off_t {
+        self.0.l_start
+    }
+
+    /// Length of the range, where 0 means up to the end of the file
+    pub fn length(&self) -> libc::off_t {
+        self.0.l_len
+    }
+
+    /// The process holding a conflicting lock, as reported by `F_GETLK`.
+    ///
+    /// This is `None` for open file description locks, which don't belong to
+    /// a process.
+    pub fn pid(&self) -> Option<Pid> {
+        if self.0.l_pid > 0 {
+            Some(Pid::from_raw(self.0.l_pid))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl AsRef<libc::flock> for FlockRange {
+    fn as_ref(&self) -> &libc::flock {
+        &self.0
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl AsMut<libc::flock> for FlockRange {
+    fn as_mut(&mut self) -> &mut libc::flock {
+        &mut self.0
+    }
+}
+
+/// Operation to perform with [`flock`](fn.flock.html)
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum FlockArg {
+    /// Take a shared lock, waiting for exclusive locks to be released
     LockShared,
+    /// Take an exclusive lock, waiting for other locks to be released
     LockExclusive,
+    /// Release the lock
     Unlock,
+    /// Take a shared lock, or fail with `EWOULDBLOCK` if it is held
+    /// exclusively
     LockSharedNonblock,
+    /// Take an exclusive lock, or fail with `EWOULDBLOCK` if it is held
     LockExclusiveNonblock,
+    /// Release the lock
     UnlockNonblock,
 }
 
+/// Apply or remove an advisory lock on a whole file.
+///
+/// The lock belongs to the open file description of `fd`, so it is shared by
+/// duplicated descriptors and released when all of them are closed.  Calling
+/// `flock` again converts the lock between shared and exclusive.  On Linux the
+/// conversion is not atomic: the existing lock is released first, so a failed
+/// `LockExclusiveNonblock` upgrade leaves the file unlocked.  See
+/// [`FlockGuard`](struct.FlockGuard.html) to release it automatically.
+///
+/// [Further reading](https://www.freebsd.org/cgi/man.cgi?query=flock&sektion=2)
 #[cfg(not(target_os = "redox"))]
 pub fn flock(fd: RawFd, arg: FlockArg) -> Result<()> {
     use self::FlockArg::*;
@@ -429,6 +913,55 @@ pub fn flock(fd: RawFd, arg: FlockArg) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// An advisory lock taken with [`flock`](fn.flock.html), which is released
+/// when the guard is dropped.
+///
+/// The file descriptor must stay open for as long as the guard lives.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::fcntl::{open, FlockArg, FlockGuard, OFlag};
+/// # use nix::sys::stat::Mode;
+/// let fd = open("/var/run/myapp.lock", OFlag::O_CREAT | OFlag::O_RDWR,
+///               Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+/// let _guard = FlockGuard::lock(fd, FlockArg::LockExclusiveNonblock)
+///     .expect("another instance is running");
+/// // The lock is held until _guard goes out of scope
+/// ```
+#[cfg(not(target_os = "redox"))]
+#[derive(Debug)]
+pub struct FlockGuard {
+    fd: RawFd,
+}
+
+#[cfg(not(target_os = "redox"))]
+impl FlockGuard {
+    /// Lock `fd` as `arg` says, which must not be an unlock operation.
+    pub fn lock(fd: RawFd, arg: FlockArg) -> Result<FlockGuard> {
+        if arg == FlockArg::Unlock || arg == FlockArg::UnlockNonblock {
+            return Err(crate::Error::invalid_argument());
+        }
+        flock(fd, arg)?;
+        Ok(FlockGuard { fd })
+    }
+
+    /// Release the lock now, returning any error instead of ignoring it like
+    /// dropping the guard does.
+    pub fn unlock(self) -> Result<()> {
+        let fd = self.fd;
+        mem::forget(self);
+        flock(fd, FlockArg::Unlock)
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl Drop for FlockGuard {
+    fn drop(&mut self) {
+        let _ = flock(self.fd, FlockArg::Unlock);
+    }
+}
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 libc_bitflags! {
     /// Additional flags to `splice` and friends.
@@ -465,7 +998,33 @@ libc_bitflags! {
 /// to the new position.
 ///
 /// On successful completion the number of bytes actually copied will be
-/// returned.
+/// returned.  Fewer bytes than `len` may be copied, and 0 means the end of
+/// `fd_in` was reached, so callers should loop.  File systems that support
+/// it may share the copied extents (reflink) instead of duplicating them.
+///
+/// Kernels older than 4.5 fail with `ENOSYS`, and those older than 5.3 fail
+/// with `EXDEV` if the files are on different file systems.  Callers should
+/// fall back to copying through user space in both cases.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::fcntl::copy_file_range;
+/// # use std::os::unix::io::AsRawFd;
+/// # let src = tempfile::tempfile().unwrap();
+/// # let dst = tempfile::tempfile().unwrap();
+/// let mut remaining = src.metadata().unwrap().len() as usize;
+/// while remaining > 0 {
+///     let n = copy_file_range(src.as_raw_fd(), None, dst.as_raw_fd(), None,
+///                             remaining).unwrap();
+///     if n == 0 {
+///         break;
+///     }
+///     remaining -= n;
+/// }
+/// ```
+///
+/// See also [copy_file_range(2)](http://man7.org/linux/man-pages/man2/copy_file_range.2.html)
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub fn copy_file_range(
     fd_in: RawFd,
@@ -495,6 +1054,20 @@ pub fn copy_file_range(
     Errno::result(ret).map(|r| r as usize)
 }
 
+/// Move data between a file descriptor and a pipe
+///
+/// The `splice` system call moves up to `len` bytes from `fd_in` to `fd_out`
+/// without copying them through user space. At least one of the two file
+/// descriptors must refer to a pipe.
+///
+/// If `off_in` or `off_out` is given, the corresponding file descriptor must
+/// not refer to a pipe; data is read from (or written to) that offset, which
+/// will be updated to reflect the new position, and the file offset of the
+/// descriptor itself is left unchanged.
+///
+/// On success the number of bytes moved is returned.
+///
+/// See also [splice(2)](http://man7.org/linux/man-pages/man2/splice.2.html)
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn splice(
     fd_in: RawFd,
@@ -515,12 +1088,30 @@ pub fn splice(
     Errno::result(ret).map(|r| r as usize)
 }
 
+/// Duplicate up to `len` bytes from one pipe to another
+///
+/// Unlike [`splice`](fn.splice.html), the data is not consumed from `fd_in`
+/// and can still be read from it afterwards. Both file descriptors must refer
+/// to pipes.
+///
+/// On success the number of bytes duplicated is returned.
+///
+/// See also [tee(2)](http://man7.org/linux/man-pages/man2/tee.2.html)
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn tee(fd_in: RawFd, fd_out: RawFd, len: usize, flags: SpliceFFlags) -> Result<usize> {
     let ret = unsafe { libc::tee(fd_in, fd_out, len, flags.bits()) };
     Errno::result(ret).map(|r| r as usize)
 }
 
+/// Splice user pages into a pipe
+///
+/// The buffers described by `iov` are mapped into the pipe referred to by
+/// `fd`, in order. With `SPLICE_F_GIFT` the pages are gifted to the kernel,
+/// and the caller must not modify them afterwards.
+///
+/// On success the number of bytes transferred into the pipe is returned.
+///
+/// See also [vmsplice(2)](http://man7.org/linux/man-pages/man2/vmsplice.2.html)
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub fn vmsplice(fd: RawFd, iov: &[IoVec<&[u8]>], flags: SpliceFFlags) -> Result<usize> {
     let ret = unsafe {
@@ -534,7 +1125,7 @@ pub fn vmsplice(fd: RawFd, iov: &[IoVec<&[u8]>], flags: SpliceFFlags) -> Result<
     Errno::result(ret).map(|r| r as usize)
 }
 
-#[cfg(any(target_os = "linux"))]
+#[cfg(any(target_os = "android", target_os = "linux"))]
 libc_bitflags!(
     /// Mode argument flags for fallocate determining operation performed on a given range.
     pub struct FallocateFlags: c_int {
@@ -558,9 +1149,9 @@ libc_bitflags!(
         ///
         /// Does not overwrite existing data. Hole starts at offset and continues for len bytes.
         FALLOC_FL_INSERT_RANGE;
-        /// Shared file data extants are made private to the file.
+        /// Shared file data extents are made private to the file.
         ///
-        /// Gaurantees that a subsequent write will not fail due to lack of space.
+        /// Guarantees that a subsequent write will not fail due to lack of space.
         FALLOC_FL_UNSHARE_RANGE;
     }
 );
@@ -568,8 +1159,25 @@ libc_bitflags!(
 /// Manipulates file space.
 ///
 /// Allows the caller to directly manipulate the allocated disk space for the
-/// file referred to by fd.
-#[cfg(any(target_os = "linux"))]
+/// file referred to by fd.  With an empty `mode`, the range is allocated and
+/// the file grows if needed, like with [`posix_fallocate`](fn.posix_fallocate.html).
+/// The other modes are only supported by some file systems, and fail with
+/// `EOPNOTSUPP` elsewhere.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::fcntl::{fallocate, FallocateFlags};
+/// # use std::os::unix::io::AsRawFd;
+/// let file = tempfile::tempfile().unwrap();
+/// // Deallocate the first 4096 bytes, which then read as zeroes
+/// fallocate(file.as_raw_fd(),
+///           FallocateFlags::FALLOC_FL_PUNCH_HOLE | FallocateFlags::FALLOC_FL_KEEP_SIZE,
+///           0, 4096).unwrap();
+/// ```
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/fallocate.2.html)
+#[cfg(any(target_os = "android", target_os = "linux"))]
 pub fn fallocate(
     fd: RawFd,
     mode: FallocateFlags,
@@ -580,6 +1188,18 @@ pub fn fallocate(
     Errno::result(res).map(drop)
 }
 
+/// Read `count` bytes of the file at `offset` into the page cache, so that
+/// later reads don't block on disk I/O.
+///
+/// This blocks until the data has been read.
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/readahead.2.html)
+#[cfg(target_os = "linux")]
+pub fn readahead(fd: RawFd, offset: libc::off64_t, count: usize) -> Result<()> {
+    let res = unsafe { libc::readahead(fd, offset, count) };
+    Errno::result(res).map(drop)
+}
+
 #[cfg(any(
     target_os = "linux",
     target_os = "android",
@@ -593,31 +1213,54 @@ mod posix_fadvise {
     use crate::errno::Errno;
     use libc;
     use std::os::unix::io::RawFd;
-    use crate::Result;
+    use crate::{Error, Result};
 
     libc_enum! {
+        /// Expected access pattern, for [`posix_fadvise`](fn.posix_fadvise.html)
         #[repr(i32)]
         pub enum PosixFadviseAdvice {
+            /// No particular access pattern (the default)
             POSIX_FADV_NORMAL,
+            /// The data will be accessed sequentially, so read ahead more
             POSIX_FADV_SEQUENTIAL,
+            /// The data will be accessed randomly, so don't read ahead
             POSIX_FADV_RANDOM,
+            /// The data will be accessed only once
             POSIX_FADV_NOREUSE,
+            /// The data will be accessed soon, so start reading it now
             POSIX_FADV_WILLNEED,
+            /// The data won't be accessed soon, so its cached pages can be
+            /// dropped
             POSIX_FADV_DONTNEED,
         }
     }
 
+    /// Announce how the range of `len` bytes at `offset` will be accessed, so
+    /// that the kernel can tune the page cache.  A `len` of 0 extends to the
+    /// end of the file.
+    ///
+    /// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/posix_fadvise.html)
     pub fn posix_fadvise(
         fd: RawFd,
         offset: libc::off_t,
         len: libc::off_t,
         advice: PosixFadviseAdvice,
-    ) -> Result<libc::c_int> {
+    ) -> Result<()> {
         let res = unsafe { libc::posix_fadvise(fd, offset, len, advice as libc::c_int) };
-        Errno::result(res)
+        match res {
+            0 => Ok(()),
+            errno => Err(Error::Sys(Errno::from_i32(errno))),
+        }
     }
 }
 
+/// Ensures that disk space is allocated for the given range of the file.
+///
+/// The file grows if `offset + len` is beyond its end.  Unlike
+/// [`fallocate`](fn.fallocate.html), this is portable, but the C library may
+/// emulate it by writing zeroes if the file system lacks support.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/posix_fallocate.html)
 #[cfg(any(
     target_os = "linux",
     target_os = "android",
@@ -144,4 +144,40 @@ mod tests {
     fn test_getifaddrs() {
         let _ = getifaddrs();
     }
+
+    // The loopback interface has an IPv4 address on every supported platform
+    #[test]
+    fn test_getifaddrs_loopback() {
+        use crate::sys::socket::{InetAddr, IpAddr};
+
+        let lo = getifaddrs().unwrap()
+            .find(|ifaddr| {
+                ifaddr.flags.contains(InterfaceFlags::IFF_LOOPBACK) &&
+                    ifaddr.address == Some(SockAddr::new_inet(
+                        InetAddr::new(IpAddr::new_v4(127, 0, 0, 1), 0)))
+            })
+            .expect("no IPv4 loopback address");
+        assert!(lo.flags.contains(InterfaceFlags::IFF_UP));
+        assert_eq!(lo.netmask, Some(SockAddr::new_inet(
+            InetAddr::new(IpAddr::new_v4(255, 0, 0, 0), 0))));
+        assert!(lo.destination.is_none());
+    }
+
+    // On Linux, each interface also has an AF_PACKET entry
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_getifaddrs_packet() {
+        let link = getifaddrs().unwrap()
+            .filter(|ifaddr| ifaddr.flags.contains(InterfaceFlags::IFF_LOOPBACK))
+            .filter_map(|ifaddr| {
+                if let Some(SockAddr::Link(link)) = ifaddr.address {
+                    Some(link)
+                } else {
+                    None
+                }
+            })
+            .next()
+            .expect("no AF_PACKET entry for the loopback interface");
+        assert_eq!(link.addr(), [0; 6]);
+    }
 }
@@ -60,7 +60,16 @@ pub mod poll;
 #[cfg(not(target_os = "redox"))]
 pub mod pty;
 pub mod sched;
+#[deny(missing_docs)]
+#[cfg(any(target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+pub mod spawn;
 pub mod sys;
+#[deny(missing_docs)]
+pub mod time;
 // This can be implemented for other platforms as soon as libc
 // provides bindings for them.
 #[cfg(all(target_os = "linux",
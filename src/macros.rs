@@ -209,10 +209,13 @@ macro_rules! libc_enum {
 #[cfg(not(target_os = "redox"))]
 macro_rules! offset_of {
     ($ty:ty, $field:ident) => {{
-        // Safe because we don't actually read from the dereferenced pointer
+        // Only used with libc structs, for which all-zeroes is a valid value.
+        // Dereferencing a null pointer instead would be undefined behavior.
+        let u = ::std::mem::MaybeUninit::<$ty>::zeroed();
         #[allow(unused_unsafe)] // for when the macro is used in an unsafe block
         unsafe {
-            &(*(ptr::null() as *const $ty)).$field as *const _ as usize
+            let base = u.as_ptr();
+            &(*base).$field as *const _ as usize - base as usize
         }
     }}
 }
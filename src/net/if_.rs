@@ -5,6 +5,17 @@
 
 use libc::c_uint;
 use crate::{Result, Error, NixPath};
+use std::ffi::CString;
+#[cfg(not(target_os = "android"))]
+use std::ffi::CStr;
+#[cfg(target_os = "linux")]
+use crate::errno::Errno;
+#[cfg(target_os = "linux")]
+use crate::sys::socket::{InetAddr, SockAddr};
+#[cfg(target_os = "linux")]
+use std::{mem, ptr};
+#[cfg(target_os = "linux")]
+use std::os::unix::io::AsRawFd;
 
 /// Resolve an interface into a interface number.
 pub fn if_nametoindex<P: ?Sized + NixPath>(name: &P) -> Result<c_uint> {
@@ -17,6 +28,76 @@ pub fn if_nametoindex<P: ?Sized + NixPath>(name: &P) -> Result<c_uint> {
     }
 }
 
+/// Resolve an interface number into its name.
+pub fn if_indextoname(index: c_uint) -> Result<CString> {
+    let mut buf = [0u8; libc::IF_NAMESIZE];
+    let ret = unsafe {
+        libc::if_indextoname(index, buf.as_mut_ptr() as *mut libc::c_char)
+    };
+
+    if ret.is_null() {
+        Err(Error::last())
+    } else {
+        let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        Ok(CString::new(&buf[..len]).unwrap())
+    }
+}
+
+/// Iterator over the interfaces of the system, as returned by
+/// [`if_nameindex`](fn.if_nameindex.html).
+///
+/// The underlying list is freed when the iterator is dropped.
+#[cfg(not(target_os = "android"))]
+#[derive(Debug)]
+pub struct InterfaceNameIndexIterator {
+    base: *mut libc::if_nameindex,
+    next: *mut libc::if_nameindex,
+}
+
+#[cfg(not(target_os = "android"))]
+impl Iterator for InterfaceNameIndexIterator {
+    type Item = (c_uint, CString);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = unsafe { &*self.next };
+        // The list ends with an entry with a zero index and a null name
+        if entry.if_index == 0 || entry.if_name.is_null() {
+            return None;
+        }
+        self.next = unsafe { self.next.add(1) };
+        let name = unsafe { CStr::from_ptr(entry.if_name) };
+        Some((entry.if_index, name.to_owned()))
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+impl Drop for InterfaceNameIndexIterator {
+    fn drop(&mut self) {
+        unsafe { libc::if_freenameindex(self.base) };
+    }
+}
+
+/// List the names and numbers of the network interfaces of the system.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::net::if_::if_nameindex;
+/// for (index, name) in if_nameindex().unwrap() {
+///     println!("interface {}: {:?}", index, name);
+/// }
+/// ```
+#[cfg(not(target_os = "android"))]
+pub fn if_nameindex() -> Result<InterfaceNameIndexIterator> {
+    let base = unsafe { libc::if_nameindex() };
+
+    if base.is_null() {
+        Err(Error::last())
+    } else {
+        Ok(InterfaceNameIndexIterator { base, next: base })
+    }
+}
+
 libc_bitflags!(
     /// Standard interface flags, used by `getifaddrs`
     pub struct InterfaceFlags: libc::c_int {
@@ -265,3 +346,170 @@ libc_bitflags!(
         IFF_IPMP;
     }
 );
+
+#[cfg(target_os = "linux")]
+mod if_ioctl {
+    crate::ioctl_readwrite_bad!(siocgifflags, libc::SIOCGIFFLAGS, libc::ifreq);
+    crate::ioctl_write_ptr_bad!(siocsifflags, libc::SIOCSIFFLAGS, libc::ifreq);
+    crate::ioctl_readwrite_bad!(siocgifmtu, libc::SIOCGIFMTU, libc::ifreq);
+    crate::ioctl_write_ptr_bad!(siocsifmtu, libc::SIOCSIFMTU, libc::ifreq);
+    crate::ioctl_readwrite_bad!(siocgifhwaddr, libc::SIOCGIFHWADDR, libc::ifreq);
+    crate::ioctl_write_ptr_bad!(siocsifhwaddr, libc::SIOCSIFHWADDR, libc::ifreq);
+    crate::ioctl_readwrite_bad!(siocgifaddr, libc::SIOCGIFADDR, libc::ifreq);
+    crate::ioctl_write_ptr_bad!(siocsifaddr, libc::SIOCSIFADDR, libc::ifreq);
+}
+
+/// A network interface, to query and change its settings by name
+///
+/// This wraps the `SIOCGIF*` and `SIOCSIF*` ioctls described in
+/// [`netdevice(7)`](http://man7.org/linux/man-pages/man7/netdevice.7.html),
+/// which are issued on a socket owned by the `Interface`.  Changing settings
+/// requires the `CAP_NET_ADMIN` capability.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::net::if_::{Interface, InterfaceFlags};
+/// let lo = Interface::new("lo").unwrap();
+/// assert!(lo.flags().unwrap().contains(InterfaceFlags::IFF_LOOPBACK));
+/// assert!(lo.mtu().unwrap() > 0);
+/// ```
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct Interface {
+    name: [libc::c_char; libc::IFNAMSIZ],
+    sock: crate::sys::socket::Socket,
+}
+
+#[cfg(target_os = "linux")]
+impl Interface {
+    /// Refer to the interface called `name`, such as "eth0"
+    ///
+    /// Fails with `EINVAL` if the name is too long.  The interface does not
+    /// need to exist until it is queried.
+    pub fn new<P: ?Sized + NixPath>(name: &P) -> Result<Interface> {
+        use crate::sys::socket::{AddressFamily, SockFlag, SockType, Socket};
+
+        let mut ifname = [0; libc::IFNAMSIZ];
+        name.with_nix_path(|name| {
+            let bytes = name.to_bytes();
+            if bytes.len() >= libc::IFNAMSIZ {
+                return Err(Error::invalid_argument());
+            }
+            for (dst, &src) in ifname.iter_mut().zip(bytes) {
+                *dst = src as libc::c_char;
+            }
+            Ok(())
+        })??;
+        let sock = Socket::new(AddressFamily::Inet, SockType::Datagram,
+                               SockFlag::SOCK_CLOEXEC, None)?;
+
+        Ok(Interface { name: ifname, sock })
+    }
+
+    /// The name of the interface
+    pub fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.name.as_ptr()) }
+    }
+
+    /// The index of the interface.  See [`if_nametoindex`](fn.if_nametoindex.html).
+    pub fn index(&self) -> Result<c_uint> {
+        if_nametoindex(self.name())
+    }
+
+    fn ifreq(&self) -> libc::ifreq {
+        let mut req: libc::ifreq = unsafe { mem::zeroed() };
+        req.ifr_name = self.name;
+        req
+    }
+
+    /// Get the interface flags (`SIOCGIFFLAGS`)
+    pub fn flags(&self) -> Result<InterfaceFlags> {
+        let mut req = self.ifreq();
+        unsafe { if_ioctl::siocgifflags(self.sock.as_raw_fd(), &mut req)? };
+        // ifr_flags is a short; don't sign-extend IFF_DYNAMIC
+        let flags = unsafe { req.ifr_ifru.ifru_flags } as u16;
+        Ok(InterfaceFlags::from_bits_truncate(libc::c_int::from(flags)))
+    }
+
+    /// Set the interface flags (`SIOCSIFFLAGS`)
+    ///
+    /// All the flags are replaced, so this is usually combined with
+    /// [`flags`](#method.flags), like to bring an interface up:
+    ///
+    /// ```no_run
+    /// # use nix::net::if_::{Interface, InterfaceFlags};
+    /// let eth0 = Interface::new("eth0").unwrap();
+    /// eth0.set_flags(eth0.flags().unwrap() | InterfaceFlags::IFF_UP).unwrap();
+    /// ```
+    pub fn set_flags(&self, flags: InterfaceFlags) -> Result<()> {
+        let mut req = self.ifreq();
+        req.ifr_ifru.ifru_flags = flags.bits() as libc::c_short;
+        unsafe { if_ioctl::siocsifflags(self.sock.as_raw_fd(), &req) }.map(drop)
+    }
+
+    /// Get the MTU of the interface (`SIOCGIFMTU`)
+    pub fn mtu(&self) -> Result<u32> {
+        let mut req = self.ifreq();
+        unsafe { if_ioctl::siocgifmtu(self.sock.as_raw_fd(), &mut req)? };
+        Ok(unsafe { req.ifr_ifru.ifru_mtu } as u32)
+    }
+
+    /// Set the MTU of the interface (`SIOCSIFMTU`)
+    pub fn set_mtu(&self, mtu: u32) -> Result<()> {
+        let mut req = self.ifreq();
+        req.ifr_ifru.ifru_mtu = mtu as libc::c_int;
+        unsafe { if_ioctl::siocsifmtu(self.sock.as_raw_fd(), &req) }.map(drop)
+    }
+
+    /// Get the hardware (MAC) address of the interface (`SIOCGIFHWADDR`)
+    pub fn hw_addr(&self) -> Result<[u8; 6]> {
+        let mut req = self.ifreq();
+        unsafe { if_ioctl::siocgifhwaddr(self.sock.as_raw_fd(), &mut req)? };
+        let data = unsafe { req.ifr_ifru.ifru_hwaddr.sa_data };
+        let mut addr = [0; 6];
+        for (dst, &src) in addr.iter_mut().zip(data.iter()) {
+            *dst = src as u8;
+        }
+        Ok(addr)
+    }
+
+    /// Set the hardware (MAC) address of an Ethernet interface
+    /// (`SIOCSIFHWADDR`)
+    pub fn set_hw_addr(&self, addr: [u8; 6]) -> Result<()> {
+        let mut req = self.ifreq();
+        unsafe {
+            req.ifr_ifru.ifru_hwaddr.sa_family = libc::ARPHRD_ETHER;
+            for (dst, &src) in req.ifr_ifru.ifru_hwaddr.sa_data.iter_mut().zip(addr.iter()) {
+                *dst = src as libc::c_char;
+            }
+            if_ioctl::siocsifhwaddr(self.sock.as_raw_fd(), &req)
+        }.map(drop)
+    }
+
+    /// Get the IPv4 address of the interface (`SIOCGIFADDR`)
+    pub fn addr(&self) -> Result<SockAddr> {
+        let mut req = self.ifreq();
+        unsafe {
+            if_ioctl::siocgifaddr(self.sock.as_raw_fd(), &mut req)?;
+            SockAddr::from_libc_sockaddr(&req.ifr_ifru.ifru_addr)
+        }.ok_or(Error::Sys(Errno::EAFNOSUPPORT))
+    }
+
+    /// Set the IPv4 address of the interface (`SIOCSIFADDR`)
+    ///
+    /// Fails with `EINVAL` if `addr` is not an IPv4 address.
+    pub fn set_addr(&self, addr: &SockAddr) -> Result<()> {
+        let inet = match *addr {
+            SockAddr::Inet(InetAddr::V4(sin)) => sin,
+            _ => return Err(Error::invalid_argument()),
+        };
+        let mut req = self.ifreq();
+        unsafe {
+            ptr::write(&mut req.ifr_ifru.ifru_addr as *mut libc::sockaddr
+                           as *mut libc::sockaddr_in,
+                       inet);
+            if_ioctl::siocsifaddr(self.sock.as_raw_fd(), &req)
+        }.map(drop)
+    }
+}
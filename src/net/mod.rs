@@ -2,3 +2,5 @@
 // To avoid clashing with the keyword "if", we use "if_" as the module name.
 // The original header is called "net/if.h".
 pub mod if_;
+#[cfg(target_os = "linux")]
+pub mod tun;
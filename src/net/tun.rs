@@ -0,0 +1,85 @@
+//! TUN/TAP virtual network devices
+//!
+//! See [the kernel documentation](https://www.kernel.org/doc/Documentation/networking/tuntap.txt)
+//! for how to use them.
+
+use crate::{Error, Result};
+use crate::fcntl::{open, OFlag};
+use crate::sys::stat::Mode;
+use crate::unistd::close;
+use libc::{self, c_char, c_int};
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::unix::io::RawFd;
+
+libc_bitflags! {
+    /// Flags for [`tun_alloc`](fn.tun_alloc.html)
+    pub struct TunFlags: c_int {
+        /// Create a TUN device, which carries IP packets.
+        IFF_TUN;
+        /// Create a TAP device, which carries Ethernet frames.
+        IFF_TAP;
+        /// Don't prepend the packet information header to each packet.
+        IFF_NO_PI;
+        /// Prepend a `virtio_net_hdr` to each packet.
+        IFF_VNET_HDR;
+        /// Allow the device to be opened several times, with one queue per
+        /// file descriptor.
+        IFF_MULTI_QUEUE;
+    }
+}
+
+mod tun_ioctl {
+    // TUNSETIFF is defined as _IOW('T', 202, int), but takes a struct ifreq,
+    // and writes the name of the interface back into it
+    crate::ioctl_readwrite_bad!(tunsetiff,
+                                crate::request_code_write!(b'T', 202,
+                                    ::std::mem::size_of::<libc::c_int>()),
+                                libc::ifreq);
+}
+
+/// Create a TUN or TAP device, or attach to an existing one.
+///
+/// Opens `/dev/net/tun` and issues `TUNSETIFF` with the given `flags`, which
+/// must include exactly one of `IFF_TUN` and `IFF_TAP`.  If `name` is `None`,
+/// or contains a `%d` format, the kernel picks the name of the device.
+/// Returns the file descriptor of the device, which is closed on exec,
+/// together with the name of the interface.  The device is destroyed when
+/// the last descriptor is closed, unless it was made persistent.
+///
+/// Creating a device requires the `CAP_NET_ADMIN` capability.  Fails with
+/// `EINVAL` if `name` is too long or contains a nul byte.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::net::tun::{tun_alloc, TunFlags};
+/// # use nix::unistd::read;
+/// let (fd, name) = tun_alloc(Some("tun%d"), TunFlags::IFF_TUN | TunFlags::IFF_NO_PI).unwrap();
+/// println!("created {:?}", name);
+/// let mut packet = [0u8; 1500];
+/// read(fd, &mut packet).unwrap();
+/// ```
+pub fn tun_alloc(name: Option<&str>, flags: TunFlags) -> Result<(RawFd, CString)> {
+    let mut req: libc::ifreq = unsafe { mem::zeroed() };
+    if let Some(name) = name {
+        let bytes = name.as_bytes();
+        if bytes.len() >= libc::IFNAMSIZ || bytes.contains(&0) {
+            return Err(Error::invalid_argument());
+        }
+        for (dst, &src) in req.ifr_name.iter_mut().zip(bytes) {
+            *dst = src as c_char;
+        }
+    }
+    req.ifr_ifru.ifru_flags = flags.bits() as libc::c_short;
+
+    let fd = open("/dev/net/tun", OFlag::O_RDWR | OFlag::O_CLOEXEC, Mode::empty())?;
+    if let Err(e) = unsafe { tun_ioctl::tunsetiff(fd, &mut req) } {
+        let _ = close(fd);
+        return Err(e);
+    }
+
+    // The kernel fills in the name that it picked
+    let name = unsafe { CStr::from_ptr(req.ifr_name.as_ptr()) };
+    Ok((fd, name.to_owned()))
+}
@@ -3,7 +3,7 @@
 use crate::sys::time::TimeSpec;
 #[cfg(any(target_os = "android", target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
 use crate::sys::signal::SigSet;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
 
 use crate::Result;
 use crate::errno::Errno;
@@ -39,6 +39,35 @@ impl PollFd {
     pub fn revents(self) -> Option<PollFlags> {
         PollFlags::from_bits(self.pollfd.revents)
     }
+
+    /// Returns the events of interest.
+    pub fn events(self) -> PollFlags {
+        PollFlags::from_bits_truncate(self.pollfd.events)
+    }
+
+    /// Changes the events of interest, for the next call to `poll` or
+    /// `ppoll`.
+    ///
+    /// An empty set of events still reports `POLLERR`, `POLLHUP` and
+    /// `POLLNVAL`.  To ignore the file descriptor entirely, remove it from
+    /// the array instead.
+    pub fn set_events(&mut self, events: PollFlags) {
+        self.pollfd.events = events.bits();
+    }
+
+    /// Clears the events returned by the last call to `poll` or `ppoll`.
+    ///
+    /// `poll` overwrites them anyway; this is only useful to avoid acting on
+    /// stale events without polling again.
+    pub fn clear_revents(&mut self) {
+        self.pollfd.revents = 0;
+    }
+}
+
+impl AsRawFd for PollFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.pollfd.fd
+    }
 }
 
 libc_bitflags! {
@@ -74,6 +103,10 @@ libc_bitflags! {
         /// Priority data may be written.
         #[cfg(not(target_os = "redox"))]
         POLLWRBAND;
+        /// The peer of a stream socket closed its end of the connection, or
+        /// shut down writing.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        POLLRDHUP;
         /// Error condition (only returned in
         /// [`PollFd::revents`](struct.PollFd.html#method.revents);
         /// ignored in [`PollFd::new`](struct.PollFd.html#method.new)).
@@ -130,17 +163,23 @@ pub fn poll(fds: &mut [PollFd], timeout: libc::c_int) -> Result<libc::c_int> {
 /// ([`poll(2)`](http://man7.org/linux/man-pages/man2/poll.2.html))
 ///
 /// `ppoll` behaves like `poll`, but let you specify what signals may interrupt it
-/// with the `sigmask` argument. If you want `ppoll` to block indefinitely,
-/// specify `None` as `timeout` (it is like `timeout = -1` for `poll`).
+/// with the `sigmask` argument, which replaces the signal mask of the thread
+/// while waiting.  If `sigmask` is `None`, the signal mask is left alone.
+/// If you want `ppoll` to block indefinitely, specify `None` as `timeout`
+/// (it is like `timeout = -1` for `poll`).
 ///
 #[cfg(any(target_os = "android", target_os = "dragonfly", target_os = "freebsd", target_os = "linux"))]
-pub fn ppoll(fds: &mut [PollFd], timeout: Option<TimeSpec>, sigmask: SigSet) -> Result<libc::c_int> {
+pub fn ppoll<S>(fds: &mut [PollFd], timeout: Option<TimeSpec>, sigmask: S) -> Result<libc::c_int>
+    where S: Into<Option<SigSet>>
+{
     let timeout = timeout.as_ref().map_or(core::ptr::null(), |r| r.as_ref());
+    let sigmask = sigmask.into();
+    let sigmask = sigmask.as_ref().map_or(core::ptr::null(), |s| s.as_ref());
     let res = unsafe {
         libc::ppoll(fds.as_mut_ptr() as *mut libc::pollfd,
                     fds.len() as libc::nfds_t,
                     timeout,
-                    sigmask.as_ref())
+                    sigmask)
     };
     Errno::result(res)
 }
@@ -0,0 +1,314 @@
+//! Spawn new processes with `posix_spawn`
+//!
+//! `posix_spawn` creates a child process and executes a program in it, like
+//! `fork` followed by `execve`.  The changes to make in the child before
+//! executing the program, like redirecting its standard streams or putting it
+//! in a new process group, are described in advance with
+//! [`PosixSpawnFileActions`](struct.PosixSpawnFileActions.html) and
+//! [`PosixSpawnAttr`](struct.PosixSpawnAttr.html).  This avoids the
+//! restrictions on what a forked child of a multi-threaded process may do,
+//! like allocating memory, and the cost of copying the address space of a
+//! large process.
+//!
+//! # Examples
+//!
+//! Run `echo` with its standard output redirected to a pipe:
+//!
+//! ```
+//! # use nix::spawn::{posix_spawnp, PosixSpawnAttr, PosixSpawnFileActions};
+//! # use nix::sys::wait::{waitpid, WaitStatus};
+//! # use nix::unistd::{close, pipe, read};
+//! # use std::ffi::{CStr, CString};
+//! let (r, w) = pipe().unwrap();
+//! let mut actions = PosixSpawnFileActions::new().unwrap();
+//! actions.add_dup2(w, 1).unwrap();
+//! actions.add_close(r).unwrap();
+//! actions.add_close(w).unwrap();
+//! let attr = PosixSpawnAttr::new().unwrap();
+//!
+//! let echo = CString::new("echo").unwrap();
+//! let hello = CString::new("hello").unwrap();
+//! let env: [&CStr; 0] = [];
+//! let pid = posix_spawnp(&echo, &actions, &attr, &[&echo, &hello], &env).unwrap();
+//! close(w).unwrap();
+//!
+//! let mut buf = [0u8; 6];
+//! assert_eq!(read(r, &mut buf).unwrap(), 6);
+//! assert_eq!(&buf, b"hello\n");
+//! assert_eq!(waitpid(pid, None).unwrap(), WaitStatus::Exited(pid, 0));
+//! # close(r).unwrap();
+//! ```
+use crate::errno::Errno;
+use crate::fcntl::OFlag;
+use crate::sys::signal::SigSet;
+use crate::sys::stat::Mode;
+use crate::unistd::Pid;
+use crate::{Error, NixPath, Result};
+use libc::{self, c_char, c_int, c_short};
+use std::ffi::CStr;
+use std::os::unix::io::RawFd;
+use std::{fmt, mem, ptr};
+
+libc_bitflags! {
+    /// Which attributes of a [`PosixSpawnAttr`](struct.PosixSpawnAttr.html)
+    /// are applied to the child process.
+    pub struct PosixSpawnFlags: c_int {
+        /// Set the effective user and group ids of the child to its real ids.
+        POSIX_SPAWN_RESETIDS;
+        /// Put the child in the process group set with
+        /// [`set_pgroup`](struct.PosixSpawnAttr.html#method.set_pgroup).
+        POSIX_SPAWN_SETPGROUP;
+        /// Reset the signals set with
+        /// [`set_sigdefault`](struct.PosixSpawnAttr.html#method.set_sigdefault)
+        /// to their default action.
+        POSIX_SPAWN_SETSIGDEF;
+        /// Set the signal mask of the child to the one set with
+        /// [`set_sigmask`](struct.PosixSpawnAttr.html#method.set_sigmask).
+        POSIX_SPAWN_SETSIGMASK;
+        /// Set the scheduling parameters of the child to the ones set with
+        /// [`set_sched_priority`](struct.PosixSpawnAttr.html#method.set_sched_priority).
+        #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "netbsd"))]
+        POSIX_SPAWN_SETSCHEDPARAM;
+        /// Set the scheduling policy of the child to the one set with
+        /// [`set_sched_policy`](struct.PosixSpawnAttr.html#method.set_sched_policy).
+        #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "netbsd"))]
+        POSIX_SPAWN_SETSCHEDULER;
+        /// Make the child a session leader, like with `setsid`.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        POSIX_SPAWN_SETSID;
+        /// Execute the program in the calling process instead of a child,
+        /// like `execve` with the attributes applied.
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        POSIX_SPAWN_SETEXEC;
+    }
+}
+
+#[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "netbsd"))]
+libc_enum! {
+    /// Scheduling policies for
+    /// [`set_sched_policy`](struct.PosixSpawnAttr.html#method.set_sched_policy)
+    #[repr(i32)]
+    pub enum SchedPolicy {
+        /// The default, time-sharing policy
+        SCHED_OTHER,
+        /// A real-time, first-in first-out policy
+        SCHED_FIFO,
+        /// A real-time, round-robin policy
+        SCHED_RR,
+        /// Like `SCHED_OTHER`, for CPU-intensive processes
+        #[cfg(target_os = "linux")]
+        SCHED_BATCH,
+        /// For very low priority background processes
+        #[cfg(target_os = "linux")]
+        SCHED_IDLE,
+    }
+}
+
+/// The `posix_spawn*` functions return an error number instead of setting
+/// `errno`.
+fn spawn_result(res: c_int) -> Result<()> {
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(Error::Sys(Errno::from_i32(res)))
+    }
+}
+
+/// Attributes of a process created by [`posix_spawn`](fn.posix_spawn.html)
+///
+/// An attribute only applies to the child if its flag is set with
+/// [`set_flags`](#method.set_flags).
+pub struct PosixSpawnAttr {
+    attr: libc::posix_spawnattr_t,
+}
+
+impl PosixSpawnAttr {
+    /// Create attributes with no flags set, so that the child keeps the
+    /// attributes of the calling process.
+    pub fn new() -> Result<PosixSpawnAttr> {
+        let mut attr = mem::MaybeUninit::uninit();
+        spawn_result(unsafe { libc::posix_spawnattr_init(attr.as_mut_ptr()) })?;
+        Ok(PosixSpawnAttr { attr: unsafe { attr.assume_init() } })
+    }
+
+    /// Set which attributes apply to the child.
+    pub fn set_flags(&mut self, flags: PosixSpawnFlags) -> Result<()> {
+        spawn_result(unsafe {
+            libc::posix_spawnattr_setflags(&mut self.attr, flags.bits() as c_short)
+        })
+    }
+
+    /// Get which attributes apply to the child.
+    pub fn flags(&self) -> Result<PosixSpawnFlags> {
+        let mut flags: c_short = 0;
+        spawn_result(unsafe { libc::posix_spawnattr_getflags(&self.attr, &mut flags) })?;
+        Ok(PosixSpawnFlags::from_bits_truncate(flags.into()))
+    }
+
+    /// Set the process group of the child, which is a new group led by the
+    /// child if `pgroup` is `Pid::from_raw(0)`.  Requires
+    /// `POSIX_SPAWN_SETPGROUP`.
+    pub fn set_pgroup(&mut self, pgroup: Pid) -> Result<()> {
+        spawn_result(unsafe { libc::posix_spawnattr_setpgroup(&mut self.attr, pgroup.into()) })
+    }
+
+    /// Get the process group of the child.
+    pub fn pgroup(&self) -> Result<Pid> {
+        let mut pgroup = 0;
+        spawn_result(unsafe { libc::posix_spawnattr_getpgroup(&self.attr, &mut pgroup) })?;
+        Ok(Pid::from_raw(pgroup))
+    }
+
+    /// Set the signal mask of the child.  Requires `POSIX_SPAWN_SETSIGMASK`.
+    pub fn set_sigmask(&mut self, sigmask: &SigSet) -> Result<()> {
+        spawn_result(unsafe {
+            libc::posix_spawnattr_setsigmask(&mut self.attr, sigmask.as_ref())
+        })
+    }
+
+    /// Set the signals to reset to their default action in the child.
+    /// Requires `POSIX_SPAWN_SETSIGDEF`.
+    ///
+    /// Signals that are caught in the calling process are always reset, but
+    /// ignored signals stay ignored unless they are in `sigdefault`.
+    pub fn set_sigdefault(&mut self, sigdefault: &SigSet) -> Result<()> {
+        spawn_result(unsafe {
+            libc::posix_spawnattr_setsigdefault(&mut self.attr, sigdefault.as_ref())
+        })
+    }
+
+    /// Set the scheduling policy of the child.  Requires
+    /// `POSIX_SPAWN_SETSCHEDULER`.
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "netbsd"))]
+    pub fn set_sched_policy(&mut self, policy: SchedPolicy) -> Result<()> {
+        spawn_result(unsafe {
+            libc::posix_spawnattr_setschedpolicy(&mut self.attr, policy as c_int)
+        })
+    }
+
+    /// Set the scheduling priority of the child.  Requires
+    /// `POSIX_SPAWN_SETSCHEDPARAM`, or `POSIX_SPAWN_SETSCHEDULER` to set it
+    /// along with the policy.
+    #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "netbsd"))]
+    pub fn set_sched_priority(&mut self, priority: c_int) -> Result<()> {
+        let mut param: libc::sched_param = unsafe { mem::zeroed() };
+        param.sched_priority = priority;
+        spawn_result(unsafe { libc::posix_spawnattr_setschedparam(&mut self.attr, &param) })
+    }
+}
+
+impl Drop for PosixSpawnAttr {
+    fn drop(&mut self) {
+        unsafe { libc::posix_spawnattr_destroy(&mut self.attr) };
+    }
+}
+
+impl fmt::Debug for PosixSpawnAttr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PosixSpawnAttr")
+            .field("flags", &self.flags())
+            .field("pgroup", &self.pgroup())
+            .finish()
+    }
+}
+
+/// File descriptor changes to make in a process created by
+/// [`posix_spawn`](fn.posix_spawn.html)
+///
+/// The actions are performed in the order they were added.  File descriptors
+/// with the close-on-exec flag are still closed when the program is executed.
+pub struct PosixSpawnFileActions {
+    fa: libc::posix_spawn_file_actions_t,
+}
+
+impl PosixSpawnFileActions {
+    /// Create an empty list of actions.
+    pub fn new() -> Result<PosixSpawnFileActions> {
+        let mut fa = mem::MaybeUninit::uninit();
+        spawn_result(unsafe { libc::posix_spawn_file_actions_init(fa.as_mut_ptr()) })?;
+        Ok(PosixSpawnFileActions { fa: unsafe { fa.assume_init() } })
+    }
+
+    /// Duplicate `fd` into `newfd` in the child, like with
+    /// [`dup2`](../unistd/fn.dup2.html).  `newfd` doesn't have the
+    /// close-on-exec flag, even if `fd` does.
+    pub fn add_dup2(&mut self, fd: RawFd, newfd: RawFd) -> Result<()> {
+        spawn_result(unsafe {
+            libc::posix_spawn_file_actions_adddup2(&mut self.fa, fd, newfd)
+        })
+    }
+
+    /// Open `path` as `fd` in the child, like with
+    /// [`open`](../fcntl/fn.open.html) followed by `dup2`.
+    pub fn add_open<P: ?Sized + NixPath>(&mut self, fd: RawFd, path: &P, oflag: OFlag,
+                                         mode: Mode) -> Result<()> {
+        // The path is copied, so it doesn't have to outlive the actions.
+        let res = path.with_nix_path(|cstr| unsafe {
+            libc::posix_spawn_file_actions_addopen(&mut self.fa, fd, cstr.as_ptr(),
+                                                   oflag.bits(), mode.bits())
+        })?;
+        spawn_result(res)
+    }
+
+    /// Close `fd` in the child.
+    pub fn add_close(&mut self, fd: RawFd) -> Result<()> {
+        spawn_result(unsafe { libc::posix_spawn_file_actions_addclose(&mut self.fa, fd) })
+    }
+}
+
+impl Drop for PosixSpawnFileActions {
+    fn drop(&mut self) {
+        unsafe { libc::posix_spawn_file_actions_destroy(&mut self.fa) };
+    }
+}
+
+impl fmt::Debug for PosixSpawnFileActions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PosixSpawnFileActions").finish()
+    }
+}
+
+fn to_exec_array(args: &[&CStr]) -> Vec<*mut c_char> {
+    use std::iter::once;
+    args.iter().map(|s| s.as_ptr() as *mut c_char).chain(once(ptr::null_mut())).collect()
+}
+
+/// Create a child process executing the program `path`, with the arguments
+/// `args` and the environment `env`, and return its process id.
+///
+/// `file_actions` and `attr` describe how the child differs from the calling
+/// process.  Errors that happen in the child, after it was created, are
+/// reported by some implementations, like glibc, and make the child exit
+/// with status 127 on others.
+///
+/// See also [posix_spawn(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/posix_spawn.html)
+pub fn posix_spawn(path: &CStr, file_actions: &PosixSpawnFileActions, attr: &PosixSpawnAttr,
+                   args: &[&CStr], env: &[&CStr]) -> Result<Pid> {
+    let args_p = to_exec_array(args);
+    let env_p = to_exec_array(env);
+    let mut pid = 0;
+
+    spawn_result(unsafe {
+        libc::posix_spawn(&mut pid, path.as_ptr(), &file_actions.fa, &attr.attr,
+                          args_p.as_ptr(), env_p.as_ptr())
+    })?;
+    Ok(Pid::from_raw(pid))
+}
+
+/// Like [`posix_spawn`](fn.posix_spawn.html), but searches for the program
+/// `file` in the directories of the `PATH` environment variable if it doesn't
+/// contain a slash.
+///
+/// See also [posix_spawnp(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/posix_spawnp.html)
+pub fn posix_spawnp(file: &CStr, file_actions: &PosixSpawnFileActions, attr: &PosixSpawnAttr,
+                    args: &[&CStr], env: &[&CStr]) -> Result<Pid> {
+    let args_p = to_exec_array(args);
+    let env_p = to_exec_array(env);
+    let mut pid = 0;
+
+    spawn_result(unsafe {
+        libc::posix_spawnp(&mut pid, file.as_ptr(), &file_actions.fa, &attr.attr,
+                           args_p.as_ptr(), env_p.as_ptr())
+    })?;
+    Ok(Pid::from_raw(pid))
+}
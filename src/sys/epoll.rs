@@ -1,7 +1,10 @@
 use crate::Result;
 use crate::errno::Errno;
+#[cfg(target_os = "linux")]
+use crate::sys::signal::SigSet;
+use crate::unistd::OwnedFd;
 use libc::{self, c_int};
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use std::ptr;
 use std::mem;
 use crate::Error;
@@ -19,10 +22,19 @@ libc_bitflags!(
         EPOLLERR;
         EPOLLHUP;
         EPOLLRDHUP;
+        /// Wake up only one of the epoll instances watching the same file
+        /// descriptor with this flag, to avoid thundering herds.  Only valid
+        /// with `EpollCtlAdd`.
         #[cfg(target_os = "linux")]  // Added in 4.5; not in Android.
         EPOLLEXCLUSIVE;
+        /// Keep the system from suspending while the event is pending or
+        /// being processed, that is until the next `epoll_wait`.  Requires
+        /// the `CAP_BLOCK_SUSPEND` capability, and is silently ignored
+        /// without it.
         #[cfg(not(target_arch = "mips"))]
         EPOLLWAKEUP;
+        /// Disable the file descriptor after one event is reported, until it
+        /// is re-armed with [`Epoll::rearm`](struct.Epoll.html#method.rearm).
         EPOLLONESHOT;
         EPOLLET;
     }
@@ -64,6 +76,18 @@ impl EpollEvent {
     pub fn data(&self) -> u64 {
         self.event.u64
     }
+
+    /// Creates an event whose data is the file descriptor `fd`, which can be
+    /// read back with [`fd`](#method.fd).
+    pub fn with_fd(events: EpollFlags, fd: RawFd) -> Self {
+        EpollEvent::new(events, fd as u64)
+    }
+
+    /// The file descriptor stored in the data of an event created with
+    /// [`with_fd`](#method.with_fd).
+    pub fn fd(&self) -> RawFd {
+        self.event.u64 as RawFd
+    }
 }
 
 #[inline]
@@ -107,3 +131,94 @@ pub fn epoll_wait(epfd: RawFd, events: &mut [EpollEvent], timeout_ms: isize) ->
 
     Errno::result(res).map(|r| r as usize)
 }
+
+/// Like [`epoll_wait`](fn.epoll_wait.html), but atomically replaces the signal
+/// mask of the calling thread with `sigmask` while waiting.  If `sigmask` is
+/// `None`, the signal mask is left alone.
+///
+/// See also [epoll_pwait(2)](http://man7.org/linux/man-pages/man2/epoll_pwait.2.html).
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn epoll_pwait(epfd: RawFd, events: &mut [EpollEvent], timeout_ms: isize,
+                   sigmask: Option<&SigSet>) -> Result<usize>
+{
+    let res = unsafe {
+        libc::epoll_pwait(epfd, events.as_mut_ptr() as *mut libc::epoll_event,
+                          events.len() as c_int, timeout_ms as c_int,
+                          sigmask.map_or(ptr::null(), |s| s.as_ref() as *const libc::sigset_t))
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// An epoll instance, which is closed when dropped.
+///
+/// # Examples
+///
+/// Wait for a pipe to become readable, in edge-triggered mode:
+///
+/// ```
+/// # use nix::sys::epoll::{Epoll, EpollCreateFlags, EpollEvent, EpollFlags};
+/// # use nix::unistd::{pipe, write};
+/// let epoll = Epoll::new(EpollCreateFlags::EPOLL_CLOEXEC).unwrap();
+/// let (r, w) = pipe().unwrap();
+/// epoll.add(r, EpollEvent::with_fd(EpollFlags::EPOLLIN | EpollFlags::EPOLLET, r)).unwrap();
+/// write(w, b"x").unwrap();
+///
+/// let mut events = [EpollEvent::empty(); 8];
+/// let n = epoll.wait(&mut events, -1).unwrap();
+/// assert_eq!(n, 1);
+/// assert_eq!(events[0].fd(), r);
+/// ```
+#[derive(Debug)]
+pub struct Epoll(OwnedFd);
+
+impl Epoll {
+    /// Creates a new epoll instance.
+    pub fn new(flags: EpollCreateFlags) -> Result<Epoll> {
+        epoll_create1(flags).map(|fd| Epoll(unsafe { OwnedFd::from_raw_fd(fd) }))
+    }
+
+    /// Starts watching `fd` for the events in `event`.
+    pub fn add(&self, fd: RawFd, mut event: EpollEvent) -> Result<()> {
+        epoll_ctl(self.as_raw_fd(), EpollOp::EpollCtlAdd, fd, &mut event)
+    }
+
+    /// Changes the events watched for on `fd`.
+    pub fn modify(&self, fd: RawFd, mut event: EpollEvent) -> Result<()> {
+        epoll_ctl(self.as_raw_fd(), EpollOp::EpollCtlMod, fd, &mut event)
+    }
+
+    /// Re-enables a file descriptor added with `EPOLLONESHOT`, after its event
+    /// was reported.  `EPOLLONESHOT` is added to the events of `event`.
+    pub fn rearm(&self, fd: RawFd, event: EpollEvent) -> Result<()> {
+        let events = event.events() | EpollFlags::EPOLLONESHOT;
+        self.modify(fd, EpollEvent::new(events, event.data()))
+    }
+
+    /// Stops watching `fd`.
+    pub fn delete(&self, fd: RawFd) -> Result<()> {
+        epoll_ctl(self.as_raw_fd(), EpollOp::EpollCtlDel, fd, None)
+    }
+
+    /// Waits for events, for at most `timeout_ms` milliseconds or forever if
+    /// it is -1, and returns how many were stored in `events`.
+    pub fn wait(&self, events: &mut [EpollEvent], timeout_ms: isize) -> Result<usize> {
+        epoll_wait(self.as_raw_fd(), events, timeout_ms)
+    }
+
+    /// Like [`wait`](#method.wait), with the signal mask replaced by `sigmask`
+    /// while waiting.  See [`epoll_pwait`](fn.epoll_pwait.html).
+    #[cfg(target_os = "linux")]
+    pub fn pwait(&self, events: &mut [EpollEvent], timeout_ms: isize,
+                 sigmask: Option<&SigSet>) -> Result<usize>
+    {
+        epoll_pwait(self.as_raw_fd(), events, timeout_ms, sigmask)
+    }
+}
+
+impl AsRawFd for Epoll {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
@@ -2,11 +2,12 @@
  */
 
 use crate::{Errno, Result};
+use crate::unistd::OwnedFd;
 #[cfg(not(target_os = "netbsd"))]
 use libc::{timespec, time_t, c_int, c_long, intptr_t, uintptr_t};
 #[cfg(target_os = "netbsd")]
 use libc::{timespec, time_t, c_long, intptr_t, uintptr_t, size_t};
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use std::ptr;
 use std::mem;
 
@@ -64,7 +65,11 @@ libc_enum! {
         #[cfg(target_os = "freebsd")]
         EVFILT_SENDFILE,
         EVFILT_SIGNAL,
+        /// Returns when a timer, whose period is given in `data`, expires.
+        /// The unit of the period is set with a `NOTE_*SECONDS` flag, and
+        /// defaults to milliseconds.
         EVFILT_TIMER,
+        /// Returns when triggered by another `kevent` call with `NOTE_TRIGGER`
         #[cfg(any(target_os = "dragonfly",
                   target_os = "freebsd",
                   target_os = "ios",
@@ -120,8 +125,16 @@ libc_bitflags!(
     pub struct FilterFlag: u32 {
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         NOTE_ABSOLUTE;
+        /// For `EVFILT_VNODE`, the attributes of the file changed
         NOTE_ATTRIB;
+        /// For `EVFILT_TIMER`, the timer is dropped when the system is idle
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        NOTE_BACKGROUND;
         NOTE_CHILD;
+        /// For `EVFILT_TIMER`, the timer is not to be coalesced with others
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        NOTE_CRITICAL;
+        /// For `EVFILT_VNODE`, the file was unlinked
         NOTE_DELETE;
         #[cfg(target_os = "openbsd")]
         NOTE_EOF;
@@ -129,6 +142,7 @@ libc_bitflags!(
         NOTE_EXIT;
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         NOTE_EXITSTATUS;
+        /// For `EVFILT_VNODE`, the file was extended
         NOTE_EXTEND;
         #[cfg(any(target_os = "macos",
                   target_os = "ios",
@@ -161,26 +175,33 @@ libc_bitflags!(
                   target_os = "dragonfly"))]
         NOTE_FFOR;
         NOTE_FORK;
+        /// For `EVFILT_VNODE`, the link count of the file changed
         NOTE_LINK;
         NOTE_LOWAT;
+        /// For `EVFILT_TIMER`, the period is in milliseconds
         #[cfg(target_os = "freebsd")]
         NOTE_MSECONDS;
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         NOTE_NONE;
+        /// For `EVFILT_TIMER`, the period is in nanoseconds
         #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
         NOTE_NSECONDS;
         #[cfg(target_os = "dragonfly")]
         NOTE_OOB;
         NOTE_PCTRLMASK;
         NOTE_PDATAMASK;
+        /// For `EVFILT_VNODE`, the file was renamed
         NOTE_RENAME;
+        /// For `EVFILT_VNODE`, access to the file was revoked
         NOTE_REVOKE;
+        /// For `EVFILT_TIMER`, the period is in seconds
         #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
         NOTE_SECONDS;
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         NOTE_SIGNAL;
         NOTE_TRACK;
         NOTE_TRACKERR;
+        /// For `EVFILT_USER`, trigger the event
         #[cfg(any(target_os = "macos",
                   target_os = "ios",
                   target_os = "freebsd",
@@ -188,6 +209,7 @@ libc_bitflags!(
         NOTE_TRIGGER;
         #[cfg(target_os = "openbsd")]
         NOTE_TRUNCATE;
+        /// For `EVFILT_TIMER`, the period is in microseconds
         #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
         NOTE_USECONDS;
         #[cfg(any(target_os = "macos", target_os = "ios"))]
@@ -198,6 +220,7 @@ libc_bitflags!(
         NOTE_VM_PRESSURE_SUDDEN_TERMINATE;
         #[cfg(any(target_os = "macos", target_os = "ios"))]
         NOTE_VM_PRESSURE_TERMINATE;
+        /// For `EVFILT_VNODE`, the file was written to
         NOTE_WRITE;
     }
 );
@@ -208,6 +231,70 @@ pub fn kqueue() -> Result<RawFd> {
     Errno::result(res)
 }
 
+/// A kernel event queue, which is closed when dropped.
+///
+/// Changes are collected with [`change`](#method.change), and submitted
+/// together by the next call to [`wait`](#method.wait), in a single `kevent`
+/// system call.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::event::*;
+/// let mut kq = Kqueue::new().unwrap();
+/// kq.change(KEvent::timer(1, EventFlag::EV_ADD, FilterFlag::empty(), 100, 0));
+/// let mut events = [KEvent::new(0, EventFilter::EVFILT_READ, EventFlag::empty(),
+///                               FilterFlag::empty(), 0, 0)];
+/// let n = kq.wait(&mut events, None).unwrap();
+/// assert_eq!(n, 1);
+/// assert_eq!(events[0].ident(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Kqueue {
+    fd: OwnedFd,
+    changes: Vec<KEvent>,
+}
+
+impl Kqueue {
+    /// Creates a new kernel event queue.
+    pub fn new() -> Result<Kqueue> {
+        kqueue().map(|fd| Kqueue {
+            fd: unsafe { OwnedFd::from_raw_fd(fd) },
+            changes: Vec::new(),
+        })
+    }
+
+    /// Adds a change to submit with the next call to [`wait`](#method.wait).
+    pub fn change(&mut self, ev: KEvent) {
+        self.changes.push(ev);
+    }
+
+    /// Submits the pending changes, without waiting for events.
+    pub fn flush(&mut self) -> Result<()> {
+        self.wait(&mut [], None).map(drop)
+    }
+
+    /// Submits the pending changes, and then waits for at most `timeout`, or
+    /// forever if it is `None`, for events.  Returns how many events were
+    /// stored in `eventlist`.  When `eventlist` is empty it returns
+    /// immediately.
+    ///
+    /// The pending changes are discarded even on failure.
+    pub fn wait(&mut self, eventlist: &mut [KEvent], timeout: Option<timespec>)
+        -> Result<usize>
+    {
+        let res = kevent_ts(self.fd.as_raw_fd(), &self.changes, eventlist, timeout);
+        self.changes.clear();
+        res
+    }
+}
+
+impl AsRawFd for Kqueue {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
 
 // KEvent can't derive Send because on some operating systems, udata is defined
 // as a void*.  However, KEvent's public API always treats udata as an intptr_t,
@@ -251,6 +338,44 @@ impl KEvent {
     pub fn udata(&self) -> intptr_t {
         self.kevent.udata as intptr_t
     }
+
+    /// An `EVFILT_USER` event, which is only ever triggered by
+    /// [`KEvent::trigger`](#method.trigger).  Register it with `EV_ADD`, and
+    /// usually `EV_CLEAR` so that it is reset once returned.
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos"))]
+    pub fn user(ident: uintptr_t, flags: EventFlag, udata: intptr_t) -> KEvent {
+        KEvent::new(ident, EventFilter::EVFILT_USER, flags, FilterFlag::empty(),
+                    0, udata)
+    }
+
+    /// The change that triggers the `EVFILT_USER` event `ident`.
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "macos"))]
+    pub fn trigger(ident: uintptr_t) -> KEvent {
+        KEvent::new(ident, EventFilter::EVFILT_USER, EventFlag::empty(),
+                    FilterFlag::NOTE_TRIGGER, 0, 0)
+    }
+
+    /// An `EVFILT_TIMER` event, which fires every `period`.  `unit` is one of
+    /// the `NOTE_*SECONDS` flags, or empty for milliseconds.  Add `EV_ONESHOT`
+    /// to `flags` for a timer that fires only once.
+    pub fn timer(ident: uintptr_t, flags: EventFlag, unit: FilterFlag,
+                 period: intptr_t, udata: intptr_t) -> KEvent {
+        KEvent::new(ident, EventFilter::EVFILT_TIMER, flags, unit, period, udata)
+    }
+
+    /// An `EVFILT_VNODE` event, which fires when one of the changes in
+    /// `fflags` happens to the file open as `fd`.
+    pub fn vnode(fd: RawFd, flags: EventFlag, fflags: FilterFlag,
+                 udata: intptr_t) -> KEvent {
+        KEvent::new(fd as uintptr_t, EventFilter::EVFILT_VNODE, flags, fflags,
+                    0, udata)
+    }
 }
 
 pub fn kevent(kq: RawFd,
@@ -328,3 +453,40 @@ fn test_struct_kevent() {
     assert_eq!(udata as type_of_udata, actual.udata() as type_of_udata);
     assert_eq!(mem::size_of::<libc::kevent>(), mem::size_of::<KEvent>());
 }
+
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "macos"))]
+#[test]
+fn test_kqueue_user() {
+    let mut kq = Kqueue::new().unwrap();
+    let mut events = [KEvent::new(0, EventFilter::EVFILT_READ, EventFlag::empty(),
+                                  FilterFlag::empty(), 0, 0)];
+    let zero = timespec { tv_sec: 0, tv_nsec: 0 };
+
+    kq.change(KEvent::user(42, EventFlag::EV_ADD | EventFlag::EV_CLEAR, 7));
+    kq.flush().unwrap();
+    assert_eq!(kq.wait(&mut events, Some(zero)).unwrap(), 0);
+
+    kq.change(KEvent::trigger(42));
+    assert_eq!(kq.wait(&mut events, Some(zero)).unwrap(), 1);
+    assert_eq!(events[0].ident(), 42);
+    assert_eq!(events[0].filter(), EventFilter::EVFILT_USER);
+    assert_eq!(events[0].udata(), 7);
+    // EV_CLEAR resets the event once it is returned
+    assert_eq!(kq.wait(&mut events, Some(zero)).unwrap(), 0);
+}
+
+#[test]
+fn test_kqueue_timer() {
+    let mut kq = Kqueue::new().unwrap();
+    let mut events = [KEvent::new(0, EventFilter::EVFILT_READ, EventFlag::empty(),
+                                  FilterFlag::empty(), 0, 0)];
+
+    kq.change(KEvent::timer(1, EventFlag::EV_ADD | EventFlag::EV_ONESHOT,
+                            FilterFlag::empty(), 10, 0));
+    assert_eq!(kq.wait(&mut events, None).unwrap(), 1);
+    assert_eq!(events[0].ident(), 1);
+    assert_eq!(events[0].filter(), EventFilter::EVFILT_TIMER);
+}
@@ -1,7 +1,8 @@
 use libc;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 use crate::Result;
 use crate::errno::Errno;
+use crate::unistd::{read, write, OwnedFd};
 
 libc_bitflags! {
     pub struct EfdFlags: libc::c_int {
@@ -16,3 +17,68 @@ pub fn eventfd(initval: libc::c_uint, flags: EfdFlags) -> Result<RawFd> {
 
     Errno::result(res).map(|r| r as RawFd)
 }
+
+/// An event notification counter, which is closed when dropped.
+///
+/// [`write`](#method.write) adds to the counter, and [`read`](#method.read)
+/// waits until it is non-zero, and then returns its value and resets it to
+/// zero.  With `EFD_SEMAPHORE`, `read` decrements the counter and returns 1
+/// instead.  With `EFD_NONBLOCK`, `read` fails with `EAGAIN` instead of
+/// waiting.
+///
+/// The file descriptor is readable when the counter is non-zero, so an
+/// `EventFd` can be used with `poll` or `epoll` to wake up another thread.
+/// Share it between threads with a reference or an `Arc`.
+///
+/// For more documentation, please read
+/// [eventfd(2)](http://man7.org/linux/man-pages/man2/eventfd.2.html).
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::eventfd::{EfdFlags, EventFd};
+/// let efd = EventFd::new(0, EfdFlags::EFD_CLOEXEC).unwrap();
+/// efd.write(2).unwrap();
+/// efd.write(3).unwrap();
+/// assert_eq!(efd.read().unwrap(), 5);
+/// ```
+#[derive(Debug)]
+pub struct EventFd {
+    fd: OwnedFd,
+}
+
+impl EventFd {
+    /// Creates a new counter, starting at `initval`.
+    pub fn new(initval: libc::c_uint, flags: EfdFlags) -> Result<EventFd> {
+        eventfd(initval, flags).map(|fd| unsafe { EventFd::from_raw_fd(fd) })
+    }
+
+    /// Reads the counter, waiting until it is non-zero unless the counter is
+    /// non-blocking.
+    pub fn read(&self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        read(self.fd.as_raw_fd(), &mut buf)?;
+        Ok(u64::from_ne_bytes(buf))
+    }
+
+    /// Adds `value` to the counter.
+    ///
+    /// If the counter would exceed `u64::MAX - 1`, waits until it is read,
+    /// unless the counter is non-blocking.  Fails with `EINVAL` if `value` is
+    /// `u64::MAX`.
+    pub fn write(&self, value: u64) -> Result<()> {
+        write(self.fd.as_raw_fd(), &value.to_ne_bytes()).map(drop)
+    }
+}
+
+impl AsRawFd for EventFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl FromRawFd for EventFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        EventFd { fd: OwnedFd::from_raw_fd(fd) }
+    }
+}
@@ -0,0 +1,395 @@
+//! System V interprocess communication: shared memory segments, semaphore
+//! sets and message queues.
+//!
+//! Each object is identified system-wide by an [`IpcKey`](struct.IpcKey.html),
+//! usually derived from a path with [`ftok`](fn.ftok.html) so that unrelated
+//! processes agree on it.  Objects aren't freed when the processes using them
+//! exit: remove them explicitly with `IPC_RMID`.
+//!
+//! New code should prefer POSIX shared memory
+//! ([`shm_open`](../mman/fn.shm_open.html)), semaphores and message queues
+//! ([`mqueue`](../../mqueue/index.html)), but System V IPC is still needed to
+//! interoperate with existing programs.
+//!
+//! For more documentation, please read
+//! [sysvipc(7)](http://man7.org/linux/man-pages/man7/sysvipc.7.html).
+use bitflags::bitflags;
+use libc::{self, c_int, c_long, c_short, c_ushort, c_void};
+use std::{mem, ptr};
+
+use crate::{Error, NixPath, Result};
+use crate::errno::Errno;
+use crate::sys::stat::Mode;
+
+/// The key of a System V IPC object.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct IpcKey(libc::key_t);
+
+impl IpcKey {
+    /// Always creates a new object, which is only accessible through its
+    /// identifier.
+    pub const IPC_PRIVATE: IpcKey = IpcKey(libc::IPC_PRIVATE);
+
+    /// Creates `IpcKey` from raw `key_t`.
+    pub fn from_raw(key: libc::key_t) -> Self {
+        IpcKey(key)
+    }
+
+    /// Returns the raw `key_t` of the `IpcKey`.
+    pub fn as_raw(self) -> libc::key_t {
+        self.0
+    }
+}
+
+/// Generate an IPC key from the inode of an existing file and the low 8 bits
+/// of `proj_id`, which must not be 0 (see
+/// [ftok(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/ftok.html)).
+pub fn ftok<P: ?Sized + NixPath>(path: &P, proj_id: u8) -> Result<IpcKey> {
+    let res = path.with_nix_path(|cstr| unsafe {
+        libc::ftok(cstr.as_ptr(), c_int::from(proj_id))
+    })?;
+
+    Errno::result(res).map(IpcKey)
+}
+
+libc_bitflags!(
+    /// Flags for `shmget`, `semget` and `msgget`.
+    pub struct IpcGetFlags: c_int {
+        /// Create the object if it doesn't exist yet.
+        IPC_CREAT;
+        /// With `IPC_CREAT`, fail with `EEXIST` if the object already exists.
+        IPC_EXCL;
+    }
+);
+
+// The lower 9 bits of the flags are the permissions of a new object.
+fn get_flags(flags: IpcGetFlags, mode: Mode) -> c_int {
+    flags.bits() | (mode.bits() & 0o777) as c_int
+}
+
+/// The identifier of a shared memory segment.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ShmId(c_int);
+
+impl ShmId {
+    /// Creates `ShmId` from raw `c_int`.
+    pub fn from_raw(id: c_int) -> Self {
+        ShmId(id)
+    }
+
+    /// Returns the raw `c_int` of the `ShmId`.
+    pub fn as_raw(self) -> c_int {
+        self.0
+    }
+}
+
+/// Get the shared memory segment of `key`, or create one of `size` bytes
+/// with the permissions in `mode` (see
+/// [shmget(2)](http://man7.org/linux/man-pages/man2/shmget.2.html)).
+///
+/// New segments are zero-filled.
+pub fn shmget(key: IpcKey, size: usize, flags: IpcGetFlags, mode: Mode) -> Result<ShmId> {
+    let res = unsafe { libc::shmget(key.0, size, get_flags(flags, mode)) };
+
+    Errno::result(res).map(ShmId)
+}
+
+libc_bitflags!(
+    /// Flags for `shmat`.
+    pub struct ShmatFlags: c_int {
+        /// Attach the segment read-only.
+        SHM_RDONLY;
+        /// Round the address down to a multiple of `SHMLBA`.
+        SHM_RND;
+        /// Replace any existing mapping at the address.
+        SHM_REMAP;
+    }
+);
+
+/// Attach a shared memory segment to the address space of the process (see
+/// [shmat(2)](http://man7.org/linux/man-pages/man2/shmat.2.html)).
+///
+/// If `addr` is `None`, the kernel chooses the address.  Returns the address
+/// the segment was attached at.
+///
+/// # Safety
+///
+/// Other processes can modify the segment at any time, so Rust references to
+/// it must not be created without some form of synchronization.  With
+/// `SHM_REMAP`, existing mappings at `addr` are replaced, as with
+/// `mmap(MAP_FIXED)`.
+pub unsafe fn shmat(id: ShmId, addr: Option<*const c_void>, flags: ShmatFlags)
+    -> Result<*mut c_void>
+{
+    let res = libc::shmat(id.0, addr.unwrap_or(ptr::null()), flags.bits());
+
+    if res as isize == -1 {
+        Err(Error::Sys(Errno::last()))
+    } else {
+        Ok(res)
+    }
+}
+
+/// Detach the shared memory segment attached at `addr` (see
+/// [shmdt(2)](http://man7.org/linux/man-pages/man2/shmdt.2.html)).
+///
+/// # Safety
+///
+/// Like `munmap`, any reference to the segment is left dangling.
+pub unsafe fn shmdt(addr: *const c_void) -> Result<()> {
+    Errno::result(libc::shmdt(addr)).map(drop)
+}
+
+/// Commands for [`shmctl`](fn.shmctl.html).
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub enum ShmctlArg<'a> {
+    /// Get the segment's permissions, size and usage.
+    IPC_STAT(&'a mut libc::shmid_ds),
+    /// Change the segment's owner and permissions.
+    IPC_SET(&'a libc::shmid_ds),
+    /// Mark the segment to be removed once it's no longer attached.
+    IPC_RMID,
+}
+
+/// Control a shared memory segment (see
+/// [shmctl(2)](http://man7.org/linux/man-pages/man2/shmctl.2.html)).
+pub fn shmctl(id: ShmId, arg: ShmctlArg) -> Result<c_int> {
+    let res = unsafe {
+        match arg {
+            ShmctlArg::IPC_STAT(buf) => libc::shmctl(id.0, libc::IPC_STAT, buf),
+            ShmctlArg::IPC_SET(buf) => {
+                libc::shmctl(id.0, libc::IPC_SET, buf as *const _ as *mut _)
+            },
+            ShmctlArg::IPC_RMID => libc::shmctl(id.0, libc::IPC_RMID, ptr::null_mut()),
+        }
+    };
+
+    Errno::result(res)
+}
+
+/// The identifier of a semaphore set.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SemId(c_int);
+
+impl SemId {
+    /// Creates `SemId` from raw `c_int`.
+    pub fn from_raw(id: c_int) -> Self {
+        SemId(id)
+    }
+
+    /// Returns the raw `c_int` of the `SemId`.
+    pub fn as_raw(self) -> c_int {
+        self.0
+    }
+}
+
+/// Get the semaphore set of `key`, or create one of `nsems` semaphores with
+/// the permissions in `mode` (see
+/// [semget(2)](http://man7.org/linux/man-pages/man2/semget.2.html)).
+///
+/// The semaphores of a new set are 0.
+pub fn semget(key: IpcKey, nsems: c_int, flags: IpcGetFlags, mode: Mode) -> Result<SemId> {
+    let res = unsafe { libc::semget(key.0, nsems, get_flags(flags, mode)) };
+
+    Errno::result(res).map(SemId)
+}
+
+bitflags!(
+    /// Flags for a [`SemBuf`](struct.SemBuf.html).
+    pub struct SemOpFlags: c_short {
+        /// Fail with `EAGAIN` instead of blocking.
+        const IPC_NOWAIT = libc::IPC_NOWAIT as c_short;
+        /// Undo the operation when the process exits.
+        // libc only has this from 0.2.175, which needs a newer Rust than nix
+        const SEM_UNDO = 0x1000;
+    }
+);
+
+/// A single operation of [`semop`](fn.semop.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct SemBuf(libc::sembuf);
+
+impl SemBuf {
+    /// Add `op` to semaphore number `num`.
+    ///
+    /// A negative `op` blocks until the semaphore is at least `-op`, and a
+    /// zero `op` blocks until it is 0.
+    pub fn new(num: c_ushort, op: c_short, flags: SemOpFlags) -> Self {
+        SemBuf(libc::sembuf {
+            sem_num: num,
+            sem_op: op,
+            sem_flg: flags.bits(),
+        })
+    }
+}
+
+/// Atomically perform all of `ops` on a semaphore set (see
+/// [semop(2)](http://man7.org/linux/man-pages/man2/semop.2.html)).
+///
+/// Blocks until all of them can be performed, unless `IPC_NOWAIT` is given.
+pub fn semop(id: SemId, ops: &[SemBuf]) -> Result<()> {
+    // The kernel only reads the operations
+    let res = unsafe {
+        libc::semop(id.0, ops.as_ptr() as *mut libc::sembuf, ops.len())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+// libc only has these from 0.2.175, which needs a newer Rust than nix
+const GETPID: c_int = 11;
+const GETVAL: c_int = 12;
+const GETNCNT: c_int = 14;
+const GETZCNT: c_int = 15;
+const SETVAL: c_int = 16;
+
+/// Commands for [`semctl`](fn.semctl.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SemctlArg {
+    /// Remove the semaphore set, waking up all processes blocked in `semop`.
+    IPC_RMID,
+    /// Return the value of the semaphore.
+    GETVAL,
+    /// Set the value of the semaphore.
+    SETVAL(c_int),
+    /// Return the PID of the process that last operated on the semaphore.
+    GETPID,
+    /// Return the number of processes waiting for the semaphore to increase.
+    GETNCNT,
+    /// Return the number of processes waiting for the semaphore to become 0.
+    GETZCNT,
+    // TODO: Rest of commands
+}
+
+// The variadic argument of `semctl`
+#[repr(C)]
+union semun {
+    val: c_int,
+    buf: *mut c_void,
+}
+
+/// Control semaphore number `num` of a semaphore set (see
+/// [semctl(2)](http://man7.org/linux/man-pages/man2/semctl.2.html)).
+pub fn semctl(id: SemId, num: c_int, arg: SemctlArg) -> Result<c_int> {
+    let (cmd, val) = match arg {
+        SemctlArg::IPC_RMID => (libc::IPC_RMID, 0),
+        SemctlArg::GETVAL => (GETVAL, 0),
+        SemctlArg::SETVAL(val) => (SETVAL, val),
+        SemctlArg::GETPID => (GETPID, 0),
+        SemctlArg::GETNCNT => (GETNCNT, 0),
+        SemctlArg::GETZCNT => (GETZCNT, 0),
+    };
+    let res = unsafe { libc::semctl(id.0, num, cmd, semun { val }) };
+
+    Errno::result(res)
+}
+
+/// The identifier of a message queue.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MsgId(c_int);
+
+impl MsgId {
+    /// Creates `MsgId` from raw `c_int`.
+    pub fn from_raw(id: c_int) -> Self {
+        MsgId(id)
+    }
+
+    /// Returns the raw `c_int` of the `MsgId`.
+    pub fn as_raw(self) -> c_int {
+        self.0
+    }
+}
+
+/// Get the message queue of `key`, or create one with the permissions in
+/// `mode` (see
+/// [msgget(2)](http://man7.org/linux/man-pages/man2/msgget.2.html)).
+pub fn msgget(key: IpcKey, flags: IpcGetFlags, mode: Mode) -> Result<MsgId> {
+    let res = unsafe { libc::msgget(key.0, get_flags(flags, mode)) };
+
+    Errno::result(res).map(MsgId)
+}
+
+libc_bitflags!(
+    /// Flags for `msgsnd` and `msgrcv`.
+    pub struct MsgFlags: c_int {
+        /// Fail with `EAGAIN` (or `ENOMSG` for `msgrcv`) instead of blocking.
+        IPC_NOWAIT;
+        /// Truncate messages that are too long for the buffer, instead of
+        /// failing with `E2BIG`.
+        MSG_NOERROR;
+        /// Receive the first message whose type differs from `mtype`.
+        MSG_EXCEPT;
+    }
+);
+
+// A `struct msgbuf` with room for `len` bytes of text, aligned for its
+// `mtype`.
+fn msgbuf(len: usize) -> Vec<c_long> {
+    vec![0; len / mem::size_of::<c_long>() + 2]
+}
+
+/// Send a message of type `mtype`, which must be positive (see
+/// [msgsnd(2)](http://man7.org/linux/man-pages/man2/msgsnd.2.html)).
+pub fn msgsnd(id: MsgId, mtype: c_long, text: &[u8], flags: MsgFlags) -> Result<()> {
+    let mut buf = msgbuf(text.len());
+    buf[0] = mtype;
+    let res = unsafe {
+        ptr::copy_nonoverlapping(text.as_ptr(), buf[1..].as_mut_ptr() as *mut u8,
+                                 text.len());
+        libc::msgsnd(id.0, buf.as_ptr() as *const c_void, text.len(), flags.bits())
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Receive a message into `text` (see
+/// [msgrcv(2)](http://man7.org/linux/man-pages/man2/msgrcv.2.html)).
+///
+/// If `mtype` is 0, the first message is received.  If it's positive, the
+/// first message of that type is, and if it's negative, the first message
+/// with the lowest type less than or equal to its absolute value is.
+///
+/// Returns the type and the length of the message.
+pub fn msgrcv(id: MsgId, mtype: c_long, text: &mut [u8], flags: MsgFlags)
+    -> Result<(c_long, usize)>
+{
+    let mut buf = msgbuf(text.len());
+    let res = unsafe {
+        libc::msgrcv(id.0, buf.as_mut_ptr() as *mut c_void, text.len(), mtype,
+                     flags.bits())
+    };
+    let len = Errno::result(res)? as usize;
+    unsafe {
+        ptr::copy_nonoverlapping(buf[1..].as_ptr() as *const u8, text.as_mut_ptr(), len);
+    }
+
+    Ok((buf[0], len))
+}
+
+/// Commands for [`msgctl`](fn.msgctl.html).
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub enum MsgctlArg<'a> {
+    /// Get the queue's permissions, size and usage.
+    IPC_STAT(&'a mut libc::msqid_ds),
+    /// Change the queue's owner, permissions and maximum size.
+    IPC_SET(&'a libc::msqid_ds),
+    /// Remove the queue, waking up all processes blocked on it.
+    IPC_RMID,
+}
+
+/// Control a message queue (see
+/// [msgctl(2)](http://man7.org/linux/man-pages/man2/msgctl.2.html)).
+pub fn msgctl(id: MsgId, arg: MsgctlArg) -> Result<c_int> {
+    let res = unsafe {
+        match arg {
+            MsgctlArg::IPC_STAT(buf) => libc::msgctl(id.0, libc::IPC_STAT, buf),
+            MsgctlArg::IPC_SET(buf) => {
+                libc::msgctl(id.0, libc::IPC_SET, buf as *const _ as *mut _)
+            },
+            MsgctlArg::IPC_RMID => libc::msgctl(id.0, libc::IPC_RMID, ptr::null_mut()),
+        }
+    };
+
+    Errno::result(res)
+}
@@ -1,16 +1,35 @@
+//! Anonymous files that live in memory.
+//!
+//! A memfd behaves like a regular file, but has no name in the file system.
+//! Passing its file descriptor to another process (for example with
+//! [`ScmRights`](../socket/enum.ControlMessage.html#variant.ScmRights)) and
+//! `mmap`-ing it there shares memory between the processes.  Seals (see
+//! [`SealFlag`](../../fcntl/struct.SealFlag.html)) let the receiver rely on
+//! the file not being resized or modified by the sender.
 use libc;
-use std::os::unix::io::RawFd;
-use crate::Result;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use crate::{Error, Result};
 use crate::errno::Errno;
+use crate::fcntl::{fcntl, FcntlArg, SealFlag};
+use crate::sys::uio::pwrite;
+use crate::unistd::{ftruncate, OwnedFd};
 use std::ffi::CStr;
 
 libc_bitflags!(
+    /// Options for [`memfd_create`](fn.memfd_create.html).
     pub struct MemFdCreateFlag: libc::c_uint {
+        /// Set the close-on-exec flag on the new file descriptor.
         MFD_CLOEXEC;
+        /// Allow adding seals to the file with `fcntl`'s `F_ADD_SEALS`.
         MFD_ALLOW_SEALING;
     }
 );
 
+/// Create an anonymous file that lives in memory (see
+/// [memfd_create(2)](http://man7.org/linux/man-pages/man2/memfd_create.2.html)).
+///
+/// `name` is only used for debugging, and shows up as the target of the
+/// file's symbolic link in `/proc/self/fd`.  The file is initially empty.
 pub fn memfd_create(name: &CStr, flags: MemFdCreateFlag) -> Result<RawFd> {
     let res = unsafe {
         libc::syscall(libc::SYS_memfd_create, name.as_ptr(), flags.bits())
@@ -18,3 +37,80 @@ pub fn memfd_create(name: &CStr, flags: MemFdCreateFlag) -> Result<RawFd> {
 
     Errno::result(res).map(|r| r as RawFd)
 }
+
+/// Creates a sealed, fixed-size memfd.
+///
+/// By default, the file is close-on-exec and sealed with `F_SEAL_SHRINK`,
+/// `F_SEAL_GROW` and `F_SEAL_SEAL`, so its size can never change, but its
+/// contents can still be modified through writable mappings.  Add
+/// `F_SEAL_WRITE` to make it read-only.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::fcntl::SealFlag;
+/// # use nix::sys::memfd::SealedMemFd;
+/// # use std::ffi::CString;
+/// let name = CString::new("config").unwrap();
+/// let fd = SealedMemFd::new(4096)
+///     .seals(SealFlag::F_SEAL_SHRINK | SealFlag::F_SEAL_GROW |
+///            SealFlag::F_SEAL_WRITE | SealFlag::F_SEAL_SEAL)
+///     .create(&name, b"verbose = true\n")
+///     .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SealedMemFd {
+    size: libc::off_t,
+    flags: MemFdCreateFlag,
+    seals: SealFlag,
+}
+
+impl SealedMemFd {
+    /// Create a file of `size` bytes, with the default flags and seals.
+    pub fn new(size: libc::off_t) -> Self {
+        SealedMemFd {
+            size,
+            flags: MemFdCreateFlag::MFD_CLOEXEC,
+            seals: SealFlag::F_SEAL_SHRINK | SealFlag::F_SEAL_GROW |
+                   SealFlag::F_SEAL_SEAL,
+        }
+    }
+
+    /// Set the flags to create the file with.  `MFD_ALLOW_SEALING` is always
+    /// added.
+    pub fn flags(mut self, flags: MemFdCreateFlag) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Set the seals to add once the file has been filled.
+    pub fn seals(mut self, seals: SealFlag) -> Self {
+        self.seals = seals;
+        self
+    }
+
+    /// Create the file, write `contents` at its start, and seal it.  The
+    /// rest of the file is zero-filled.
+    ///
+    /// Fails with `EINVAL` if `contents` doesn't fit in the file.  Nothing is
+    /// leaked on failure.
+    pub fn create(&self, name: &CStr, contents: &[u8]) -> Result<OwnedFd> {
+        if self.size < 0 || contents.len() as u64 > self.size as u64 {
+            return Err(Error::invalid_argument());
+        }
+
+        let fd = memfd_create(name, self.flags | MemFdCreateFlag::MFD_ALLOW_SEALING)?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        self.fill_and_seal(fd.as_raw_fd(), contents)?;
+        Ok(fd)
+    }
+
+    fn fill_and_seal(&self, fd: RawFd, contents: &[u8]) -> Result<()> {
+        ftruncate(fd, self.size)?;
+        let mut written = 0;
+        while written < contents.len() {
+            written += pwrite(fd, &contents[written..], written as libc::off_t)?;
+        }
+        fcntl(fd, FcntlArg::F_ADD_SEALS(self.seals)).map(drop)
+    }
+}
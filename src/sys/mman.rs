@@ -7,7 +7,11 @@ use crate::fcntl::OFlag;
 use libc::{self, c_int, c_void, size_t, off_t};
 #[cfg(not(target_os = "android"))]
 use crate::sys::stat::Mode;
+#[cfg(target_os = "linux")]
+use crate::sys::uio::RemoteIoVec;
 use std::os::unix::io::RawFd;
+#[cfg(any(target_os = "linux", target_os = "netbsd"))]
+use std::ptr;
 
 libc_bitflags!{
     /// Desired memory protection of a memory mapping.
@@ -139,6 +143,30 @@ libc_bitflags!{
     }
 }
 
+#[cfg(any(target_os = "linux", target_os = "netbsd"))]
+libc_bitflags!{
+    /// Options for `mremap()`.
+    pub struct MRemapFlags: c_int {
+        /// Permit the kernel to relocate the mapping to a new virtual address,
+        /// if necessary.
+        #[cfg(target_os = "linux")]
+        MREMAP_MAYMOVE;
+        /// Place the mapping at exactly the address specified in `new_address`.
+        #[cfg(target_os = "linux")]
+        MREMAP_FIXED;
+        /// Leave the old mapping in place, but with no pages, after moving it.
+        /// Requires `MREMAP_MAYMOVE` and Linux 5.7.
+        #[cfg(target_os = "linux")]
+        MREMAP_DONTUNMAP;
+        /// Place the mapping at exactly the address specified in `new_address`.
+        #[cfg(target_os = "netbsd")]
+        MAP_FIXED;
+        /// Duplicate the mapping, leaving the old one in place.
+        #[cfg(target_os = "netbsd")]
+        MAP_REMAPDUP;
+    }
+}
+
 libc_enum!{
     /// Usage information for a range of memory to allow for performance optimizations by the kernel.
     ///
@@ -198,6 +226,21 @@ libc_enum!{
         /// Undo the effect of an earlier `MADV_DONTDUMP`.
         #[cfg(any(target_os = "android", target_os = "linux"))]
         MADV_DODUMP,
+        /// Zero the range in the child after a `fork`, instead of sharing or
+        /// copying it.  Useful for secrets and random number generator state.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        MADV_WIPEONFORK,
+        /// Undo the effect of `MADV_WIPEONFORK`.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        MADV_KEEPONFORK,
+        /// Deactivate the range, making it a preferred target for reclaim
+        /// under memory pressure.  The contents are preserved.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        MADV_COLD,
+        /// Reclaim the range right away, writing it out to swap or its backing
+        /// file if needed.  The contents are preserved.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        MADV_PAGEOUT,
         /// Specify that the application no longer needs the pages in the given range.
         MADV_FREE,
         /// Request that the system not flush the current range to disk unless it needs to.
@@ -257,6 +300,20 @@ libc_bitflags!{
         MCL_CURRENT;
         /// Lock pages which will become mapped into the address space of the process in the future.
         MCL_FUTURE;
+        /// With `MCL_CURRENT` or `MCL_FUTURE`, only lock pages once they are
+        /// faulted in, instead of populating them all at once.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        MCL_ONFAULT;
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+libc_bitflags!{
+    /// Flags for `mlock2`.
+    pub struct MlockFlags: libc::c_uint {
+        /// Only lock pages once they are faulted in, instead of populating
+        /// them all at once.
+        MLOCK_ONFAULT as libc::c_uint;
     }
 }
 
@@ -272,6 +329,22 @@ pub unsafe fn mlock(addr: *const c_void, length: size_t) -> Result<()> {
     Errno::result(libc::mlock(addr, length)).map(drop)
 }
 
+/// Locks all memory pages that contain part of the address range with `length`
+/// bytes starting at `addr`, like [`mlock`](fn.mlock.html) (see
+/// [mlock2(2)](http://man7.org/linux/man-pages/man2/mlock2.2.html)).
+///
+/// With `MLOCK_ONFAULT`, pages are only locked once they are accessed, which
+/// avoids populating a large, sparsely used range.  Requires Linux 4.4.
+///
+/// # Safety
+///
+/// `addr` must meet all the requirements described in the `mlock2(2)` man
+/// page.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub unsafe fn mlock2(addr: *const c_void, length: size_t, flags: MlockFlags) -> Result<()> {
+    Errno::result(libc::mlock2(addr, length, flags.bits() as _)).map(drop)
+}
+
 /// Unlocks all memory pages that contain part of the address range with
 /// `length` bytes starting at `addr`.
 ///
@@ -285,12 +358,8 @@ pub unsafe fn munlock(addr: *const c_void, length: size_t) -> Result<()> {
 
 /// Locks all memory pages mapped into this process' address space.
 ///
-/// Locked pages never move to the swap area.
-///
-/// # Safety
-///
-/// `addr` must meet all the requirements described in the `mlockall(2)` man
-/// page.
+/// Locked pages never move to the swap area.  `flags` must include at least
+/// one of `MCL_CURRENT` and `MCL_FUTURE`.
 pub fn mlockall(flags: MlockAllFlags) -> Result<()> {
     unsafe { Errno::result(libc::mlockall(flags.bits())) }.map(drop)
 }
@@ -325,6 +394,67 @@ pub unsafe fn munmap(addr: *mut c_void, len: size_t) -> Result<()> {
     Errno::result(libc::munmap(addr, len)).map(drop)
 }
 
+/// Expand (or shrink) an existing memory mapping, potentially moving it at
+/// the same time (see
+/// [mremap(2)](http://man7.org/linux/man-pages/man2/mremap.2.html)).
+///
+/// `new_address` is only used with `MREMAP_FIXED` on Linux, or `MAP_FIXED`
+/// on NetBSD; otherwise pass `None`.  On success, returns the address of the
+/// resized mapping, which differs from `addr` if it was moved.
+///
+/// # Safety
+///
+/// See the `mremap(2)` man page for detailed requirements.  In particular,
+/// any pointers into a mapping that was moved are left dangling.
+#[cfg(target_os = "linux")]
+pub unsafe fn mremap(
+    addr: *mut c_void,
+    old_size: size_t,
+    new_size: size_t,
+    flags: MRemapFlags,
+    new_address: Option<*mut c_void>,
+) -> Result<*mut c_void> {
+    let ret = libc::mremap(addr, old_size, new_size, flags.bits(),
+                           new_address.unwrap_or(ptr::null_mut()));
+
+    if ret == libc::MAP_FAILED {
+        Err(Error::Sys(Errno::last()))
+    } else {
+        Ok(ret)
+    }
+}
+
+/// Expand (or shrink) an existing memory mapping, potentially moving it at
+/// the same time (see
+/// [mremap(2)](https://man.netbsd.org/mremap.2)).
+///
+/// `new_address` is only used with `MAP_FIXED`, as a hint otherwise.  On
+/// success, returns the address of the resized mapping, which differs from
+/// `addr` if it was moved.
+///
+/// # Safety
+///
+/// See the `mremap(2)` man page for detailed requirements.  In particular,
+/// any pointers into a mapping that was moved are left dangling.
+#[cfg(target_os = "netbsd")]
+pub unsafe fn mremap(
+    addr: *mut c_void,
+    old_size: size_t,
+    new_size: size_t,
+    flags: MRemapFlags,
+    new_address: Option<*mut c_void>,
+) -> Result<*mut c_void> {
+    let ret = libc::mremap(addr, old_size,
+                           new_address.unwrap_or(ptr::null_mut()), new_size,
+                           flags.bits());
+
+    if ret == libc::MAP_FAILED {
+        Err(Error::Sys(Errno::last()))
+    } else {
+        Ok(ret)
+    }
+}
+
 /// give advice about use of memory
 ///
 /// # Safety
@@ -335,6 +465,30 @@ pub unsafe fn madvise(addr: *mut c_void, length: size_t, advise: MmapAdvise) ->
     Errno::result(libc::madvise(addr, length, advise as i32)).map(drop)
 }
 
+/// Give advice about the use of memory in another process (see
+/// [process_madvise(2)](http://man7.org/linux/man-pages/man2/process_madvise.2.html)).
+///
+/// `pidfd` refers to the target process, as obtained from
+/// [`PidFd`](../pidfd/struct.PidFd.html), and `iov` lists address ranges in
+/// it.  For other processes, only advice that doesn't lose data is permitted,
+/// such as `MADV_WILLNEED`, `MADV_COLD` and `MADV_PAGEOUT`.  This requires the
+/// same permission as `ptrace`-attaching to the process, and `CAP_SYS_NICE`.
+///
+/// Returns the number of bytes advised, which may be less than requested if
+/// an error occurred after some ranges had been processed.  Requires Linux 5.10.
+#[cfg(target_os = "linux")]
+pub fn process_madvise(pidfd: RawFd, iov: &[RemoteIoVec], advice: MmapAdvise)
+    -> Result<usize>
+{
+    let res = unsafe {
+        libc::syscall(libc::SYS_process_madvise, pidfd,
+                      iov.as_ptr() as *const libc::iovec, iov.len(),
+                      advice as c_int, 0 as libc::c_uint)
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
 /// Set protection of memory mapping.
 ///
 /// See [`mprotect(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mprotect.html) for
@@ -374,6 +528,15 @@ pub unsafe fn msync(addr: *mut c_void, length: size_t, flags: MsFlags) -> Result
     Errno::result(libc::msync(addr, length, flags.bits())).map(drop)
 }
 
+/// Create or open a POSIX shared memory object (see
+/// [shm_open(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shm_open.html)).
+///
+/// `name` should start with a `/` and contain no other slashes.  `flag` must
+/// include `O_RDONLY` or `O_RDWR`, and may include `O_CREAT`, `O_EXCL` and
+/// `O_TRUNC`.  A newly created object is empty: set its size with
+/// [`ftruncate`](../../unistd/fn.ftruncate.html) before mapping it with
+/// [`mmap`](fn.mmap.html) and `MAP_SHARED`.  The file descriptor is
+/// close-on-exec.
 #[cfg(not(target_os = "android"))]
 pub fn shm_open<P: ?Sized + NixPath>(name: &P, flag: OFlag, mode: Mode) -> Result<RawFd> {
     let ret = name.with_nix_path(|cstr| {
@@ -390,6 +553,11 @@ pub fn shm_open<P: ?Sized + NixPath>(name: &P, flag: OFlag, mode: Mode) -> Resul
     Errno::result(ret)
 }
 
+/// Remove the name of a POSIX shared memory object (see
+/// [shm_unlink(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/shm_unlink.html)).
+///
+/// The object itself is freed once all file descriptors and mappings of it are
+/// gone.
 #[cfg(not(target_os = "android"))]
 pub fn shm_unlink<P: ?Sized + NixPath>(name: &P) -> Result<()> {
     let ret = name.with_nix_path(|cstr| {
@@ -32,12 +32,18 @@ pub mod eventfd;
 #[macro_use]
 pub mod ioctl;
 
+#[cfg(target_os = "linux")]
+pub mod ipc;
+
 #[cfg(target_os = "linux")]
 pub mod memfd;
 
 #[cfg(not(target_os = "redox"))]
 pub mod mman;
 
+#[cfg(target_os = "linux")]
+pub mod pidfd;
+
 pub mod pthread;
 
 #[cfg(any(target_os = "android",
@@ -55,10 +61,14 @@ pub mod quota;
 #[cfg(any(target_os = "linux"))]
 pub mod reboot;
 
+#[cfg(not(target_os = "redox"))]
+pub mod resource;
+
 #[cfg(not(target_os = "redox"))]
 pub mod select;
 
 #[cfg(any(target_os = "android",
+          target_os = "dragonfly",
           target_os = "freebsd",
           target_os = "ios",
           target_os = "linux",
@@ -103,5 +113,8 @@ pub mod wait;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod inotify;
 
+#[cfg(target_os = "linux")]
+pub mod timer;
+
 #[cfg(target_os = "linux")]
 pub mod timerfd;
@@ -0,0 +1,120 @@
+//! Process file descriptors.
+//!
+//! A `PidFd` refers to a single process.  Unlike a `Pid`, it can't be
+//! recycled: once the process has exited and been reaped, operations on the
+//! `PidFd` fail with `ESRCH` instead of affecting an unrelated process that
+//! happens to reuse the number.
+//!
+//! A `PidFd` becomes readable when its process exits, so it can be monitored
+//! along with other files by [`poll`](../../poll/fn.poll.html) or
+//! [`epoll`](../epoll/index.html).
+//!
+//! For more documentation, please read
+//! [pidfd_open(2)](http://man7.org/linux/man-pages/man2/pidfd_open.2.html).
+//!
+//! # Examples
+//!
+//! Start a child, then kill it and reap it through its `PidFd`.
+//! ```no_run
+//! # use nix::sys::pidfd::PidFd;
+//! # use nix::sys::signal::Signal;
+//! # use nix::sys::wait::{WaitPidFlag, WaitStatus};
+//! # use nix::unistd::{fork, pause, ForkResult};
+//! match fork().unwrap() {
+//!     ForkResult::Child => loop { pause(); },
+//!     ForkResult::Parent { child } => {
+//!         let pidfd = PidFd::open(child).unwrap();
+//!         pidfd.send_signal(Signal::SIGKILL).unwrap();
+//!         let status = pidfd.wait(WaitPidFlag::WEXITED).unwrap();
+//!         assert_eq!(status, WaitStatus::Signaled(child, Signal::SIGKILL, false));
+//!     }
+//! }
+//! ```
+use libc::{self, c_uint};
+use crate::Result;
+use crate::errno::Errno;
+use crate::sys::signal::SigNum;
+use crate::sys::wait::{waitid, Id, WaitPidFlag, WaitStatus};
+use crate::unistd::{OwnedFd, Pid};
+
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::ptr;
+
+/// A file descriptor referring to a process.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct PidFd(OwnedFd);
+
+impl PidFd {
+    /// Obtain a `PidFd` for the process `pid` (see
+    /// [pidfd_open(2)](http://man7.org/linux/man-pages/man2/pidfd_open.2.html)).
+    ///
+    /// The file descriptor is close-on-exec.  Requires Linux 5.3.
+    pub fn open(pid: Pid) -> Result<PidFd> {
+        let res = unsafe {
+            libc::syscall(libc::SYS_pidfd_open, libc::pid_t::from(pid), 0 as c_uint)
+        };
+
+        Errno::result(res).map(|fd| unsafe { PidFd::from_raw_fd(fd as RawFd) })
+    }
+
+    /// Send a signal to the process (see
+    /// [pidfd_send_signal(2)](http://man7.org/linux/man-pages/man2/pidfd_send_signal.2.html)).
+    ///
+    /// If `signal` is `None`, only error checking is performed, which tells
+    /// whether the process is still alive.  Requires Linux 5.1.
+    pub fn send_signal<T: Into<Option<SigNum>>>(&self, signal: T) -> Result<()> {
+        let signal = match signal.into() {
+            Some(s) => s.as_raw(),
+            None => 0,
+        };
+        let res = unsafe {
+            libc::syscall(libc::SYS_pidfd_send_signal, self.as_raw_fd(), signal,
+                          ptr::null::<libc::siginfo_t>(), 0 as c_uint)
+        };
+
+        Errno::result(res).map(drop)
+    }
+
+    /// Duplicate the file descriptor `targetfd` of the process into the
+    /// calling process (see
+    /// [pidfd_getfd(2)](http://man7.org/linux/man-pages/man2/pidfd_getfd.2.html)).
+    ///
+    /// The new file descriptor is close-on-exec.  This requires the same
+    /// permission as `ptrace`-attaching to the process, and Linux 5.6.
+    pub fn get_fd(&self, targetfd: RawFd) -> Result<OwnedFd> {
+        let res = unsafe {
+            libc::syscall(libc::SYS_pidfd_getfd, self.as_raw_fd(), targetfd, 0 as c_uint)
+        };
+
+        Errno::result(res).map(|fd| unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+    }
+
+    /// Wait for a state change of the process, which must be a child of the
+    /// caller (see
+    /// [waitid(2)](http://man7.org/linux/man-pages/man2/waitid.2.html)).
+    ///
+    /// `flags` must include at least one of `WEXITED`, `WSTOPPED` and
+    /// `WCONTINUED`.  Returns `WaitStatus::StillAlive` if `WNOHANG` was given
+    /// and the process hasn't changed state.  Requires Linux 5.4.
+    pub fn wait(&self, flags: WaitPidFlag) -> Result<WaitStatus> {
+        waitid(Id::PidFd(self.as_raw_fd()), flags)
+    }
+}
+
+impl AsRawFd for PidFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for PidFd {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+impl FromRawFd for PidFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> PidFd {
+        PidFd(OwnedFd::from_raw_fd(fd))
+    }
+}
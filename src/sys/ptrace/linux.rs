@@ -16,6 +16,10 @@ pub type AddressType = *mut ::libc::c_void;
             any(target_env = "gnu", target_env = "musl")),
         all(target_arch = "x86", target_env = "gnu"))
 ))]
+use libc::{user_fpregs_struct, user_regs_struct};
+#[cfg(all(target_os = "linux",
+          target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
 use libc::user_regs_struct;
 
 cfg_if! {
@@ -133,7 +137,9 @@ libc_enum!{
         PTRACE_EVENT_EXIT,
         /// STop triggered by a seccomp rule on a tracee.
         PTRACE_EVENT_SECCOMP,
-        // PTRACE_EVENT_STOP not provided by libc because it's defined in glibc 2.26
+        /// Stop induced by `PTRACE_INTERRUPT`, or a group-stop or `PTRACE_LISTEN`
+        /// wakeup of a tracee attached with `PTRACE_SEIZE`.
+        PTRACE_EVENT_STOP,
     }
 }
 
@@ -179,6 +185,13 @@ fn ptrace_peek(request: Request, pid: Pid, addr: AddressType, data: *mut c_void)
     }
 }
 
+fn ptrace_poke(request: Request, pid: Pid, addr: AddressType, data: c_long) -> Result<()> {
+    let ret = unsafe {
+        libc::ptrace(request as RequestType, libc::pid_t::from(pid), addr, data as *mut c_void)
+    };
+    Errno::result(ret).map(drop)
+}
+
 /// Get user registers, as with `ptrace(PTRACE_GETREGS, ...)`
 #[cfg(all(
     target_os = "linux",
@@ -207,6 +220,159 @@ pub fn setregs(pid: Pid, regs: user_regs_struct) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// Get floating point registers, as with `ptrace(PTRACE_GETFPREGS, ...)`
+#[cfg(all(
+    target_os = "linux",
+    any(all(target_arch = "x86_64",
+            any(target_env = "gnu", target_env = "musl")),
+        all(target_arch = "x86", target_env = "gnu"))
+))]
+pub fn getfpregs(pid: Pid) -> Result<user_fpregs_struct> {
+    ptrace_get_data::<user_fpregs_struct>(Request::PTRACE_GETFPREGS, pid)
+}
+
+/// Set floating point registers, as with `ptrace(PTRACE_SETFPREGS, ...)`
+#[cfg(all(
+    target_os = "linux",
+    any(all(target_arch = "x86_64",
+            any(target_env = "gnu", target_env = "musl")),
+        all(target_arch = "x86", target_env = "gnu"))
+))]
+pub fn setfpregs(pid: Pid, regs: user_fpregs_struct) -> Result<()> {
+    let res = unsafe {
+        libc::ptrace(Request::PTRACE_SETFPREGS as RequestType,
+                     libc::pid_t::from(pid),
+                     ptr::null_mut::<c_void>(),
+                     &regs as *const _ as *const c_void)
+    };
+    Errno::result(res).map(drop)
+}
+
+/// A register set for [`getregset`](fn.getregset.html) and
+/// [`setregset`](fn.setregset.html), identified by the type of the ELF core
+/// dump note that holds it.
+///
+/// Which register sets exist and what they contain depends on the
+/// architecture; see `<elf.h>` and the kernel's `include/uapi/linux/elf.h`.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RegisterSet(libc::c_int);
+
+// FIXME: Use the constants from `libc` once it has them on all targets
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+impl RegisterSet {
+    /// General purpose registers, as a `user_regs_struct`.
+    pub const NT_PRSTATUS: RegisterSet = RegisterSet(1);
+    /// Floating point registers, as a `user_fpregs_struct` (or
+    /// `user_fpsimd_struct` on aarch64).
+    pub const NT_PRFPREG: RegisterSet = RegisterSet(2);
+    /// Thread local storage segments, as an array of `struct user_desc`.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub const NT_386_TLS: RegisterSet = RegisterSet(0x200);
+    /// Extended processor state, in the layout of the `XSAVE` instruction.
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub const NT_X86_XSTATE: RegisterSet = RegisterSet(0x202);
+    /// VFP registers.
+    #[cfg(target_arch = "arm")]
+    pub const NT_ARM_VFP: RegisterSet = RegisterSet(0x400);
+    /// The TLS register (`tpidr_el0` on aarch64, `tpidruro` on arm).
+    #[cfg(any(target_arch = "aarch64", target_arch = "arm"))]
+    pub const NT_ARM_TLS: RegisterSet = RegisterSet(0x401);
+    /// Hardware breakpoint registers.
+    #[cfg(target_arch = "aarch64")]
+    pub const NT_ARM_HW_BREAK: RegisterSet = RegisterSet(0x402);
+    /// Hardware watchpoint registers.
+    #[cfg(target_arch = "aarch64")]
+    pub const NT_ARM_HW_WATCH: RegisterSet = RegisterSet(0x403);
+    /// The system call number, which can be changed at a syscall-entry stop.
+    #[cfg(target_arch = "aarch64")]
+    pub const NT_ARM_SYSTEM_CALL: RegisterSet = RegisterSet(0x404);
+    /// Scalable Vector Extension registers.
+    #[cfg(target_arch = "aarch64")]
+    pub const NT_ARM_SVE: RegisterSet = RegisterSet(0x405);
+
+    /// Create a `RegisterSet` from the raw `NT_*` value of its note type.
+    pub fn from_raw(note_type: libc::c_int) -> RegisterSet {
+        RegisterSet(note_type)
+    }
+
+    /// Returns the raw `NT_*` value of the register set's note type.
+    pub fn as_raw(self) -> libc::c_int {
+        self.0
+    }
+}
+
+/// Read a register set, as with `ptrace(PTRACE_GETREGSET, ...)`
+///
+/// The registers are stored in `buf`, and the number of bytes stored is
+/// returned.  This is smaller than `buf` if the register set is, and fails with
+/// `EINVAL` or `ENODEV` if the register set doesn't exist or isn't active.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn getregset(pid: Pid, set: RegisterSet, buf: &mut [u8]) -> Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let res = unsafe {
+        libc::ptrace(Request::PTRACE_GETREGSET as RequestType,
+                     libc::pid_t::from(pid),
+                     set.0 as AddressType,
+                     &mut iov as *mut libc::iovec as *mut c_void)
+    };
+    Errno::result(res).map(|_| iov.iov_len)
+}
+
+/// Write a register set, as with `ptrace(PTRACE_SETREGSET, ...)`
+///
+/// Returns the number of bytes of `buf` that were used, which is smaller than
+/// `buf` if the register set is.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn setregset(pid: Pid, set: RegisterSet, buf: &[u8]) -> Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_ptr() as *mut c_void,
+        iov_len: buf.len(),
+    };
+    let res = unsafe {
+        libc::ptrace(Request::PTRACE_SETREGSET as RequestType,
+                     libc::pid_t::from(pid),
+                     set.0 as AddressType,
+                     &mut iov as *mut libc::iovec as *mut c_void)
+    };
+    Errno::result(res).map(|_| iov.iov_len)
+}
+
+/// Get user registers, as with `ptrace(PTRACE_GETREGSET, ...)` of
+/// `NT_PRSTATUS`, as `PTRACE_GETREGS` doesn't exist on aarch64
+#[cfg(all(target_os = "linux",
+          target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub fn getregs(pid: Pid) -> Result<user_regs_struct> {
+    // Zeroed, as the bytes are viewed as a slice before the kernel fills them
+    let mut regs: user_regs_struct = unsafe { mem::zeroed() };
+    let buf = unsafe {
+        std::slice::from_raw_parts_mut(&mut regs as *mut user_regs_struct as *mut u8,
+                                       mem::size_of::<user_regs_struct>())
+    };
+    let len = getregset(pid, RegisterSet::NT_PRSTATUS, buf)?;
+    if len != mem::size_of::<user_regs_struct>() {
+        return Err(Error::invalid_argument());
+    }
+    Ok(regs)
+}
+
+/// Set user registers, as with `ptrace(PTRACE_SETREGSET, ...)` of
+/// `NT_PRSTATUS`, as `PTRACE_SETREGS` doesn't exist on aarch64
+#[cfg(all(target_os = "linux",
+          target_arch = "aarch64",
+          any(target_env = "gnu", target_env = "musl")))]
+pub fn setregs(pid: Pid, regs: user_regs_struct) -> Result<()> {
+    let buf = unsafe {
+        std::slice::from_raw_parts(&regs as *const user_regs_struct as *const u8,
+                                   mem::size_of::<user_regs_struct>())
+    };
+    setregset(pid, RegisterSet::NT_PRSTATUS, buf).map(drop)
+}
+
 /// Function for ptrace requests that return values from the data field.
 /// Some ptrace get requests populate structs or larger elements than `c_long`
 /// and therefore use the data field to return values. This function handles these
@@ -314,6 +480,11 @@ pub fn attach(pid: Pid) -> Result<()> {
 /// Attach to a running process, as with `ptrace(PTRACE_SEIZE, ...)`
 ///
 /// Attaches to the process specified in pid, making it a tracee of the calling process.
+/// Unlike [`attach`](fn.attach.html), this doesn't stop the process, and
+/// sets the given options at once.  Group-stops of a seized tracee are
+/// reported as `PTRACE_EVENT_STOP` events, which can be handled with
+/// [`listen`](fn.listen.html), and the tracee can be stopped at any time with
+/// [`interrupt`](fn.interrupt.html).
 #[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
 pub fn seize(pid: Pid, options: Options) -> Result<()> {
     unsafe {
@@ -326,6 +497,33 @@ pub fn seize(pid: Pid, options: Options) -> Result<()> {
     }
 }
 
+/// Stop a tracee, as with `ptrace(PTRACE_INTERRUPT, ...)`
+///
+/// Only works on tracees attached with [`seize`](fn.seize.html).  The tracee
+/// enters a `PTRACE_EVENT_STOP` stop, which `waitpid` reports as
+/// `WaitStatus::PtraceEvent(pid, SIGTRAP, PTRACE_EVENT_STOP)`, without any
+/// signal being sent to it.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn interrupt(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(Request::PTRACE_INTERRUPT, pid, ptr::null_mut(), ptr::null_mut()).map(drop)
+    }
+}
+
+/// Restart a tracee in group-stop without resuming it, as with
+/// `ptrace(PTRACE_LISTEN, ...)`
+///
+/// Only works on tracees attached with [`seize`](fn.seize.html).  The tracee
+/// stays stopped as if it weren't traced, but the tracer is notified with a
+/// `PTRACE_EVENT_STOP` when it is resumed by `SIGCONT` or gets a new signal,
+/// so that job control keeps working while the process is being traced.
+#[cfg(all(target_os = "linux", not(any(target_arch = "mips", target_arch = "mips64"))))]
+pub fn listen(pid: Pid) -> Result<()> {
+    unsafe {
+        ptrace_other(Request::PTRACE_LISTEN, pid, ptr::null_mut(), ptr::null_mut()).map(drop)
+    }
+}
+
 /// Detaches the current running process, as with `ptrace(PTRACE_DETACH, ...)`
 ///
 /// Detaches from the process specified by `pid` allowing it to run freely, optionally delivering a
@@ -409,15 +607,23 @@ pub fn read(pid: Pid, addr: AddressType) -> Result<c_long> {
 }
 
 /// Writes a word into the processes memory at the given address
+pub fn write(pid: Pid, addr: AddressType, data: c_long) -> Result<()> {
+    ptrace_poke(Request::PTRACE_POKEDATA, pid, addr, data)
+}
+
+/// Reads a word from the tracee's USER area at the given offset, as with
+/// `ptrace(PTRACE_PEEKUSER, ...)`
 ///
-/// # Safety
+/// The USER area holds the registers and other information about the
+/// process; see `<sys/user.h>` for its layout.
+pub fn read_user(pid: Pid, offset: AddressType) -> Result<c_long> {
+    ptrace_peek(Request::PTRACE_PEEKUSER, pid, offset, ptr::null_mut())
+}
+
+/// Writes a word into the tracee's USER area at the given offset, as with
+/// `ptrace(PTRACE_POKEUSER, ...)`
 ///
-/// The `data` argument is passed directly to `ptrace(2)`.  Read that man page
-/// for guidance.
-pub unsafe fn write(
-    pid: Pid,
-    addr: AddressType,
-    data: *mut c_void) -> Result<()>
-{
-    ptrace_other(Request::PTRACE_POKEDATA, pid, addr, data).map(drop)
+/// Some fields can't be written and fail with `EIO`.
+pub fn write_user(pid: Pid, offset: AddressType, data: c_long) -> Result<()> {
+    ptrace_poke(Request::PTRACE_POKEUSER, pid, offset, data)
 }
@@ -0,0 +1,123 @@
+//! Resource usage of processes and threads
+use libc::{self, c_int, c_long};
+use std::mem;
+
+use crate::Result;
+use crate::errno::Errno;
+use crate::sys::time::TimeVal;
+
+libc_enum! {
+    /// Whose resource usage `getrusage` should report.
+    #[repr(i32)]
+    pub enum UsageWho {
+        /// The calling process, including all of its threads.
+        RUSAGE_SELF,
+        /// All children of the calling process that have terminated and been
+        /// waited for.
+        RUSAGE_CHILDREN,
+        /// The calling thread.
+        #[cfg(any(target_os = "freebsd", target_os = "linux",
+                  target_os = "openbsd"))]
+        RUSAGE_THREAD,
+    }
+}
+
+/// Resource usage statistics, as returned by [`getrusage`](fn.getrusage.html)
+/// and [`wait4`](../wait/fn.wait4.html).
+///
+/// Not all fields are maintained by all platforms; unmaintained ones are 0.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct Rusage(libc::rusage);
+
+impl Rusage {
+    /// Time spent executing in user mode.
+    pub fn user_time(&self) -> TimeVal {
+        TimeVal::from(self.0.ru_utime)
+    }
+
+    /// Time spent executing in kernel mode.
+    pub fn system_time(&self) -> TimeVal {
+        TimeVal::from(self.0.ru_stime)
+    }
+
+    /// The maximum resident set size, in kilobytes on most platforms and in
+    /// bytes on Apple platforms.
+    pub fn max_rss(&self) -> c_long {
+        self.0.ru_maxrss
+    }
+
+    /// Number of page faults serviced without any I/O.
+    pub fn minor_page_faults(&self) -> c_long {
+        self.0.ru_minflt
+    }
+
+    /// Number of page faults that required I/O.
+    pub fn major_page_faults(&self) -> c_long {
+        self.0.ru_majflt
+    }
+
+    /// Number of times the process was swapped out of main memory.
+    pub fn swaps(&self) -> c_long {
+        self.0.ru_nswap
+    }
+
+    /// Number of times the file system had to perform input.
+    pub fn block_reads(&self) -> c_long {
+        self.0.ru_inblock
+    }
+
+    /// Number of times the file system had to perform output.
+    pub fn block_writes(&self) -> c_long {
+        self.0.ru_oublock
+    }
+
+    /// Number of IPC messages sent.
+    pub fn ipc_sends(&self) -> c_long {
+        self.0.ru_msgsnd
+    }
+
+    /// Number of IPC messages received.
+    pub fn ipc_receives(&self) -> c_long {
+        self.0.ru_msgrcv
+    }
+
+    /// Number of signals received.
+    pub fn signals(&self) -> c_long {
+        self.0.ru_nsignals
+    }
+
+    /// Number of times a context switch was voluntarily invoked, usually
+    /// because the process blocked waiting for a resource.
+    pub fn voluntary_context_switches(&self) -> c_long {
+        self.0.ru_nvcsw
+    }
+
+    /// Number of times a context switch was imposed by the kernel, usually
+    /// because the process's time slice expired.
+    pub fn involuntary_context_switches(&self) -> c_long {
+        self.0.ru_nivcsw
+    }
+}
+
+impl AsRef<libc::rusage> for Rusage {
+    fn as_ref(&self) -> &libc::rusage {
+        &self.0
+    }
+}
+
+impl From<libc::rusage> for Rusage {
+    fn from(rusage: libc::rusage) -> Rusage {
+        Rusage(rusage)
+    }
+}
+
+/// Get the resource usage of the calling process, its children or the
+/// calling thread (see
+/// [getrusage(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/getrusage.html)).
+pub fn getrusage(who: UsageWho) -> Result<Rusage> {
+    let mut rusage = mem::MaybeUninit::<libc::rusage>::uninit();
+    let res = unsafe { libc::getrusage(who as c_int, rusage.as_mut_ptr()) };
+
+    Errno::result(res).map(|_| Rusage(unsafe { rusage.assume_init() }))
+}
@@ -24,16 +24,34 @@ impl FdSet {
         }
     }
 
+    /// Adds `fd` to the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fd` is negative or not less than `FD_SETSIZE`.
     pub fn insert(&mut self, fd: RawFd) {
+        assert_fd_valid(fd);
         unsafe { libc::FD_SET(fd, &mut self.0) };
     }
 
+    /// Removes `fd` from the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fd` is negative or not less than `FD_SETSIZE`.
     pub fn remove(&mut self, fd: RawFd) {
+        assert_fd_valid(fd);
         unsafe { libc::FD_CLR(fd, &mut self.0) };
     }
 
-    pub fn contains(&mut self, fd: RawFd) -> bool {
-        unsafe { libc::FD_ISSET(fd, &mut self.0) }
+    /// Tests whether `fd` is in the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fd` is negative or not less than `FD_SETSIZE`.
+    pub fn contains(&self, fd: RawFd) -> bool {
+        assert_fd_valid(fd);
+        unsafe { libc::FD_ISSET(fd, &self.0) }
     }
 
     pub fn clear(&mut self) {
@@ -59,7 +77,7 @@ impl FdSet {
     /// ```
     ///
     /// [`select`]: fn.select.html
-    pub fn highest(&mut self) -> Option<RawFd> {
+    pub fn highest(&self) -> Option<RawFd> {
         self.fds(None).next_back()
     }
 
@@ -81,7 +99,7 @@ impl FdSet {
     /// assert_eq!(fds, vec![4, 9]);
     /// ```
     #[inline]
-    pub fn fds(&mut self, highest: Option<RawFd>) -> Fds {
+    pub fn fds(&self, highest: Option<RawFd>) -> Fds {
         Fds {
             set: self,
             range: 0..highest.map(|h| h as usize + 1).unwrap_or(FD_SETSIZE),
@@ -89,6 +107,13 @@ impl FdSet {
     }
 }
 
+// The FD_* macros don't check their argument, and would access memory
+// outside of the set
+fn assert_fd_valid(fd: RawFd) {
+    assert!(fd >= 0 && (fd as usize) < FD_SETSIZE,
+            "fd {} is out of range for an FdSet", fd);
+}
+
 impl Default for FdSet {
     fn default() -> Self {
         Self::new()
@@ -98,7 +123,7 @@ impl Default for FdSet {
 /// Iterator over `FdSet`.
 #[derive(Debug)]
 pub struct Fds<'a> {
-    set: &'a mut FdSet,
+    set: &'a FdSet,
     range: Range<usize>,
 }
 
@@ -305,6 +330,18 @@ mod tests {
         }
     }
 
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn fdset_insert_out_of_range() {
+        FdSet::new().insert(FD_SETSIZE as RawFd);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn fdset_contains_negative() {
+        FdSet::new().contains(-1);
+    }
+
     #[test]
     fn fdset_clear() {
         let mut fd_set = FdSet::new();
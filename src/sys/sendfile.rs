@@ -1,3 +1,40 @@
+//! Zero-copy transfer of file data to a socket.
+//!
+//! Every supported platform has a `sendfile` function, but its signature
+//! follows the underlying system call:
+//!
+//! * On Linux and Android, data goes from `in_fd` to `out_fd`, which may be
+//!   any file descriptor.  The optional offset is read and updated in place,
+//!   and the number of bytes sent is returned.  `sendfile64` takes a 64-bit
+//!   offset on 32-bit Linux.
+//! * On FreeBSD, macOS and iOS, data goes from `in_fd` to the stream socket
+//!   `out_sock`.  The offset is passed by value, headers and trailers can be
+//!   sent around the file data, and the number of bytes sent is returned
+//!   even when an error occurs.
+//!
+//! [`send_file`](fn.send_file.html) has the same signature on all of these
+//! platforms and on DragonFly, and sends optional headers and trailers around
+//! the file data everywhere.
+//!
+//! # Examples
+//!
+//! Send a whole file to a socket, on any of these platforms:
+//!
+//! ```no_run
+//! # use nix::sys::sendfile::send_file;
+//! # use std::os::unix::io::AsRawFd;
+//! # use std::os::unix::net::UnixStream;
+//! # let file = tempfile::tempfile().unwrap();
+//! # let (sock, _) = UnixStream::pair().unwrap();
+//! let len = file.metadata().unwrap().len() as usize;
+//! let mut sent = 0;
+//! while sent < len {
+//!     let (res, count) = send_file(sock.as_raw_fd(), file.as_raw_fd(),
+//!                                  sent as libc::off_t, len - sent, None, None);
+//!     res.unwrap();
+//!     sent += count;
+//! }
+//! ```
 use cfg_if::cfg_if;
 use std::os::unix::io::RawFd;
 use std::ptr;
@@ -33,8 +70,28 @@ pub fn sendfile(
     Errno::result(ret).map(|r| r as usize)
 }
 
+/// Like [`sendfile`](fn.sendfile.html), but with a 64-bit offset.
+///
+/// This allows sending files larger than 2 GiB on 32-bit platforms.
+///
+/// For more information, see [the sendfile(2) man page.](http://man7.org/linux/man-pages/man2/sendfile.2.html)
+#[cfg(target_os = "linux")]
+pub fn sendfile64(
+    out_fd: RawFd,
+    in_fd: RawFd,
+    offset: Option<&mut libc::off64_t>,
+    count: usize,
+) -> Result<usize> {
+    let offset = offset
+        .map(|offset| offset as *mut _)
+        .unwrap_or(ptr::null_mut());
+    let ret = unsafe { libc::sendfile64(out_fd, in_fd, offset, count) };
+    Errno::result(ret).map(|r| r as usize)
+}
+
 cfg_if! {
-    if #[cfg(any(target_os = "freebsd",
+    if #[cfg(any(target_os = "dragonfly",
+                 target_os = "freebsd",
                  target_os = "ios",
                  target_os = "macos"))] {
         use crate::sys::uio::IoVec;
@@ -201,3 +258,120 @@ cfg_if! {
         }
     }
 }
+
+/// Send optional `headers`, then up to `count` bytes from `in_fd` starting at
+/// `offset`, and then optional `trailers` to the stream socket `out_sock`.
+///
+/// Unlike [`sendfile`](fn.sendfile.html), this has the same signature on
+/// every platform.  If `count` is 0, data is sent until the end of the file.
+/// `offset` and `count` do not apply to headers or trailers.
+///
+/// Returns a `Result` and the number of bytes sent, including headers and
+/// trailers, which may be non-zero even if an error occurs.  As with `write`,
+/// fewer bytes than requested may be sent, for instance if `out_sock` is
+/// non-blocking.  The pieces are sent in order, so the count tells how far it
+/// got.
+///
+/// `in_fd` must describe a regular file.  On Linux and Android, the headers
+/// and trailers are sent with separate `sendmsg` calls, with `MSG_MORE` set on
+/// the headers so that they can share packets with the file data.
+pub fn send_file(
+    out_sock: RawFd,
+    in_fd: RawFd,
+    offset: off_t,
+    count: usize,
+    headers: Option<&[&[u8]]>,
+    trailers: Option<&[&[u8]]>,
+) -> (Result<()>, usize) {
+    cfg_if! {
+        if #[cfg(any(target_os = "android", target_os = "linux"))] {
+            use crate::sys::socket::MsgFlags;
+            use crate::sys::stat::fstat;
+            use std::cmp;
+
+            let count = if count == 0 {
+                match fstat(in_fd) {
+                    Ok(stat) => cmp::max(stat.st_size - offset, 0) as usize,
+                    Err(e) => return (Err(e), 0),
+                }
+            } else {
+                count
+            };
+            let headers = headers.unwrap_or(&[]);
+            let trailers = trailers.unwrap_or(&[]);
+            let mut sent = 0;
+
+            let hdr_len = headers.iter().map(|h| h.len()).sum();
+            if hdr_len > 0 {
+                let flags = if count > 0 || !trailers.is_empty() {
+                    MsgFlags::MSG_MORE
+                } else {
+                    MsgFlags::empty()
+                };
+                match send_slices(out_sock, headers, flags) {
+                    Ok(n) => {
+                        sent += n;
+                        if n < hdr_len {
+                            return (Ok(()), sent);
+                        }
+                    },
+                    Err(e) => return (Err(e), sent),
+                }
+            }
+            if count > 0 {
+                let mut offset = offset;
+                match sendfile(out_sock, in_fd, Some(&mut offset), count) {
+                    Ok(n) => {
+                        sent += n;
+                        if n < count {
+                            return (Ok(()), sent);
+                        }
+                    },
+                    Err(e) => return (Err(e), sent),
+                }
+            }
+            if trailers.iter().any(|t| !t.is_empty()) {
+                match send_slices(out_sock, trailers, MsgFlags::empty()) {
+                    Ok(n) => sent += n,
+                    Err(e) => return (Err(e), sent),
+                }
+            }
+            (Ok(()), sent)
+        } else if #[cfg(target_os = "freebsd")] {
+            let (res, sent) = sendfile(in_fd, out_sock, offset, Some(count), headers, trailers,
+                                       SfFlags::empty(), 0);
+            (res, sent as usize)
+        } else if #[cfg(target_os = "dragonfly")] {
+            let mut sent: off_t = 0;
+            let hdtr = headers.or(trailers).map(|_| SendfileHeaderTrailer::new(headers, trailers));
+            let hdtr_ptr = hdtr.as_ref().map_or(ptr::null(), |s| &s.0 as *const libc::sf_hdtr);
+            let res = unsafe {
+                libc::sendfile(in_fd, out_sock, offset, count, hdtr_ptr as *mut libc::sf_hdtr,
+                               &mut sent as *mut off_t, 0)
+            };
+            (Errno::result(res).and(Ok(())), sent as usize)
+        } else {
+            // Apple counts the headers in the limit, but not the trailers
+            let count = if count == 0 {
+                0
+            } else {
+                count + headers.map_or(0, |h| h.iter().map(|b| b.len()).sum())
+            };
+            let (res, sent) = sendfile(in_fd, out_sock, offset, Some(count as off_t), headers,
+                                       trailers);
+            (res, sent as usize)
+        }
+    }
+}
+
+// Send `slices` with a single `sendmsg`, and return how many bytes were sent
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn send_slices(sock: RawFd, slices: &[&[u8]], flags: crate::sys::socket::MsgFlags)
+    -> Result<usize>
+{
+    use crate::sys::socket::sendmsg;
+    use crate::sys::uio::IoVec;
+
+    let iov: Vec<_> = slices.iter().map(|s| IoVec::from_slice(s)).collect();
+    sendmsg(sock, &iov, &[], flags, None)
+}
@@ -6,13 +6,31 @@
 use crate::{Error, Result};
 use crate::errno::Errno;
 use crate::unistd::Pid;
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+use crate::unistd::Uid;
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+use crate::sys::time::TimeSpec;
 use std::convert::TryFrom;
 use std::mem;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
-#[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
-use std::os::unix::io::RawFd;
+#[cfg(not(target_os = "redox"))]
+use crate::unistd::Pipe;
+#[cfg(not(target_os = "redox"))]
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::ptr;
+#[cfg(not(target_os = "redox"))]
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
 
 #[cfg(not(any(target_os = "openbsd", target_os = "redox")))]
 pub use self::sigevent::*;
@@ -356,6 +374,110 @@ pub const SIGIOT : Signal = SIGABRT;
 pub const SIGPOLL : Signal = SIGIO;
 pub const SIGUNUSED : Signal = SIGSYS;
 
+/// A signal number: either one of the standard signals of
+/// [`Signal`](enum.Signal.html), or on Linux, a real-time signal.
+///
+/// Functions taking a signal accept both a `Signal` and a `SigNum`.
+/// Real-time signals are numbered from `SIGRTMIN` to `SIGRTMAX`, which are
+/// only known at runtime, because the C library reserves some of them for its
+/// own use.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::signal::*;
+/// let mut mask = SigSet::empty();
+/// mask.add(SIGUSR1);
+/// # #[cfg(any(target_os = "android", target_os = "linux"))]
+/// mask.add(SigNum::rt(1).unwrap());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SigNum(libc::c_int);
+
+impl SigNum {
+    /// Get the signal number `signum`.
+    ///
+    /// Fails with `EINVAL` if it is neither a standard signal nor a
+    /// real-time signal.
+    pub fn from_raw(signum: libc::c_int) -> Result<SigNum> {
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            if libc::SIGRTMIN() <= signum && signum <= libc::SIGRTMAX() {
+                return Ok(SigNum(signum));
+            }
+        }
+        Signal::try_from(signum).map(SigNum::from)
+    }
+
+    /// The raw signal number
+    pub fn as_raw(self) -> libc::c_int {
+        self.0
+    }
+
+    /// The first real-time signal available to applications
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn rtmin() -> SigNum {
+        SigNum(libc::SIGRTMIN())
+    }
+
+    /// The last real-time signal
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn rtmax() -> SigNum {
+        SigNum(libc::SIGRTMAX())
+    }
+
+    /// The real-time signal `SIGRTMIN + n`.
+    ///
+    /// Fails with `EINVAL` if it is past `SIGRTMAX`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn rt(n: libc::c_int) -> Result<SigNum> {
+        let signum = libc::SIGRTMIN().checked_add(n)
+            .ok_or_else(Error::invalid_argument)?;
+        if n < 0 || signum > libc::SIGRTMAX() {
+            return Err(Error::invalid_argument());
+        }
+        Ok(SigNum(signum))
+    }
+
+    /// Whether this is a real-time signal
+    pub fn is_realtime(self) -> bool {
+        Signal::try_from(self.0).is_err()
+    }
+}
+
+impl From<Signal> for SigNum {
+    fn from(signal: Signal) -> SigNum {
+        SigNum(signal as libc::c_int)
+    }
+}
+
+impl From<Signal> for Option<SigNum> {
+    fn from(signal: Signal) -> Option<SigNum> {
+        Some(SigNum::from(signal))
+    }
+}
+
+impl TryFrom<SigNum> for Signal {
+    type Error = Error;
+
+    /// Fails with `EINVAL` for real-time signals.
+    fn try_from(signum: SigNum) -> Result<Signal> {
+        Signal::try_from(signum.0)
+    }
+}
+
+impl fmt::Display for SigNum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match Signal::try_from(self.0) {
+            Ok(signal) => f.write_str(signal.as_ref()),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            Err(_) => write!(f, "SIGRTMIN+{}", self.0 - libc::SIGRTMIN()),
+            #[cfg(not(any(target_os = "android", target_os = "linux")))]
+            Err(_) => write!(f, "{}", self.0),
+        }
+    }
+}
+
 #[cfg(not(target_os = "redox"))]
 type SaFlags_t = libc::c_int;
 #[cfg(target_os = "redox")]
@@ -366,6 +488,9 @@ libc_bitflags!{
         SA_NOCLDSTOP;
         SA_NOCLDWAIT;
         SA_NODEFER;
+        /// Run the handler on the alternate signal stack installed with
+        /// [`sigaltstack`](fn.sigaltstack.html), if there is one.  This lets a
+        /// handler for `SIGSEGV` run even when the fault was a stack overflow.
         SA_ONSTACK;
         SA_RESETHAND;
         SA_RESTART;
@@ -403,20 +528,20 @@ impl SigSet {
         unsafe{ SigSet { sigset: sigset.assume_init() } }
     }
 
-    pub fn add(&mut self, signal: Signal) {
-        unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, signal as libc::c_int) };
+    pub fn add<S: Into<SigNum>>(&mut self, signal: S) {
+        unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, signal.into().0) };
     }
 
     pub fn clear(&mut self) {
         unsafe { libc::sigemptyset(&mut self.sigset as *mut libc::sigset_t) };
     }
 
-    pub fn remove(&mut self, signal: Signal) {
-        unsafe { libc::sigdelset(&mut self.sigset as *mut libc::sigset_t, signal as libc::c_int) };
+    pub fn remove<S: Into<SigNum>>(&mut self, signal: S) {
+        unsafe { libc::sigdelset(&mut self.sigset as *mut libc::sigset_t, signal.into().0) };
     }
 
-    pub fn contains(&self, signal: Signal) -> bool {
-        let res = unsafe { libc::sigismember(&self.sigset as *const libc::sigset_t, signal as libc::c_int) };
+    pub fn contains<S: Into<SigNum>>(&self, signal: S) -> bool {
+        let res = unsafe { libc::sigismember(&self.sigset as *const libc::sigset_t, signal.into().0) };
 
         match res {
             1 => true,
@@ -431,9 +556,21 @@ impl SigSet {
                 self.add(signal);
             }
         }
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        {
+            for signum in libc::SIGRTMIN()..=libc::SIGRTMAX() {
+                if other.contains(SigNum(signum)) {
+                    self.add(SigNum(signum));
+                }
+            }
+        }
     }
 
     /// Gets the currently blocked (masked) set of signals for the calling thread.
+    ///
+    /// The `thread_*` methods use `pthread_sigmask`, and only affect the
+    /// calling thread.  Threads spawned afterwards inherit its mask.  See
+    /// [`pthread_sigmask`](fn.pthread_sigmask.html).
     pub fn thread_get_mask() -> Result<SigSet> {
         let mut oldmask = mem::MaybeUninit::uninit();
         do_pthread_sigmask(SigmaskHow::SIG_SETMASK, None, Some(oldmask.as_mut_ptr()))?;
@@ -455,7 +592,8 @@ impl SigSet {
         pthread_sigmask(SigmaskHow::SIG_UNBLOCK, Some(self), None)
     }
 
-    /// Sets the set of signals as the signal mask, and returns the old mask.
+    /// Updates the signal mask for the calling thread with the set of signals,
+    /// as specified by `how`, and returns the old mask.
     pub fn thread_swap_mask(&self, how: SigmaskHow) -> Result<SigSet> {
         let mut oldmask = mem::MaybeUninit::uninit();
         do_pthread_sigmask(how, Some(self), Some(oldmask.as_mut_ptr()))?;
@@ -464,15 +602,63 @@ impl SigSet {
 
     /// Suspends execution of the calling thread until one of the signals in the
     /// signal mask becomes pending, and returns the accepted signal.
+    ///
+    /// Fails with `EINVAL` if the accepted signal is a real-time signal, which
+    /// is consumed nonetheless.  Use [`wait_num`](#method.wait_num) for sets
+    /// that contain real-time signals.
     #[cfg(not(target_os = "redox"))] // RedoxFS does not yet support sigwait
     pub fn wait(&self) -> Result<Signal> {
+        self.wait_num().and_then(Signal::try_from)
+    }
+
+    /// Like [`wait`](#method.wait), but the accepted signal may also be a
+    /// real-time signal.
+    #[cfg(not(target_os = "redox"))]
+    pub fn wait_num(&self) -> Result<SigNum> {
         let mut signum = mem::MaybeUninit::uninit();
         let res = unsafe { libc::sigwait(&self.sigset as *const libc::sigset_t, signum.as_mut_ptr()) };
 
         Errno::result(res).map(|_| unsafe {
-            Signal::try_from(signum.assume_init()).unwrap()
+            SigNum(signum.assume_init())
         })
     }
+
+    /// Suspends execution of the calling thread until one of the signals in the
+    /// signal mask becomes pending, and returns information about the accepted
+    /// signal.  See [sigwaitinfo(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sigwaitinfo.html).
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "netbsd"))]
+    pub fn wait_info(&self) -> Result<SigInfo> {
+        let mut info = mem::MaybeUninit::uninit();
+        let res = unsafe { libc::sigwaitinfo(&self.sigset as *const libc::sigset_t, info.as_mut_ptr()) };
+
+        Errno::result(res).map(|_| unsafe { SigInfo(info.assume_init()) })
+    }
+
+    /// Like [`wait_info`](#method.wait_info), but gives up if no signal of the
+    /// mask becomes pending within `timeout`, in which case `Ok(None)` is
+    /// returned.  See [sigtimedwait(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sigtimedwait.html).
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "netbsd"))]
+    pub fn wait_timeout(&self, timeout: TimeSpec) -> Result<Option<SigInfo>> {
+        let mut info = mem::MaybeUninit::uninit();
+        let res = unsafe {
+            libc::sigtimedwait(&self.sigset as *const libc::sigset_t,
+                               info.as_mut_ptr(),
+                               timeout.as_ref() as *const libc::timespec)
+        };
+
+        match Errno::result(res) {
+            Ok(_) => Ok(Some(unsafe { SigInfo(info.assume_init()) })),
+            Err(Error::Sys(Errno::EAGAIN)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
 }
 
 impl AsRef<libc::sigset_t> for SigSet {
@@ -481,20 +667,282 @@ impl AsRef<libc::sigset_t> for SigSet {
     }
 }
 
+/// Information about a signal, such as its number and where it came from.
+///
+/// Which of the fields are meaningful depends on the signal and on how it
+/// was generated, as described by [`si_code`](#method.si_code).  See
+/// [sigaction(2)](http://man7.org/linux/man-pages/man2/sigaction.2.html) for
+/// the details.
+#[cfg(not(target_os = "redox"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct SigInfo(libc::siginfo_t);
+
+#[cfg(not(target_os = "redox"))]
+impl SigInfo {
+    /// The signal number
+    pub fn si_signo(&self) -> libc::c_int {
+        self.0.si_signo
+    }
+
+    /// Why the signal was sent, like `libc::SI_USER` when it was sent by
+    /// `kill` or `libc::SI_QUEUE` when it was sent by `sigqueue`
+    pub fn si_code(&self) -> libc::c_int {
+        self.0.si_code
+    }
+
+    /// The process that sent the signal, for signals sent by another process
+    /// or by a child process changing state
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    pub fn si_pid(&self) -> Pid {
+        Pid::from_raw(unsafe { self.0.si_pid() })
+    }
+
+    /// The real user ID of the process that sent the signal, for signals sent
+    /// by another process or by a child process changing state
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    pub fn si_uid(&self) -> Uid {
+        Uid::from_raw(unsafe { self.0.si_uid() })
+    }
+
+    /// The value sent along with the signal, for signals sent by
+    /// [`sigqueue`](fn.sigqueue.html) or by a timer
+    pub fn si_value(&self) -> SigVal {
+        SigVal(unsafe { self.0.si_value() })
+    }
+
+    /// The address that caused the fault, for `SIGSEGV`, `SIGBUS`, `SIGILL`
+    /// and `SIGFPE`
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd",
+              target_os = "openbsd"))]
+    pub fn si_addr(&self) -> *mut libc::c_void {
+        let addr = unsafe { self.0.si_addr() };
+        // OpenBSD declares the address as a `*mut c_char`
+        #[cfg(target_os = "openbsd")]
+        let addr = addr as *mut libc::c_void;
+        addr
+    }
+
+    /// The exit status or the signal of the child process, for `SIGCHLD`,
+    /// depending on [`si_code`](#method.si_code)
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd"))]
+    pub fn si_status(&self) -> libc::c_int {
+        unsafe { self.0.si_status() }
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl AsRef<libc::siginfo_t> for SigInfo {
+    fn as_ref(&self) -> &libc::siginfo_t {
+        &self.0
+    }
+}
+
+/// Converts the `siginfo_t` passed to a `SigHandler::SigAction` handler.
+#[cfg(not(target_os = "redox"))]
+impl From<libc::siginfo_t> for SigInfo {
+    fn from(info: libc::siginfo_t) -> Self {
+        SigInfo(info)
+    }
+}
+
+/// A value sent along with a signal by [`sigqueue`](fn.sigqueue.html), or by
+/// a timer.
+///
+/// It holds either an integer or a pointer.  The receiver must know which one
+/// the sender used.
+#[cfg(not(target_os = "redox"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SigVal(libc::sigval);
+
+#[cfg(not(target_os = "redox"))]
+impl SigVal {
+    /// A value holding the integer `value`
+    pub fn int(value: libc::c_int) -> SigVal {
+        // libc represents the union by its pointer member.  The integer member
+        // starts at the same address.
+        let mut sigval = libc::sigval { sival_ptr: ptr::null_mut() };
+        unsafe { ptr::write(&mut sigval as *mut libc::sigval as *mut libc::c_int, value) };
+        SigVal(sigval)
+    }
+
+    /// A value holding the pointer `value`
+    pub fn ptr(value: *mut libc::c_void) -> SigVal {
+        SigVal(libc::sigval { sival_ptr: value })
+    }
+
+    /// The value as an integer
+    pub fn as_int(&self) -> libc::c_int {
+        unsafe { ptr::read(&self.0 as *const libc::sigval as *const libc::c_int) }
+    }
+
+    /// The value as a pointer
+    pub fn as_ptr(&self) -> *mut libc::c_void {
+        self.0.sival_ptr
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl AsRef<libc::sigval> for SigVal {
+    fn as_ref(&self) -> &libc::sigval {
+        &self.0
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+libc_bitflags!{
+    /// Flags describing the state of an alternate signal stack
+    pub struct SsFlags: libc::c_int {
+        /// The thread is currently running on the alternate signal stack.
+        /// Only ever returned by [`sigaltstack`](fn.sigaltstack.html).
+        SS_ONSTACK;
+        /// The alternate signal stack is disabled.
+        SS_DISABLE;
+    }
+}
+
+/// An alternate stack for signal handlers, as set by
+/// [`sigaltstack`](fn.sigaltstack.html).
+///
+/// Handlers installed with `SaFlags::SA_ONSTACK` run on this stack instead
+/// of the normal stack of the thread.  `libc::SIGSTKSZ` is a suitable size
+/// for most handlers.
+#[cfg(not(target_os = "redox"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SigStack(libc::stack_t);
+
+#[cfg(not(target_os = "redox"))]
+impl SigStack {
+    /// A signal stack of `size` bytes starting at `sp`.
+    pub fn new(sp: *mut libc::c_void, size: usize) -> SigStack {
+        SigStack(libc::stack_t {
+            ss_sp: sp,
+            ss_size: size,
+            ss_flags: 0,
+        })
+    }
+
+    /// A signal stack with `SS_DISABLE` set, which removes the alternate
+    /// signal stack when passed to [`sigaltstack`](fn.sigaltstack.html).
+    pub fn disabled() -> SigStack {
+        SigStack(libc::stack_t {
+            ss_sp: ptr::null_mut(),
+            ss_size: 0,
+            ss_flags: libc::SS_DISABLE,
+        })
+    }
+
+    /// The lowest address of the stack
+    pub fn sp(&self) -> *mut libc::c_void {
+        self.0.ss_sp
+    }
+
+    /// The size of the stack in bytes
+    pub fn size(&self) -> usize {
+        self.0.ss_size
+    }
+
+    /// The state of the stack
+    pub fn flags(&self) -> SsFlags {
+        SsFlags::from_bits_truncate(self.0.ss_flags)
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl AsRef<libc::stack_t> for SigStack {
+    fn as_ref(&self) -> &libc::stack_t {
+        &self.0
+    }
+}
+
 /// A signal handler.
 #[allow(unknown_lints)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy)]
 pub enum SigHandler {
     /// Default signal handling.
     SigDfl,
     /// Request that the signal be ignored.
     SigIgn,
     /// Use the given signal-catching function, which takes in the signal.
-    Handler(extern fn(libc::c_int)),
+    Handler(extern "C" fn(libc::c_int)),
     /// Use the given signal-catching function, which takes in the signal, information about how
     /// the signal was generated, and a pointer to the threads `ucontext_t`.
     #[cfg(not(target_os = "redox"))]
-    SigAction(extern fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void))
+    SigAction(extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void)),
+    /// Like `SigAction`, but the information about the signal is passed as a
+    /// [`SigInfo`](struct.SigInfo.html).
+    ///
+    /// [`SigAction::handler`](struct.SigAction.html#method.handler) can't tell
+    /// this variant apart from `SigAction`, and returns the latter.
+    #[cfg(not(target_os = "redox"))]
+    SigInfoAction(extern "C" fn(libc::c_int, &SigInfo, *mut libc::c_void)),
+}
+
+// Function pointers taking references don't implement these traits on older
+// compilers, so compare handlers by their address instead.
+impl SigHandler {
+    fn key(&self) -> (u8, usize) {
+        match *self {
+            SigHandler::SigDfl => (0, 0),
+            SigHandler::SigIgn => (1, 0),
+            SigHandler::Handler(f) => (2, f as usize),
+            #[cfg(not(target_os = "redox"))]
+            SigHandler::SigAction(f) => (3, f as usize),
+            #[cfg(not(target_os = "redox"))]
+            SigHandler::SigInfoAction(f) => (4, f as usize),
+        }
+    }
+}
+
+impl PartialEq for SigHandler {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SigHandler {}
+
+impl Hash for SigHandler {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl fmt::Debug for SigHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (name, addr) = match *self {
+            SigHandler::SigDfl => return f.write_str("SigDfl"),
+            SigHandler::SigIgn => return f.write_str("SigIgn"),
+            SigHandler::Handler(h) => ("Handler", h as usize),
+            #[cfg(not(target_os = "redox"))]
+            SigHandler::SigAction(h) => ("SigAction", h as usize),
+            #[cfg(not(target_os = "redox"))]
+            SigHandler::SigInfoAction(h) => ("SigInfoAction", h as usize),
+        };
+        f.debug_tuple(name).field(&(addr as *const libc::c_void)).finish()
+    }
 }
 
 /// Action to take on receipt of a signal. Corresponds to `sigaction`.
@@ -507,7 +955,7 @@ impl SigAction {
     /// Creates a new action.
     ///
     /// The `SA_SIGINFO` bit in the `flags` argument is ignored (it will be set only if `handler`
-    /// is the `SigAction` variant). `mask` specifies other signals to block during execution of
+    /// is the `SigAction` or `SigInfoAction` variant). `mask` specifies other signals to block during execution of
     /// the signal-catching function.
     pub fn new(handler: SigHandler, flags: SaFlags, mask: SigSet) -> SigAction {
         #[cfg(target_os = "redox")]
@@ -515,7 +963,7 @@ impl SigAction {
             (*p).sa_handler = match handler {
                 SigHandler::SigDfl => libc::SIG_DFL,
                 SigHandler::SigIgn => libc::SIG_IGN,
-                SigHandler::Handler(f) => f as *const extern fn(libc::c_int) as usize,
+                SigHandler::Handler(f) => f as *const extern "C" fn(libc::c_int) as usize,
             };
         }
 
@@ -524,8 +972,9 @@ impl SigAction {
             (*p).sa_sigaction = match handler {
                 SigHandler::SigDfl => libc::SIG_DFL,
                 SigHandler::SigIgn => libc::SIG_IGN,
-                SigHandler::Handler(f) => f as *const extern fn(libc::c_int) as usize,
-                SigHandler::SigAction(f) => f as *const extern fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) as usize,
+                SigHandler::Handler(f) => f as *const extern "C" fn(libc::c_int) as usize,
+                SigHandler::SigAction(f) => f as *const extern "C" fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) as usize,
+                SigHandler::SigInfoAction(f) => f as *const extern "C" fn(libc::c_int, &SigInfo, *mut libc::c_void) as usize,
             };
         }
 
@@ -535,7 +984,8 @@ impl SigAction {
             install_sig(p, handler);
             (*p).sa_flags = match handler {
                 #[cfg(not(target_os = "redox"))]
-                SigHandler::SigAction(_) => (flags | SaFlags::SA_SIGINFO).bits(),
+                SigHandler::SigAction(_) | SigHandler::SigInfoAction(_) =>
+                    (flags | SaFlags::SA_SIGINFO).bits(),
                 _ => (flags - SaFlags::SA_SIGINFO).bits(),
             };
             (*p).sa_mask = mask.sigset;
@@ -588,10 +1038,10 @@ impl SigAction {
 /// Signal handlers may be called at any point during execution, which limits what is safe to do in
 /// the body of the signal-catching function. Be certain to only make syscalls that are explicitly
 /// marked safe for signal handlers and only share global data using atomics.
-pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigAction> {
+pub unsafe fn sigaction<S: Into<SigNum>>(signal: S, sigaction: &SigAction) -> Result<SigAction> {
     let mut oldact = mem::MaybeUninit::<libc::sigaction>::uninit();
 
-    let res = libc::sigaction(signal as libc::c_int,
+    let res = libc::sigaction(signal.into().0,
                               &sigaction.sigaction as *const libc::sigaction,
                               oldact.as_mut_ptr());
 
@@ -630,7 +1080,7 @@ pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigActi
 ///    static ref SIGNALED: AtomicBool = AtomicBool::new(false);
 /// }
 ///
-/// extern fn handle_sigint(signal: libc::c_int) {
+/// extern "C" fn handle_sigint(signal: libc::c_int) {
 ///     let signal = Signal::try_from(signal).unwrap();
 ///     SIGNALED.store(signal == Signal::SIGINT, Ordering::Relaxed);
 /// }
@@ -644,7 +1094,8 @@ pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigActi
 /// # Errors
 ///
 /// Returns [`Error::UnsupportedOperation`] if `handler` is
-/// [`SigAction`][SigActionStruct]. Use [`sigaction`][SigActionFn] instead.
+/// [`SigAction`][SigActionStruct] or `SigInfoAction`. Use
+/// [`sigaction`][SigActionFn] instead.
 ///
 /// `signal` also returns any error from `libc::signal`, such as when an attempt
 /// is made to catch a signal that cannot be caught or to ignore a signal that
@@ -653,14 +1104,15 @@ pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigActi
 /// [`Error::UnsupportedOperation`]: ../../enum.Error.html#variant.UnsupportedOperation
 /// [SigActionStruct]: struct.SigAction.html
 /// [sigactionFn]: fn.sigaction.html
-pub unsafe fn signal(signal: Signal, handler: SigHandler) -> Result<SigHandler> {
-    let signal = signal as libc::c_int;
+pub unsafe fn signal<S: Into<SigNum>>(signal: S, handler: SigHandler) -> Result<SigHandler> {
+    let signal = signal.into().0;
     let res = match handler {
         SigHandler::SigDfl => libc::signal(signal, libc::SIG_DFL),
         SigHandler::SigIgn => libc::signal(signal, libc::SIG_IGN),
         SigHandler::Handler(handler) => libc::signal(signal, handler as libc::sighandler_t),
         #[cfg(not(target_os = "redox"))]
-        SigHandler::SigAction(_) => return Err(Error::UnsupportedOperation),
+        SigHandler::SigAction(_) | SigHandler::SigInfoAction(_) =>
+            return Err(Error::UnsupportedOperation),
     };
     Errno::result(res).map(|oldhandler| {
         match oldhandler {
@@ -712,6 +1164,49 @@ pub fn pthread_sigmask(how: SigmaskHow,
     do_pthread_sigmask(how, set, oldset.map(|os| &mut os.sigset as *mut _ ))
 }
 
+/// Replace the signal mask of the calling thread with `mask` and suspend it
+/// until a signal is caught [(see
+/// sigsuspend(2))](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sigsuspend.html).
+///
+/// Returns after a signal handler has run, with the previous signal mask
+/// restored.  Changing the mask and waiting happen atomically, so a signal
+/// that is blocked while checking a flag set by its handler, and unblocked
+/// by `mask`, can't be missed.  See also
+/// [`pause`](../../unistd/fn.pause.html).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::signal::*;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// static GOT_SIGUSR1: AtomicBool = AtomicBool::new(false);
+/// extern "C" fn handle_sigusr1(_: libc::c_int) {
+///     GOT_SIGUSR1.store(true, Ordering::SeqCst);
+/// }
+///
+/// let act = SigAction::new(SigHandler::Handler(handle_sigusr1), SaFlags::empty(),
+///                          SigSet::empty());
+/// unsafe { sigaction(Signal::SIGUSR1, &act) }.unwrap();
+/// let mut block = SigSet::empty();
+/// block.add(Signal::SIGUSR1);
+/// let mut mask = block.thread_swap_mask(SigmaskHow::SIG_BLOCK).unwrap();
+/// mask.remove(Signal::SIGUSR1);
+/// while !GOT_SIGUSR1.load(Ordering::SeqCst) {
+///     sigsuspend(&mask);
+/// }
+/// ```
+#[cfg(not(target_os = "redox"))]
+pub fn sigsuspend(mask: &SigSet) {
+    // libc only declares sigsuspend on Linux and Android in the versions
+    // nix supports
+    extern "C" {
+        fn sigsuspend(mask: *const libc::sigset_t) -> libc::c_int;
+    }
+
+    // sigsuspend always fails with EINTR
+    unsafe { sigsuspend(&mask.sigset as *const libc::sigset_t) };
+}
+
 /// Examine and change blocked signals.
 ///
 /// For more informations see the [`sigprocmask` man
@@ -733,10 +1228,67 @@ pub fn sigprocmask(how: SigmaskHow, set: Option<&SigSet>, oldset: Option<&mut Si
     Errno::result(res).map(drop)
 }
 
-pub fn kill<T: Into<Option<Signal>>>(pid: Pid, signal: T) -> Result<()> {
-    let res = unsafe { libc::kill(pid.into(),
+/// The processes that [`kill`](fn.kill.html) sends a signal to.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KillTarget {
+    /// The process with the given ID.  A `Pid` converts into this variant,
+    /// but its raw value is passed to `kill` unchanged, so zero or negative
+    /// values keep their special meaning.
+    Process(Pid),
+    /// Every process in the given process group, as with
+    /// [`killpg`](fn.killpg.html).
+    ProcessGroup(Pid),
+    /// Every process in the process group of the caller
+    CurrentProcessGroup,
+    /// Every process that the caller may send signals to, except for some
+    /// system processes such as `init`
+    All,
+}
+
+impl From<Pid> for KillTarget {
+    fn from(pid: Pid) -> Self {
+        KillTarget::Process(pid)
+    }
+}
+
+/// Send a signal to a process or a group of processes [(see
+/// kill(2))](http://pubs.opengroup.org/onlinepubs/9699919799/functions/kill.html).
+///
+/// `target` is either a `Pid` or a [`KillTarget`](enum.KillTarget.html).
+/// If `signal` is `None`, `kill` will only perform error checking and won't
+/// send any signal.  Use [`kill_num`](fn.kill_num.html) to send a real-time
+/// signal.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::signal::{kill, KillTarget, Signal};
+/// # use nix::unistd::{getpgrp, Pid};
+/// kill(Pid::from_raw(1234), Signal::SIGTERM).unwrap();
+/// kill(KillTarget::ProcessGroup(getpgrp()), Signal::SIGHUP).unwrap();
+/// ```
+pub fn kill<P, T>(target: P, signal: T) -> Result<()>
+    where P: Into<KillTarget>,
+          T: Into<Option<Signal>>
+{
+    kill_num(target, signal.into().map(SigNum::from))
+}
+
+/// Like [`kill`](fn.kill.html), but the signal may also be a real-time
+/// signal.
+pub fn kill_num<P, T>(target: P, signal: T) -> Result<()>
+    where P: Into<KillTarget>,
+          T: Into<Option<SigNum>>
+{
+    let pid = match target.into() {
+        KillTarget::Process(pid) => pid.into(),
+        KillTarget::ProcessGroup(pgrp) => return killpg_num(pgrp, signal),
+        KillTarget::CurrentProcessGroup => 0,
+        KillTarget::All => -1,
+    };
+    let res = unsafe { libc::kill(pid,
                                   match signal.into() {
-                                      Some(s) => s as libc::c_int,
+                                      Some(s) => s.0,
                                       None => 0,
                                   }) };
 
@@ -748,23 +1300,245 @@ pub fn kill<T: Into<Option<Signal>>>(pid: Pid, signal: T) -> Result<()> {
 ///
 /// If `pgrp` less then or equal 1, the behavior is platform-specific.
 /// If `signal` is `None`, `killpg` will only preform error checking and won't
-/// send any signal.
+/// send any signal.  Use [`killpg_num`](fn.killpg_num.html) to send a
+/// real-time signal.
 pub fn killpg<T: Into<Option<Signal>>>(pgrp: Pid, signal: T) -> Result<()> {
+    killpg_num(pgrp, signal.into().map(SigNum::from))
+}
+
+/// Like [`killpg`](fn.killpg.html), but the signal may also be a real-time
+/// signal.
+pub fn killpg_num<T: Into<Option<SigNum>>>(pgrp: Pid, signal: T) -> Result<()> {
     let res = unsafe { libc::killpg(pgrp.into(),
                                   match signal.into() {
-                                      Some(s) => s as libc::c_int,
+                                      Some(s) => s.0,
                                       None => 0,
                                   }) };
 
     Errno::result(res).map(drop)
 }
 
-pub fn raise(signal: Signal) -> Result<()> {
-    let res = unsafe { libc::raise(signal as libc::c_int) };
+pub fn raise<S: Into<SigNum>>(signal: S) -> Result<()> {
+    let res = unsafe { libc::raise(signal.into().0) };
 
     Errno::result(res).map(drop)
 }
 
+/// Send a signal along with a value to a process [(see
+/// sigqueue(3))](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sigqueue.html).
+///
+/// The receiver gets the value in the `si_value` field of the signal's
+/// [`SigInfo`](struct.SigInfo.html), as returned by
+/// [`SigSet::wait_info`](struct.SigSet.html#method.wait_info) or passed to a
+/// `SigHandler::SigAction` handler.  Unlike other signals, real-time signals
+/// sent this way are queued, so none of them is lost.
+///
+/// If `signal` is `None`, `sigqueue` will only perform error checking and
+/// won't send any signal.
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+pub fn sigqueue<T: Into<Option<SigNum>>>(pid: Pid, signal: T, value: SigVal) -> Result<()> {
+    // libc only declares sigqueue in versions that need Rust 1.63, newer than
+    // nix's minimum supported Rust version
+    extern "C" {
+        fn sigqueue(pid: libc::pid_t, sig: libc::c_int, value: libc::sigval) -> libc::c_int;
+    }
+
+    let res = unsafe { sigqueue(pid.into(),
+                                match signal.into() {
+                                    Some(s) => s.0,
+                                    None => 0,
+                                },
+                                value.0) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set and/or get the alternate signal stack of the calling thread [(see
+/// sigaltstack(2))](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sigaltstack.html).
+///
+/// If `ss` is `Some`, it becomes the new alternate signal stack, or the
+/// alternate signal stack is removed if it was created with
+/// [`SigStack::disabled`](struct.SigStack.html#method.disabled).  Returns the
+/// previous alternate signal stack.
+///
+/// Only handlers installed with `SaFlags::SA_ONSTACK` run on the alternate
+/// signal stack.  Fails with `ENOMEM` if the stack is smaller than
+/// `libc::MINSIGSTKSZ`, and with `EPERM` if the thread is currently running
+/// on the alternate signal stack.
+///
+/// # Safety
+///
+/// The memory of the new stack must stay valid, and must not be used for
+/// anything else, until the stack is replaced or removed, or the thread
+/// exits.
+///
+/// # Examples
+///
+/// Catch the `SIGSEGV` caused by a stack overflow:
+///
+/// ```no_run
+/// # use nix::sys::signal::*;
+/// extern "C" fn handle_sigsegv(_: libc::c_int) {
+///     // Only async-signal-safe functions may be called here
+///     unsafe { libc::_exit(1) };
+/// }
+///
+/// let stack = Box::leak(vec![0u8; libc::SIGSTKSZ].into_boxed_slice());
+/// let ss = SigStack::new(stack.as_mut_ptr() as *mut libc::c_void, stack.len());
+/// unsafe { sigaltstack(Some(&ss)) }.unwrap();
+/// let act = SigAction::new(SigHandler::Handler(handle_sigsegv),
+///                          SaFlags::SA_ONSTACK, SigSet::empty());
+/// unsafe { sigaction(Signal::SIGSEGV, &act) }.unwrap();
+/// ```
+#[cfg(not(target_os = "redox"))]
+pub unsafe fn sigaltstack(ss: Option<&SigStack>) -> Result<SigStack> {
+    let mut oss = mem::MaybeUninit::<libc::stack_t>::uninit();
+    let res = libc::sigaltstack(ss.map_or(ptr::null(), |s| &s.0 as *const libc::stack_t),
+                                oss.as_mut_ptr());
+
+    Errno::result(res).map(|_| SigStack(oss.assume_init()))
+}
+
+/// The write end of the pipe of the `SignalPipe`, or -1 if there is none
+#[cfg(not(target_os = "redox"))]
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+/// How many handlers may be using the write end of the pipe
+#[cfg(not(target_os = "redox"))]
+static SIGNAL_PIPE_WRITERS: AtomicUsize = AtomicUsize::new(0);
+/// Whether a `SignalPipe` exists
+#[cfg(not(target_os = "redox"))]
+static SIGNAL_PIPE_BUSY: AtomicBool = AtomicBool::new(false);
+
+#[cfg(not(target_os = "redox"))]
+extern "C" fn signal_pipe_handler(signal: libc::c_int) {
+    // Announce ourselves before loading the file descriptor, so that a
+    // `SignalPipe` being dropped waits for us before closing it
+    SIGNAL_PIPE_WRITERS.fetch_add(1, Ordering::SeqCst);
+    let fd = SIGNAL_PIPE.load(Ordering::SeqCst);
+    if fd >= 0 {
+        // write may clobber errno, which the interrupted code could be using
+        let errno = crate::errno::errno();
+        let byte = signal as u8;
+        unsafe { libc::write(fd, &byte as *const u8 as *const libc::c_void, 1) };
+        crate::errno::set_errno(errno);
+    }
+    SIGNAL_PIPE_WRITERS.fetch_sub(1, Ordering::SeqCst);
+}
+
+/// Receives signals through a pipe, using the "self-pipe trick".
+///
+/// Signals added with [`add`](#method.add) get a handler which writes their
+/// number to a non-blocking pipe, whose read end can be watched with `poll`,
+/// `select`, `epoll` or `kqueue` together with other file descriptors.  This
+/// works on every platform, unlike [`SignalFd`](../signalfd/struct.SignalFd.html)
+/// which is only available on Linux.
+///
+/// Only one `SignalPipe` can exist at a time.  When it is dropped, the
+/// previous actions of its signals are restored and the pipe is closed, once
+/// handlers running on other threads are done with it.  If the pipe fills up,
+/// further signals are dropped until it is read from.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::signal::{SignalPipe, Signal};
+/// # use nix::poll::{poll, PollFd, PollFlags};
+/// # use std::os::unix::io::AsRawFd;
+/// let mut pipe = SignalPipe::new().unwrap();
+/// unsafe { pipe.add(Signal::SIGINT) }.unwrap();
+/// let mut fds = [PollFd::new(pipe.as_raw_fd(), PollFlags::POLLIN)];
+/// poll(&mut fds, -1).unwrap();
+/// while let Some(signal) = pipe.read().unwrap() {
+///     println!("got {}", signal);
+/// }
+/// ```
+#[cfg(not(target_os = "redox"))]
+#[derive(Debug)]
+pub struct SignalPipe {
+    pipe: Pipe,
+    actions: Vec<(SigNum, SigAction)>,
+}
+
+#[cfg(not(target_os = "redox"))]
+impl SignalPipe {
+    /// Creates the pipe, without catching any signal yet.
+    ///
+    /// Fails with `EBUSY` if another `SignalPipe` exists.
+    pub fn new() -> Result<SignalPipe> {
+        use crate::fcntl::OFlag;
+
+        if SIGNAL_PIPE_BUSY.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(Error::Sys(Errno::EBUSY));
+        }
+        let pipe = match Pipe::new(OFlag::O_CLOEXEC | OFlag::O_NONBLOCK) {
+            Ok(pipe) => pipe,
+            Err(e) => {
+                SIGNAL_PIPE_BUSY.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+        SIGNAL_PIPE.store(pipe.write.as_raw_fd(), Ordering::SeqCst);
+        Ok(SignalPipe { pipe, actions: Vec::new() })
+    }
+
+    /// Starts catching `signal`, and sending it through the pipe.
+    ///
+    /// # Safety
+    ///
+    /// This replaces the action of `signal` for the whole process, like
+    /// [`sigaction`](fn.sigaction.html).
+    pub unsafe fn add<S: Into<SigNum>>(&mut self, signal: S) -> Result<()> {
+        let signal = signal.into();
+        let act = SigAction::new(SigHandler::Handler(signal_pipe_handler),
+                                 SaFlags::SA_RESTART, SigSet::empty());
+        let old = sigaction(signal, &act)?;
+        if !self.actions.iter().any(|&(s, _)| s == signal) {
+            self.actions.push((signal, old));
+        }
+        Ok(())
+    }
+
+    /// Reads the next signal from the pipe, or returns `None` if there is
+    /// none.
+    pub fn read(&self) -> Result<Option<SigNum>> {
+        let mut byte = 0u8;
+        match crate::unistd::read(self.as_raw_fd(), std::slice::from_mut(&mut byte)) {
+            Ok(_) => Ok(Some(SigNum(libc::c_int::from(byte)))),
+            Err(Error::Sys(Errno::EAGAIN)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl AsRawFd for SignalPipe {
+    /// The read end of the pipe
+    fn as_raw_fd(&self) -> RawFd {
+        self.pipe.read.as_raw_fd()
+    }
+}
+
+#[cfg(not(target_os = "redox"))]
+impl Drop for SignalPipe {
+    fn drop(&mut self) {
+        for &(signal, ref old) in self.actions.iter().rev() {
+            let _ = unsafe { sigaction(signal, old) };
+        }
+        // A handler that loaded the file descriptor before it was detached
+        // may still write to it, so wait for it before the pipe is closed
+        SIGNAL_PIPE.store(-1, Ordering::SeqCst);
+        while SIGNAL_PIPE_WRITERS.load(Ordering::SeqCst) > 0 {
+            std::thread::yield_now();
+        }
+        SIGNAL_PIPE_BUSY.store(false, Ordering::SeqCst);
+    }
+}
+
 
 #[cfg(target_os = "freebsd")]
 pub type type_of_thread_id = libc::lwpid_t;
@@ -784,6 +1558,8 @@ pub enum SigevNotify {
     /// value in `si_value` will be present in the `si_value` field of the
     /// `siginfo_t` structure of the queued signal.
     SigevSignal { signal: Signal, si_value: libc::intptr_t },
+    /// Like `SigevSignal`, but the signal may also be a real-time signal.
+    SigevSignalNum { signal: SigNum, si_value: libc::intptr_t },
     // Note: SIGEV_THREAD is not implemented because libc::sigevent does not
     // expose a way to set the union members needed by SIGEV_THREAD.
     /// A new `kevent` is posted to the kqueue `kq`.  The `kevent`'s `udata`
@@ -796,6 +1572,10 @@ pub enum SigevNotify {
     #[cfg(any(target_os = "freebsd", target_os = "linux"))]
     SigevThreadId { signal: Signal, thread_id: type_of_thread_id,
                     si_value: libc::intptr_t },
+    /// Like `SigevThreadId`, but the signal may also be a real-time signal.
+    #[cfg(any(target_os = "freebsd", target_os = "linux"))]
+    SigevThreadIdNum { signal: SigNum, thread_id: type_of_thread_id,
+                       si_value: libc::intptr_t },
 }
 
 #[cfg(not(any(target_os = "openbsd", target_os = "redox")))]
@@ -833,30 +1613,39 @@ mod sigevent {
             sev.sigev_notify = match sigev_notify {
                 SigevNotify::SigevNone => libc::SIGEV_NONE,
                 SigevNotify::SigevSignal{..} => libc::SIGEV_SIGNAL,
+                SigevNotify::SigevSignalNum{..} => libc::SIGEV_SIGNAL,
                 #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
                 SigevNotify::SigevKevent{..} => libc::SIGEV_KEVENT,
                 #[cfg(target_os = "freebsd")]
-                SigevNotify::SigevThreadId{..} => libc::SIGEV_THREAD_ID,
+                SigevNotify::SigevThreadId{..} |
+                SigevNotify::SigevThreadIdNum{..} => libc::SIGEV_THREAD_ID,
                 #[cfg(all(target_os = "linux", target_env = "gnu", not(target_arch = "mips")))]
-                SigevNotify::SigevThreadId{..} => libc::SIGEV_THREAD_ID,
+                SigevNotify::SigevThreadId{..} |
+                SigevNotify::SigevThreadIdNum{..} => libc::SIGEV_THREAD_ID,
                 #[cfg(any(all(target_os = "linux", target_env = "musl"), target_arch = "mips"))]
-                SigevNotify::SigevThreadId{..} => 4  // No SIGEV_THREAD_ID defined
+                SigevNotify::SigevThreadId{..} |
+                SigevNotify::SigevThreadIdNum{..} => 4  // No SIGEV_THREAD_ID defined
             };
             sev.sigev_signo = match sigev_notify {
                 SigevNotify::SigevSignal{ signal, .. } => signal as libc::c_int,
+                SigevNotify::SigevSignalNum{ signal, .. } => signal.as_raw(),
                 #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
                 SigevNotify::SigevKevent{ kq, ..} => kq,
                 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
                 SigevNotify::SigevThreadId{ signal, .. } => signal as libc::c_int,
+                #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+                SigevNotify::SigevThreadIdNum{ signal, .. } => signal.as_raw(),
                 _ => 0
             };
             sev.sigev_value.sival_ptr = match sigev_notify {
                 SigevNotify::SigevNone => ptr::null_mut::<libc::c_void>(),
-                SigevNotify::SigevSignal{ si_value, .. } => si_value as *mut libc::c_void,
+                SigevNotify::SigevSignal{ si_value, .. } |
+                SigevNotify::SigevSignalNum{ si_value, .. } => si_value as *mut libc::c_void,
                 #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
                 SigevNotify::SigevKevent{ udata, .. } => udata as *mut libc::c_void,
                 #[cfg(any(target_os = "freebsd", target_os = "linux"))]
-                SigevNotify::SigevThreadId{ si_value, .. } => si_value as *mut libc::c_void,
+                SigevNotify::SigevThreadId{ si_value, .. } |
+                SigevNotify::SigevThreadIdNum{ si_value, .. } => si_value as *mut libc::c_void,
             };
             SigEvent::set_tid(&mut sev, &sigev_notify);
             SigEvent{sigevent: sev}
@@ -865,7 +1654,8 @@ mod sigevent {
         #[cfg(any(target_os = "freebsd", target_os = "linux"))]
         fn set_tid(sev: &mut libc::sigevent, sigev_notify: &SigevNotify) {
             sev.sigev_notify_thread_id = match *sigev_notify {
-                SigevNotify::SigevThreadId { thread_id, .. } => thread_id,
+                SigevNotify::SigevThreadId { thread_id, .. } |
+                SigevNotify::SigevThreadIdNum { thread_id, .. } => thread_id,
                 _ => 0 as type_of_thread_id
             };
         }
@@ -977,6 +1767,24 @@ mod tests {
         }).join().unwrap();
     }
 
+    #[test]
+    #[cfg(not(target_os = "redox"))]
+    fn test_thread_signal_block_is_per_thread() {
+        thread::spawn(|| {
+            let mut mask = SigSet::empty();
+            mask.add(SIGUSR2);
+            mask.thread_unblock().unwrap();
+
+            let blocker = thread::spawn(move || {
+                mask.thread_block().unwrap();
+                assert!(SigSet::thread_get_mask().unwrap().contains(SIGUSR2));
+            });
+            blocker.join().unwrap();
+
+            assert!(!SigSet::thread_get_mask().unwrap().contains(SIGUSR2));
+        }).join().unwrap();
+    }
+
     #[test]
     #[cfg(not(target_os = "redox"))]
     fn test_thread_signal_unblock() {
@@ -1017,8 +1825,8 @@ mod tests {
     #[cfg(not(target_os = "redox"))]
     fn test_sigaction() {
         thread::spawn(|| {
-            extern fn test_sigaction_handler(_: libc::c_int) {}
-            extern fn test_sigaction_action(_: libc::c_int,
+            extern "C" fn test_sigaction_handler(_: libc::c_int) {}
+            extern "C" fn test_sigaction_action(_: libc::c_int,
                 _: *mut libc::siginfo_t, _: *mut libc::c_void) {}
 
             let handler_sig = SigHandler::Handler(test_sigaction_handler);
@@ -1064,4 +1872,107 @@ mod tests {
             assert_eq!(mask.wait().unwrap(), SIGUSR1);
         }).join().unwrap();
     }
+
+    #[test]
+    #[cfg(any(target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "netbsd"))]
+    fn test_sigwaitinfo() {
+        thread::spawn(|| {
+            let mut mask = SigSet::empty();
+            mask.add(SIGUSR1);
+            mask.add(SIGUSR2);
+            mask.thread_block().unwrap();
+
+            raise(SIGUSR2).unwrap();
+            let info = mask.wait_info().unwrap();
+            assert_eq!(info.si_signo(), SIGUSR2 as libc::c_int);
+            assert!(info.si_code() <= 0, "not sent by a process");
+            #[cfg(not(target_os = "netbsd"))]
+            {
+                assert_eq!(info.si_pid(), crate::unistd::getpid());
+                assert_eq!(info.si_uid(), crate::unistd::getuid());
+            }
+        }).join().unwrap();
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_signum_rt() {
+        let rtmin = SigNum::rtmin();
+        assert_eq!(SigNum::rt(0).unwrap(), rtmin);
+        assert!(SigNum::rt(-1).is_err());
+        assert!(SigNum::rt(1000).is_err());
+        let n = SigNum::rtmax().as_raw() - rtmin.as_raw();
+        assert_eq!(SigNum::rt(n).unwrap(), SigNum::rtmax());
+
+        let rt1 = SigNum::rt(1).unwrap();
+        assert!(rt1.is_realtime());
+        assert_eq!(rt1.to_string(), "SIGRTMIN+1");
+        assert_eq!(SigNum::from_raw(rt1.as_raw()).unwrap(), rt1);
+        assert!(Signal::try_from(rt1).is_err());
+
+        let usr1 = SigNum::from(SIGUSR1);
+        assert!(!usr1.is_realtime());
+        assert_eq!(usr1.to_string(), "SIGUSR1");
+        assert_eq!(Signal::try_from(usr1).unwrap(), SIGUSR1);
+        assert!(SigNum::from_raw(0).is_err());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_rt_signal_wait() {
+        thread::spawn(|| {
+            let rt = SigNum::rt(1).unwrap();
+            let mut mask = SigSet::empty();
+            mask.add(rt);
+            assert!(mask.contains(rt));
+            assert!(!mask.contains(SigNum::rt(2).unwrap()));
+            mask.thread_block().unwrap();
+
+            // Real-time signals are queued
+            raise(rt).unwrap();
+            raise(rt).unwrap();
+            for _ in 0..2 {
+                assert_eq!(mask.wait_info().unwrap().si_signo(), rt.as_raw());
+            }
+        }).join().unwrap();
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_sigwait_rtmin() {
+        thread::spawn(|| {
+            let rtmin = SigNum::rtmin();
+            let mut mask = SigSet::empty();
+            mask.add(rtmin);
+            mask.thread_block().unwrap();
+
+            raise(rtmin).unwrap();
+            assert_eq!(mask.wait_num().unwrap(), rtmin);
+        }).join().unwrap();
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "netbsd"))]
+    fn test_sigtimedwait() {
+        use crate::sys::time::TimeValLike;
+
+        thread::spawn(|| {
+            let mut mask = SigSet::empty();
+            mask.add(SIGUSR1);
+            mask.thread_block().unwrap();
+
+            let timeout = TimeSpec::milliseconds(10);
+            assert!(mask.wait_timeout(timeout).unwrap().is_none());
+            raise(SIGUSR1).unwrap();
+            let info = mask.wait_timeout(timeout).unwrap().unwrap();
+            assert_eq!(info.si_signo(), SIGUSR1 as libc::c_int);
+        }).join().unwrap();
+    }
 }
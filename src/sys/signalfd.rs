@@ -16,24 +16,33 @@
 //! Please note that signal discarding is not specific to `signalfd`, but also happens with regular
 //! signal handlers.
 use libc;
-use crate::unistd;
+use crate::unistd::OwnedFd;
 use crate::{Error, Result};
 use crate::errno::Errno;
 pub use crate::sys::signal::{self, SigSet};
 pub use libc::signalfd_siginfo as siginfo;
 
-use std::os::unix::io::{RawFd, AsRawFd};
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd};
 use std::mem;
 
 
 libc_bitflags!{
+    /// Flags for [`signalfd`](fn.signalfd.html) and
+    /// [`SignalFd::with_flags`](struct.SignalFd.html#method.with_flags)
     pub struct SfdFlags: libc::c_int {
+        /// Set the `O_NONBLOCK` flag on the file descriptor, so that reading
+        /// it fails with `EAGAIN` instead of blocking when no signal is
+        /// pending.
         SFD_NONBLOCK;
+        /// Set the close-on-exec flag on the file descriptor.
         SFD_CLOEXEC;
     }
 }
 
+/// Passed as the `fd` argument of [`signalfd`](fn.signalfd.html) to create a
+/// new file descriptor.
 pub const SIGNALFD_NEW: RawFd = -1;
+/// The size of a [`siginfo`](type.siginfo.html) as read from a signalfd.
 pub const SIGNALFD_SIGINFO_SIZE: usize = 128;
 
 /// Creates a new file descriptor for reading signals.
@@ -72,41 +81,58 @@ pub fn signalfd(fd: RawFd, mask: &SigSet, flags: SfdFlags) -> Result<RawFd> {
 ///
 /// match sfd.read_signal() {
 ///     // we caught a signal
-///     Ok(Some(sig)) => (),
+///     Ok(Some(sig)) => {
+///         assert_eq!(sig.ssi_signo, signal::SIGUSR1 as u32);
+///         println!("SIGUSR1 sent by process {}", sig.ssi_pid);
+///     },
 ///     // there were no signals waiting (only happens when the SFD_NONBLOCK flag is set,
 ///     // otherwise the read_signal call blocks)
 ///     Ok(None) => (),
 ///     Err(err) => (), // some error happend
 /// }
 /// ```
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-pub struct SignalFd(RawFd);
+///
+/// Since it is a file descriptor, a `SignalFd` can be monitored along with
+/// other files by [`poll`](../../poll/fn.poll.html) or
+/// [`epoll`](../epoll/index.html), which report it as readable when a signal
+/// of its mask is pending.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct SignalFd(OwnedFd);
 
 impl SignalFd {
+    /// Create a `SignalFd` accepting the signals of `mask`.
     pub fn new(mask: &SigSet) -> Result<SignalFd> {
         Self::with_flags(mask, SfdFlags::empty())
     }
 
+    /// Create a `SignalFd` accepting the signals of `mask`, with the given
+    /// flags.
     pub fn with_flags(mask: &SigSet, flags: SfdFlags) -> Result<SignalFd> {
         let fd = signalfd(SIGNALFD_NEW, mask, flags)?;
 
-        Ok(SignalFd(fd))
+        Ok(SignalFd(unsafe { OwnedFd::from_raw_fd(fd) }))
     }
 
+    /// Replace the set of signals accepted by this `SignalFd`.
     pub fn set_mask(&mut self, mask: &SigSet) -> Result<()> {
-        signalfd(self.0, mask, SfdFlags::empty()).map(drop)
+        signalfd(self.as_raw_fd(), mask, SfdFlags::empty()).map(drop)
     }
 
+    /// Dequeue a pending signal.
+    ///
+    /// Blocks until a signal of the mask is pending, unless the `SignalFd`
+    /// was created with `SFD_NONBLOCK`, in which case `Ok(None)` is returned
+    /// if there is none.
     pub fn read_signal(&mut self) -> Result<Option<siginfo>> {
-        let mut buffer = mem::MaybeUninit::<[u8; SIGNALFD_SIGINFO_SIZE]>::uninit();
+        let mut buffer = mem::MaybeUninit::<siginfo>::uninit();
 
         let res = Errno::result(unsafe {
-            libc::read(self.0,
+            libc::read(self.as_raw_fd(),
                        buffer.as_mut_ptr() as *mut libc::c_void,
                        SIGNALFD_SIGINFO_SIZE as libc::size_t)
         }).map(|r| r as usize);
         match res {
-            Ok(SIGNALFD_SIGINFO_SIZE) => Ok(Some(unsafe { mem::transmute(buffer.assume_init()) })),
+            Ok(SIGNALFD_SIGINFO_SIZE) => Ok(Some(unsafe { buffer.assume_init() })),
             Ok(_) => unreachable!("partial read on signalfd"),
             Err(Error::Sys(Errno::EAGAIN)) => Ok(None),
             Err(error) => Err(error)
@@ -114,15 +140,9 @@ impl SignalFd {
     }
 }
 
-impl Drop for SignalFd {
-    fn drop(&mut self) {
-        let _ = unistd::close(self.0);
-    }
-}
-
 impl AsRawFd for SignalFd {
     fn as_raw_fd(&self) -> RawFd {
-        self.0
+        self.0.as_raw_fd()
     }
 }
 
@@ -2,6 +2,7 @@ use super::sa_family_t;
 use crate::{Error, Result, NixPath};
 use crate::errno::Errno;
 use std::{fmt, mem, net, ptr, slice};
+use std::convert::TryFrom;
 use std::ffi::OsStr;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
@@ -23,7 +24,7 @@ use crate::sys::socket::addr::sys_control::SysControlAddr;
           target_os = "netbsd",
           target_os = "openbsd"))]
 pub use self::datalink::LinkAddr;
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "android", target_os = "linux"))]
 pub use self::vsock::VsockAddr;
 
 /// These constants specify the protocol family to be used
@@ -115,7 +116,7 @@ pub enum AddressFamily {
     Alg = libc::AF_ALG,
     #[cfg(target_os = "linux")]
     Nfc = libc::AF_NFC,
-    #[cfg(target_os = "linux")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
     Vsock = libc::AF_VSOCK,
     #[cfg(any(target_os = "dragonfly",
               target_os = "freebsd",
@@ -242,7 +243,7 @@ impl AddressFamily {
                       target_os = "netbsd",
                       target_os = "openbsd"))]
             libc::AF_LINK => Some(AddressFamily::Link),
-            #[cfg(target_os = "linux")]
+            #[cfg(any(target_os = "android", target_os = "linux"))]
             libc::AF_VSOCK => Some(AddressFamily::Vsock),
             _ => None
         }
@@ -344,6 +345,18 @@ impl fmt::Display for InetAddr {
     }
 }
 
+impl From<net::SocketAddr> for InetAddr {
+    fn from(addr: net::SocketAddr) -> InetAddr {
+        InetAddr::from_std(&addr)
+    }
+}
+
+impl From<InetAddr> for net::SocketAddr {
+    fn from(addr: InetAddr) -> net::SocketAddr {
+        addr.to_std()
+    }
+}
+
 /*
  *
  * ===== IpAddr =====
@@ -396,6 +409,18 @@ impl fmt::Display for IpAddr {
     }
 }
 
+impl From<net::IpAddr> for IpAddr {
+    fn from(addr: net::IpAddr) -> IpAddr {
+        IpAddr::from_std(&addr)
+    }
+}
+
+impl From<IpAddr> for net::IpAddr {
+    fn from(addr: IpAddr) -> net::IpAddr {
+        addr.to_std()
+    }
+}
+
 /*
  *
  * ===== Ipv4Addr =====
@@ -510,6 +535,18 @@ impl fmt::Display for Ipv6Addr {
 #[derive(Clone, Copy, Debug)]
 pub struct UnixAddr(pub libc::sockaddr_un, pub usize);
 
+/// The kind of address held by a [`UnixAddr`](struct.UnixAddr.html).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum UnixAddrKind<'a> {
+    /// A filesystem path
+    Pathname(&'a Path),
+    /// An unbound socket, such as one returned by `socketpair`
+    Unnamed,
+    /// A name in the Linux abstract namespace, without the leading null byte
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    Abstract(&'a [u8]),
+}
+
 impl UnixAddr {
     /// Create a new sockaddr_un representing a filesystem path.
     pub fn new<P: ?Sized + NixPath>(path: &P) -> Result<UnixAddr> {
@@ -597,6 +634,26 @@ impl UnixAddr {
             None
         }
     }
+
+    /// Return whether this address is a filesystem path, an abstract name or
+    /// unnamed.
+    pub fn kind(&self) -> UnixAddrKind<'_> {
+        if self.1 == 0 {
+            UnixAddrKind::Unnamed
+        } else if let Some(path) = self.path() {
+            UnixAddrKind::Pathname(path)
+        } else {
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            {
+                UnixAddrKind::Abstract(&self.sun_path()[1..])
+            }
+            #[cfg(not(any(target_os = "android", target_os = "linux")))]
+            {
+                // Other systems don't have an abstract namespace
+                UnixAddrKind::Unnamed
+            }
+        }
+    }
 }
 
 impl fmt::Display for UnixAddr {
@@ -647,7 +704,7 @@ pub enum SockAddr {
               target_os = "netbsd",
               target_os = "openbsd"))]
     Link(LinkAddr),
-    #[cfg(target_os = "linux")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
     Vsock(VsockAddr),
 }
 
@@ -675,7 +732,7 @@ impl SockAddr {
         SysControlAddr::from_name(sockfd, name, unit).map(|a| SockAddr::SysControl(a))
     }
 
-    #[cfg(target_os = "linux")]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
     pub fn new_vsock(cid: u32, port: u32) -> SockAddr {
         SockAddr::Vsock(VsockAddr::new(cid, port))
     }
@@ -700,7 +757,7 @@ impl SockAddr {
                       target_os = "netbsd",
                       target_os = "openbsd"))]
             SockAddr::Link(..) => AddressFamily::Link,
-            #[cfg(target_os = "linux")]
+            #[cfg(any(target_os = "android", target_os = "linux"))]
             SockAddr::Vsock(..) => AddressFamily::Vsock,
         }
     }
@@ -751,7 +808,7 @@ impl SockAddr {
                         Some(SockAddr::Link(ether_addr))
                     }
                 },
-                #[cfg(target_os = "linux")]
+                #[cfg(any(target_os = "android", target_os = "linux"))]
                 Some(AddressFamily::Vsock) => Some(SockAddr::Vsock(
                     VsockAddr(*(addr as *const libc::sockaddr_vm)))),
                 // Other address families are currently not supported and simply yield a None
@@ -837,7 +894,7 @@ impl SockAddr {
                 },
                 mem::size_of_val(addr) as libc::socklen_t
             ),
-            #[cfg(target_os = "linux")]
+            #[cfg(any(target_os = "android", target_os = "linux"))]
             SockAddr::Vsock(VsockAddr(ref sa)) => (
                 // This cast is always allowed in C
                 unsafe {
@@ -869,12 +926,30 @@ impl fmt::Display for SockAddr {
                       target_os = "netbsd",
                       target_os = "openbsd"))]
             SockAddr::Link(ref ether_addr) => ether_addr.fmt(f),
-            #[cfg(target_os = "linux")]
+            #[cfg(any(target_os = "android", target_os = "linux"))]
             SockAddr::Vsock(ref svm) => svm.fmt(f),
         }
     }
 }
 
+impl From<net::SocketAddr> for SockAddr {
+    fn from(addr: net::SocketAddr) -> SockAddr {
+        SockAddr::Inet(InetAddr::from_std(&addr))
+    }
+}
+
+/// Fails with `EINVAL` if the address is not an IPv4 or IPv6 one.
+impl TryFrom<SockAddr> for net::SocketAddr {
+    type Error = Error;
+
+    fn try_from(addr: SockAddr) -> Result<net::SocketAddr> {
+        match addr {
+            SockAddr::Inet(inet) => Ok(inet.to_std()),
+            _ => Err(Error::invalid_argument()),
+        }
+    }
+}
+
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod netlink {
     use crate::sys::socket::addr::AddressFamily;
@@ -1204,7 +1279,7 @@ mod datalink {
     }
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "android", target_os = "linux"))]
 pub mod vsock {
     use crate::sys::socket::addr::AddressFamily;
     use libc::{sa_family_t, sockaddr_vm};
@@ -0,0 +1,280 @@
+use super::{AddressFamily, SockAddr, SockProtocol, SockType};
+use crate::errno::Errno;
+use libc::{self, c_int};
+use std::{error, fmt, mem, ptr};
+use std::ffi::CStr;
+
+libc_bitflags! {
+    /// Flags for [`getaddrinfo`](fn.getaddrinfo.html)
+    pub struct AddrInfoFlags: c_int {
+        /// Return addresses suitable for `bind`ing a listening socket when no
+        /// node is given, instead of the loopback address.
+        AI_PASSIVE;
+        /// Request the canonical name of the node.
+        AI_CANONNAME;
+        /// The node must be a numeric address; no name resolution is done.
+        AI_NUMERICHOST;
+        /// The service must be a numeric port; no service lookup is done.
+        AI_NUMERICSERV;
+        /// Only return IPv4 (IPv6) addresses if the system has an IPv4 (IPv6)
+        /// address configured.
+        AI_ADDRCONFIG;
+        /// Return IPv4-mapped IPv6 addresses if no IPv6 addresses are found
+        /// for an `AddressFamily::Inet6` query.
+        #[cfg(any(target_os = "android",
+                  target_os = "dragonfly",
+                  target_os = "freebsd",
+                  target_os = "ios",
+                  target_os = "linux",
+                  target_os = "macos"))]
+        AI_V4MAPPED;
+        /// Together with `AI_V4MAPPED`, return both IPv6 and IPv4-mapped
+        /// addresses.
+        #[cfg(any(target_os = "android",
+                  target_os = "dragonfly",
+                  target_os = "freebsd",
+                  target_os = "ios",
+                  target_os = "linux",
+                  target_os = "macos"))]
+        AI_ALL;
+    }
+}
+
+#[cfg(not(target_os = "dragonfly"))]
+libc_bitflags! {
+    /// Flags for [`getnameinfo`](fn.getnameinfo.html)
+    pub struct NameInfoFlags: c_int {
+        /// Return the numeric form of the host address instead of its name.
+        NI_NUMERICHOST;
+        /// Return the numeric form of the port instead of the service name.
+        NI_NUMERICSERV;
+        /// Return only the hostname part of the fully qualified domain name
+        /// for local hosts.
+        NI_NOFQDN;
+        /// Fail with `EAI_NONAME` if the host name cannot be determined,
+        /// instead of returning the numeric address.
+        NI_NAMEREQD;
+        /// Look up the service as a datagram (UDP) service instead of a
+        /// stream (TCP) one.
+        NI_DGRAM;
+    }
+}
+
+/// An error returned by [`getaddrinfo`](fn.getaddrinfo.html) or
+/// [`getnameinfo`](fn.getnameinfo.html).
+///
+/// The resolver functions report failures with their own `EAI_*` codes rather
+/// than through `errno`, except for `EAI_SYSTEM`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GaiError {
+    /// A resolver error, holding one of the `EAI_*` codes such as
+    /// `libc::EAI_NONAME`.
+    Gai(c_int),
+    /// A system error (`EAI_SYSTEM`), holding the value of `errno`.
+    Sys(Errno),
+}
+
+impl GaiError {
+    fn from_code(code: c_int) -> GaiError {
+        if code == libc::EAI_SYSTEM {
+            GaiError::Sys(Errno::last())
+        } else {
+            GaiError::Gai(code)
+        }
+    }
+}
+
+impl error::Error for GaiError {}
+
+impl fmt::Display for GaiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GaiError::Gai(code) => {
+                let desc = unsafe { CStr::from_ptr(libc::gai_strerror(code)) };
+                write!(f, "{}", desc.to_string_lossy())
+            },
+            GaiError::Sys(errno) => write!(f, "{}", errno),
+        }
+    }
+}
+
+/// Criteria for selecting the addresses returned by
+/// [`getaddrinfo`](fn.getaddrinfo.html).
+///
+/// Fields left as `None` match any value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AddrInfoHints {
+    /// Flags modifying how the lookup is done
+    pub flags: AddrInfoFlags,
+    /// Only return addresses of this family
+    pub family: Option<AddressFamily>,
+    /// Only return addresses usable with this socket type
+    pub socktype: Option<SockType>,
+    /// Only return addresses usable with this protocol
+    pub protocol: Option<SockProtocol>,
+}
+
+impl Default for AddrInfoHints {
+    fn default() -> Self {
+        AddrInfoHints {
+            flags: AddrInfoFlags::empty(),
+            family: None,
+            socktype: None,
+            protocol: None,
+        }
+    }
+}
+
+/// Iterator over the addresses returned by
+/// [`getaddrinfo`](fn.getaddrinfo.html).
+///
+/// The underlying list is freed when the iterator is dropped.
+#[derive(Debug)]
+pub struct AddrInfoIter {
+    head: *mut libc::addrinfo,
+    cur: *mut libc::addrinfo,
+}
+
+impl Iterator for AddrInfoIter {
+    type Item = (SockAddr, SockType, Option<SockProtocol>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.cur.is_null() {
+            let ai = unsafe { &*self.cur };
+            self.cur = ai.ai_next;
+
+            let addr = unsafe { SockAddr::from_libc_sockaddr(ai.ai_addr) };
+            if let (Some(addr), Some(ty)) = (addr, sock_type(ai.ai_socktype)) {
+                return Some((addr, ty, sock_protocol(ai.ai_protocol)));
+            }
+        }
+        None
+    }
+}
+
+impl Drop for AddrInfoIter {
+    fn drop(&mut self) {
+        if !self.head.is_null() {
+            unsafe { libc::freeaddrinfo(self.head) };
+        }
+    }
+}
+
+fn sock_type(ty: c_int) -> Option<SockType> {
+    match ty {
+        libc::SOCK_STREAM => Some(SockType::Stream),
+        libc::SOCK_DGRAM => Some(SockType::Datagram),
+        libc::SOCK_SEQPACKET => Some(SockType::SeqPacket),
+        libc::SOCK_RAW => Some(SockType::Raw),
+        libc::SOCK_RDM => Some(SockType::Rdm),
+        _ => None,
+    }
+}
+
+fn sock_protocol(proto: c_int) -> Option<SockProtocol> {
+    match proto {
+        libc::IPPROTO_TCP => Some(SockProtocol::Tcp),
+        libc::IPPROTO_UDP => Some(SockProtocol::Udp),
+        #[cfg(any(target_os = "android",
+                  target_os = "freebsd",
+                  target_os = "ios",
+                  target_os = "linux",
+                  target_os = "macos",
+                  target_os = "netbsd"))]
+        libc::IPPROTO_SCTP => Some(SockProtocol::Sctp),
+        libc::IPPROTO_ICMP => Some(SockProtocol::Icmp),
+        libc::IPPROTO_ICMPV6 => Some(SockProtocol::IcmpV6),
+        libc::IPPROTO_RAW => Some(SockProtocol::Raw),
+        _ => None,
+    }
+}
+
+/// Resolve a node and/or service name into socket addresses.
+///
+/// At least one of `node` (a host name or numeric address) and `service` (a
+/// service name or port number) must be given.  Each item of the returned
+/// iterator holds an address together with the socket type and protocol it
+/// can be used with.  The protocol is `None` when the resolver leaves it
+/// unspecified.  Addresses of families that `SockAddr` cannot represent are
+/// skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use nix::sys::socket::*;
+/// # use std::ffi::CString;
+/// let hints = AddrInfoHints {
+///     socktype: Some(SockType::Stream),
+///     ..AddrInfoHints::default()
+/// };
+/// let node = CString::new("localhost").unwrap();
+/// let service = CString::new("80").unwrap();
+/// for (addr, _, _) in getaddrinfo(Some(&node), Some(&service), Some(&hints)).unwrap() {
+///     println!("{}", addr);
+/// }
+/// ```
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/getaddrinfo.html)
+pub fn getaddrinfo(node: Option<&CStr>,
+                   service: Option<&CStr>,
+                   hints: Option<&AddrInfoHints>)
+    -> std::result::Result<AddrInfoIter, GaiError>
+{
+    let c_hints = hints.map(|hints| {
+        let mut c_hints: libc::addrinfo = unsafe { mem::zeroed() };
+        c_hints.ai_flags = hints.flags.bits();
+        c_hints.ai_family = hints.family.map_or(libc::AF_UNSPEC, |f| f as c_int);
+        c_hints.ai_socktype = hints.socktype.map_or(0, |t| t as c_int);
+        c_hints.ai_protocol = hints.protocol.map_or(0, |p| p as c_int);
+        c_hints
+    });
+    let mut res = ptr::null_mut();
+
+    let code = unsafe {
+        libc::getaddrinfo(node.map_or(ptr::null(), |n| n.as_ptr()),
+                          service.map_or(ptr::null(), |s| s.as_ptr()),
+                          c_hints.as_ref().map_or(ptr::null(), |h| h as *const _),
+                          &mut res)
+    };
+    if code != 0 {
+        return Err(GaiError::from_code(code));
+    }
+
+    Ok(AddrInfoIter { head: res, cur: res })
+}
+
+/// Translate a socket address into a host name and a service name.
+///
+/// Returns the `(host, service)` pair, whose form is controlled by `flags`.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/getnameinfo.html)
+#[cfg(not(target_os = "dragonfly"))]
+pub fn getnameinfo(addr: &SockAddr, flags: NameInfoFlags)
+    -> std::result::Result<(std::ffi::OsString, std::ffi::OsString), GaiError>
+{
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    // NI_MAXHOST and NI_MAXSERV as defined by glibc
+    let mut host = [0u8; 1025];
+    let mut serv = [0u8; 32];
+    let (sa, salen) = addr.as_ffi_pair();
+
+    let code = unsafe {
+        libc::getnameinfo(sa, salen,
+                          host.as_mut_ptr() as *mut libc::c_char,
+                          host.len() as _,
+                          serv.as_mut_ptr() as *mut libc::c_char,
+                          serv.len() as _,
+                          flags.bits())
+    };
+    if code != 0 {
+        return Err(GaiError::from_code(code));
+    }
+
+    let to_os_string = |buf: &[u8]| {
+        let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        OsStr::from_bytes(&buf[..len]).to_owned()
+    };
+    Ok((to_os_string(&host), to_os_string(&serv)))
+}
@@ -5,12 +5,14 @@ use cfg_if::cfg_if;
 use crate::{Error, Result, errno::Errno};
 use libc::{self, c_void, c_int, iovec, socklen_t, size_t,
         CMSG_FIRSTHDR, CMSG_NXTHDR, CMSG_DATA, CMSG_LEN};
-use std::{mem, ptr, slice};
-use std::os::unix::io::RawFd;
+use std::{io, mem, ptr, slice};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use crate::sys::time::TimeVal;
 use crate::sys::uio::IoVec;
+use crate::unistd::OwnedFd;
 
 mod addr;
+mod addrinfo;
 pub mod sockopt;
 
 /*
@@ -24,17 +26,35 @@ pub use self::addr::{
     SockAddr,
     InetAddr,
     UnixAddr,
+    UnixAddrKind,
     IpAddr,
     Ipv4Addr,
     Ipv6Addr,
     LinkAddr,
 };
+pub use self::addrinfo::{
+    getaddrinfo,
+    AddrInfoFlags,
+    AddrInfoHints,
+    AddrInfoIter,
+    GaiError,
+};
+#[cfg(not(target_os = "dragonfly"))]
+pub use self::addrinfo::{getnameinfo, NameInfoFlags};
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub use crate::sys::socket::addr::netlink::NetlinkAddr;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 pub use crate::sys::socket::addr::alg::AlgAddr;
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "android", target_os = "linux"))]
 pub use crate::sys::socket::addr::vsock::VsockAddr;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use libc::{
+    VMADDR_CID_ANY,
+    VMADDR_CID_HOST,
+    VMADDR_CID_HYPERVISOR,
+    VMADDR_CID_LOCAL,
+    VMADDR_PORT_ANY,
+};
 
 pub use libc::{
     cmsghdr,
@@ -84,6 +104,32 @@ pub enum SockProtocol {
     Tcp = libc::IPPROTO_TCP,
     /// UDP protocol ([ip(7)](http://man7.org/linux/man-pages/man7/ip.7.html))
     Udp = libc::IPPROTO_UDP,
+    /// SCTP protocol ([sctp(7)](http://man7.org/linux/man-pages/man7/sctp.7.html))
+    ///
+    /// Use it with `SockType::Stream` for one-to-one style sockets, or with
+    /// `SockType::SeqPacket` for one-to-many style sockets.
+    #[cfg(any(target_os = "android",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd"))]
+    Sctp = libc::IPPROTO_SCTP,
+    /// ICMP protocol ([icmp(7)](http://man7.org/linux/man-pages/man7/icmp.7.html))
+    ///
+    /// Use it with `SockType::Raw`, or on Linux with `SockType::Datagram` for
+    /// unprivileged "ping" sockets.
+    // On Apple platforms, this has the same value as KextEvent
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    Icmp = libc::IPPROTO_ICMP,
+    /// ICMPv6 protocol ([icmpv6(7)](http://man7.org/linux/man-pages/man7/icmpv6.7.html))
+    IcmpV6 = libc::IPPROTO_ICMPV6,
+    /// Raw IP protocol ([raw(7)](http://man7.org/linux/man-pages/man7/raw.7.html))
+    ///
+    /// Use it with `SockType::Raw` to send packets of any protocol.  It
+    /// implies the `IpHdrIncl` socket option, so the IP header must be
+    /// included in the data sent.
+    Raw = libc::IPPROTO_RAW,
     /// Allows applications and other KEXTs to be notified when certain kernel events occur
     /// ([ref](https://developer.apple.com/library/content/documentation/Darwin/Conceptual/NKEConceptual/control/control.html))
     #[cfg(any(target_os = "ios", target_os = "macos"))]
@@ -94,6 +140,15 @@ pub enum SockProtocol {
     KextControl = libc::SYSPROTO_CONTROL,
 }
 
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+#[allow(non_upper_case_globals)]
+impl SockProtocol {
+    /// ICMP protocol ([icmp(4)](https://www.freebsd.org/cgi/man.cgi?query=icmp&sektion=4))
+    ///
+    /// It has the same value as `KextEvent`.
+    pub const Icmp: SockProtocol = SockProtocol::KextEvent;
+}
+
 libc_bitflags!{
     /// Additional socket options
     pub struct SockFlag: c_int {
@@ -182,6 +237,101 @@ libc_bitflags!{
                   target_os = "netbsd",
                   target_os = "openbsd"))]
         MSG_CMSG_CLOEXEC;
+        /// The caller has more data to send.  With TCP, the data is held back
+        /// until it can fill a segment; with UDP, it is added to the same
+        /// datagram; with `AF_ALG` sockets, it is added to the same
+        /// operation.  See [send(2)](http://man7.org/linux/man-pages/man2/send.2.html).
+        ///
+        /// Only used in sending functions.
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        MSG_MORE;
+        /// Send the data without copying it into the kernel, if possible.
+        /// The buffer must not be modified until the kernel reports that it
+        /// is done with it, in a `ControlMessageOwned::ZeroCopyCompletion`
+        /// read from the error queue.  Requires the `ZeroCopy` socket option.
+        /// See [msg_zerocopy](https://www.kernel.org/doc/html/latest/networking/msg_zerocopy.html).
+        ///
+        /// Only used in sending functions.
+        #[cfg(target_os = "linux")]
+        MSG_ZEROCOPY;
+    }
+}
+
+#[cfg(target_os = "linux")]
+libc_bitflags!{
+    /// Flags for the `SO_TIMESTAMPING` socket option, set with
+    /// [`sockopt::Timestamping`](sockopt/struct.Timestamping.html).
+    ///
+    /// [Further reading](https://www.kernel.org/doc/Documentation/networking/timestamping.txt)
+    pub struct TimestampingFlag: c_uint {
+        /// Request transmit timestamps generated by the network adapter.
+        SOF_TIMESTAMPING_TX_HARDWARE;
+        /// Request transmit timestamps generated when the data leaves the
+        /// kernel.
+        SOF_TIMESTAMPING_TX_SOFTWARE;
+        /// Request receive timestamps generated by the network adapter.
+        SOF_TIMESTAMPING_RX_HARDWARE;
+        /// Request receive timestamps generated when the data enters the
+        /// kernel.
+        SOF_TIMESTAMPING_RX_SOFTWARE;
+        /// Report software timestamps, when available.
+        SOF_TIMESTAMPING_SOFTWARE;
+        /// Deprecated and ignored by the kernel.
+        SOF_TIMESTAMPING_SYS_HARDWARE;
+        /// Report hardware timestamps, when available.
+        SOF_TIMESTAMPING_RAW_HARDWARE;
+    }
+}
+
+/// The timestamps carried by a `SCM_TIMESTAMPING` control message.
+///
+/// Timestamps that were not requested or are not available are zero.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Timestamps {
+    /// Software timestamp
+    pub system: crate::sys::time::TimeSpec,
+    /// Legacy transformed hardware timestamp; always zero on modern kernels
+    pub hw_trans: crate::sys::time::TimeSpec,
+    /// Raw hardware timestamp
+    pub hw_raw: crate::sys::time::TimeSpec,
+}
+
+// linux/errqueue.h; not in libc yet
+#[cfg(target_os = "linux")]
+const SO_EE_ORIGIN_ZEROCOPY: u8 = 5;
+#[cfg(target_os = "linux")]
+const SO_EE_CODE_ZEROCOPY_COPIED: u8 = 1;
+
+/// A notification that the kernel is done with the buffers of a range of
+/// `MSG_ZEROCOPY` sends.
+///
+/// Each `MSG_ZEROCOPY` send on a socket is numbered by a 32-bit counter,
+/// starting at zero.  Once the kernel reports a send as completed, its buffer
+/// may be reused.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ZeroCopyCompletion {
+    /// Number of the first completed send
+    pub first: u32,
+    /// Number of the last completed send, inclusive
+    pub last: u32,
+    /// The kernel fell back to copying the data of these sends.  Zero-copy
+    /// sending is then only overhead, and may be worth disabling.
+    pub copied: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl ZeroCopyCompletion {
+    fn from_extended_err(err: &libc::sock_extended_err) -> Option<Self> {
+        if err.ee_errno != 0 || err.ee_origin != SO_EE_ORIGIN_ZEROCOPY {
+            return None;
+        }
+        Some(ZeroCopyCompletion {
+            first: err.ee_info,
+            last: err.ee_data,
+            copied: err.ee_code & SO_EE_CODE_ZEROCOPY_COPIED != 0,
+        })
     }
 }
 
@@ -317,9 +467,273 @@ impl Ipv6MembershipRequest {
     }
 }
 
+/// The leading part of the kernel's `struct tcp_info`, whose layout is
+/// stable.
+///
+/// libc 0.2.150 doesn't provide `tcp_info`, and later versions only do for
+/// the Linux C libraries, not for Android, so `TcpInfo` can't wrap it on
+/// every platform that has the option.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct RawTcpInfo {
+    tcpi_state: u8,
+    tcpi_ca_state: u8,
+    tcpi_retransmits: u8,
+    tcpi_probes: u8,
+    tcpi_backoff: u8,
+    tcpi_options: u8,
+    tcpi_snd_rcv_wscale: u8,
+    tcpi_rto: u32,
+    tcpi_ato: u32,
+    tcpi_snd_mss: u32,
+    tcpi_rcv_mss: u32,
+    tcpi_unacked: u32,
+    tcpi_sacked: u32,
+    tcpi_lost: u32,
+    tcpi_retrans: u32,
+    tcpi_fackets: u32,
+    tcpi_last_data_sent: u32,
+    tcpi_last_ack_sent: u32,
+    tcpi_last_data_recv: u32,
+    tcpi_last_ack_recv: u32,
+    tcpi_pmtu: u32,
+    tcpi_rcv_ssthresh: u32,
+    tcpi_rtt: u32,
+    tcpi_rttvar: u32,
+    tcpi_snd_ssthresh: u32,
+    tcpi_snd_cwnd: u32,
+    tcpi_advmss: u32,
+    tcpi_reordering: u32,
+    tcpi_rcv_rtt: u32,
+    tcpi_rcv_space: u32,
+    tcpi_total_retrans: u32,
+}
+
+/// Information about a TCP connection, as returned by the
+/// [`TcpInfo`](sockopt/struct.TcpInfo.html) socket option.
+///
+/// See [tcp(7)](http://man7.org/linux/man-pages/man7/tcp.7.html).
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TcpInfo(RawTcpInfo);
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl TcpInfo {
+    /// The state of the connection, using the kernel's `TCP_*` state numbers
+    /// (`TCP_ESTABLISHED` is 1)
+    pub fn state(&self) -> u8 {
+        self.0.tcpi_state
+    }
+
+    /// The state of the congestion control algorithm
+    pub fn ca_state(&self) -> u8 {
+        self.0.tcpi_ca_state
+    }
+
+    /// The number of unrecovered retransmission timeouts
+    pub fn retransmits(&self) -> u8 {
+        self.0.tcpi_retransmits
+    }
+
+    /// The number of unanswered zero window probes
+    pub fn probes(&self) -> u8 {
+        self.0.tcpi_probes
+    }
+
+    /// The exponential backoff of the retransmission timer
+    pub fn backoff(&self) -> u8 {
+        self.0.tcpi_backoff
+    }
+
+    /// The TCP options negotiated for the connection (`TCPI_OPT_*` flags)
+    pub fn options(&self) -> u8 {
+        self.0.tcpi_options
+    }
+
+    // `tcpi_snd_wscale` and `tcpi_rcv_wscale` are 4-bit bitfields sharing a
+    // byte.  The first one takes the low bits on little-endian targets, and
+    // the high bits on big-endian ones.
+
+    /// The window scale advertised by the peer
+    #[cfg(target_endian = "little")]
+    pub fn snd_wscale(&self) -> u8 {
+        self.0.tcpi_snd_rcv_wscale & 0xf
+    }
+
+    /// The window scale advertised by the peer
+    #[cfg(target_endian = "big")]
+    pub fn snd_wscale(&self) -> u8 {
+        self.0.tcpi_snd_rcv_wscale >> 4
+    }
+
+    /// The window scale advertised to the peer
+    #[cfg(target_endian = "little")]
+    pub fn rcv_wscale(&self) -> u8 {
+        self.0.tcpi_snd_rcv_wscale >> 4
+    }
+
+    /// The window scale advertised to the peer
+    #[cfg(target_endian = "big")]
+    pub fn rcv_wscale(&self) -> u8 {
+        self.0.tcpi_snd_rcv_wscale & 0xf
+    }
+
+    /// The retransmission timeout, in microseconds
+    pub fn rto(&self) -> u32 {
+        self.0.tcpi_rto
+    }
+
+    /// The delayed acknowledgement timeout, in microseconds
+    pub fn ato(&self) -> u32 {
+        self.0.tcpi_ato
+    }
+
+    /// The maximum segment size for sending
+    pub fn snd_mss(&self) -> u32 {
+        self.0.tcpi_snd_mss
+    }
+
+    /// The maximum segment size for receiving
+    pub fn rcv_mss(&self) -> u32 {
+        self.0.tcpi_rcv_mss
+    }
+
+    /// The number of unacknowledged segments
+    pub fn unacked(&self) -> u32 {
+        self.0.tcpi_unacked
+    }
+
+    /// The number of selectively acknowledged segments
+    pub fn sacked(&self) -> u32 {
+        self.0.tcpi_sacked
+    }
+
+    /// The number of segments considered lost
+    pub fn lost(&self) -> u32 {
+        self.0.tcpi_lost
+    }
+
+    /// The number of retransmitted segments in flight
+    pub fn retrans(&self) -> u32 {
+        self.0.tcpi_retrans
+    }
+
+    /// The time since data was last sent, in milliseconds
+    pub fn last_data_sent(&self) -> u32 {
+        self.0.tcpi_last_data_sent
+    }
+
+    /// The time since data was last received, in milliseconds
+    pub fn last_data_recv(&self) -> u32 {
+        self.0.tcpi_last_data_recv
+    }
+
+    /// The time since an acknowledgement was last received, in milliseconds
+    pub fn last_ack_recv(&self) -> u32 {
+        self.0.tcpi_last_ack_recv
+    }
+
+    /// The path MTU
+    pub fn pmtu(&self) -> u32 {
+        self.0.tcpi_pmtu
+    }
+
+    /// The slow start threshold for receiving
+    pub fn rcv_ssthresh(&self) -> u32 {
+        self.0.tcpi_rcv_ssthresh
+    }
+
+    /// The smoothed round trip time, in microseconds
+    pub fn rtt(&self) -> u32 {
+        self.0.tcpi_rtt
+    }
+
+    /// The round trip time variance, in microseconds
+    pub fn rttvar(&self) -> u32 {
+        self.0.tcpi_rttvar
+    }
+
+    /// The slow start threshold for sending
+    pub fn snd_ssthresh(&self) -> u32 {
+        self.0.tcpi_snd_ssthresh
+    }
+
+    /// The congestion window, in segments
+    pub fn snd_cwnd(&self) -> u32 {
+        self.0.tcpi_snd_cwnd
+    }
+
+    /// The maximum segment size advertised to the peer
+    pub fn advmss(&self) -> u32 {
+        self.0.tcpi_advmss
+    }
+
+    /// The amount of reordering tolerated, in segments
+    pub fn reordering(&self) -> u32 {
+        self.0.tcpi_reordering
+    }
+
+    /// The estimated round trip time on the receiving side, in microseconds
+    pub fn rcv_rtt(&self) -> u32 {
+        self.0.tcpi_rcv_rtt
+    }
+
+    /// The advertised receive space
+    pub fn rcv_space(&self) -> u32 {
+        self.0.tcpi_rcv_space
+    }
+
+    /// The total number of retransmitted segments
+    pub fn total_retrans(&self) -> u32 {
+        self.0.tcpi_total_retrans
+    }
+}
+
+/// The SCTP notifications that a socket receives, as set by the
+/// [`SctpEvents`](sockopt/struct.SctpEvents.html) socket option.
+///
+/// Each field enables one kind of notification when it is non-zero.  This is
+/// the leading part of the kernel's `struct sctp_event_subscribe`, which
+/// every kernel with SCTP support accepts; events added later can only be
+/// enabled with `SCTP_EVENT`.
+///
+/// See [sctp(7)](http://man7.org/linux/man-pages/man7/sctp.7.html).
+#[cfg(target_os = "linux")]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SctpEventSubscribe {
+    /// Receive an `SCTP_SNDRCV` control message with each message
+    pub sctp_data_io_event: u8,
+    /// Association changes, like new or lost associations
+    pub sctp_association_event: u8,
+    /// Changes of the peer's addresses
+    pub sctp_address_event: u8,
+    /// Messages that couldn't be delivered
+    pub sctp_send_failure_event: u8,
+    /// Operation errors reported by the peer
+    pub sctp_peer_error_event: u8,
+    /// The peer started a graceful shutdown
+    pub sctp_shutdown_event: u8,
+    /// Partial delivery of a message was aborted
+    pub sctp_partial_delivery_event: u8,
+    /// The peer's adaptation layer indication
+    pub sctp_adaptation_layer_event: u8,
+    /// Authentication key changes
+    pub sctp_authentication_event: u8,
+    /// There is no more user data left to send
+    pub sctp_sender_dry_event: u8,
+}
+
 /// Create a buffer large enough for storing some control messages as returned
 /// by [`recvmsg`](fn.recvmsg.html).
 ///
+/// Each argument is the type of the data of one control message.  An array
+/// type `[T; n]` stands for a message holding `n` values of type `T`, where
+/// `n` may be any expression of type `usize`, so the size of the buffer can
+/// be chosen at runtime.
+///
 /// # Examples
 ///
 /// ```
@@ -335,23 +749,33 @@ impl Ipv6MembershipRequest {
 /// // Create a buffer big enough for a `ControlMessageOwned::ScmRights` message
 /// // and a `ControlMessageOwned::ScmTimestamp` message
 /// let _ = cmsg_space!(RawFd, TimeVal);
+/// // Create a buffer big enough for a `ControlMessageOwned::ScmRights` message
+/// // with a number of file descriptors only known at runtime
+/// let max_fds = std::env::args().count();
+/// let _ = cmsg_space!([RawFd; max_fds]);
 /// # }
 /// ```
 // Unfortunately, CMSG_SPACE isn't a const_fn, or else we could return a
 // stack-allocated array.
 #[macro_export]
 macro_rules! cmsg_space {
-    ( $( $x:ty ),* ) => {
+    (@add $space:ident; ) => {};
+    (@add $space:ident; [ $x:ty; $n:expr ] $(, $( $rest:tt )* )? ) => {
+        let len = (::std::mem::size_of::<$x>() * $n) as $crate::sys::socket::c_uint;
+        // CMSG_SPACE is always safe
+        $space += unsafe { $crate::sys::socket::CMSG_SPACE(len) } as usize;
+        $crate::cmsg_space!(@add $space; $( $( $rest )* )? );
+    };
+    (@add $space:ident; $x:ty $(, $( $rest:tt )* )? ) => {
+        let len = ::std::mem::size_of::<$x>() as $crate::sys::socket::c_uint;
+        // CMSG_SPACE is always safe
+        $space += unsafe { $crate::sys::socket::CMSG_SPACE(len) } as usize;
+        $crate::cmsg_space!(@add $space; $( $( $rest )* )? );
+    };
+    ( $( $x:tt )* ) => {
         {
-            use nix::sys::socket::{c_uint, CMSG_SPACE};
-            use std::mem;
-            let mut space = 0;
-            $(
-                // CMSG_SPACE is always safe
-                space += unsafe {
-                    CMSG_SPACE(mem::size_of::<$x>() as c_uint)
-                } as usize;
-            )*
+            let mut space = 0usize;
+            $crate::cmsg_space!(@add space; $( $x )*);
             Vec::<u8>::with_capacity(space)
         }
     }
@@ -488,6 +912,21 @@ pub enum ControlMessageOwned {
     /// # }
     /// ```
     ScmTimestamp(TimeVal),
+    /// A message of type `SCM_TIMESTAMPNS`, containing the time the
+    /// packet was received by the kernel, with nanosecond resolution.
+    ///
+    /// It is enabled with the `ReceiveTimestampNs` socket option.  See the
+    /// kernel's explanation in "SO_TIMESTAMPNS" of
+    /// [networking/timestamping](https://www.kernel.org/doc/Documentation/networking/timestamping.txt).
+    #[cfg(target_os = "linux")]
+    ScmTimestampNs(crate::sys::time::TimeSpec),
+    /// A message of type `SCM_TIMESTAMPING`, containing the software and
+    /// hardware timestamps requested with the `Timestamping` socket option.
+    ///
+    /// See the kernel's explanation in "SO_TIMESTAMPING" of
+    /// [networking/timestamping](https://www.kernel.org/doc/Documentation/networking/timestamping.txt).
+    #[cfg(target_os = "linux")]
+    ScmTimestamping(Timestamps),
     #[cfg(any(
         target_os = "android",
         target_os = "ios",
@@ -535,6 +974,44 @@ pub enum ControlMessageOwned {
     #[cfg(target_os = "linux")]
     UdpGroSegments(u16),
 
+    /// A message of type `SCTP_SNDRCV`, describing the stream and
+    /// association a message was received on.
+    ///
+    /// See [sctp(7)](http://man7.org/linux/man-pages/man7/sctp.7.html).
+    #[cfg(target_os = "linux")]
+    SctpSndRcvInfo(libc::sctp_sndrcvinfo),
+    /// A message of type `SCTP_RCVINFO`, describing the stream and
+    /// association a message was received on.
+    ///
+    /// It is received on sockets with the `SctpRecvRcvInfo` socket option
+    /// enabled.  See [RFC 6458](https://tools.ietf.org/html/rfc6458#section-5.3.5).
+    #[cfg(target_os = "linux")]
+    SctpRcvInfo(libc::sctp_rcvinfo),
+
+    /// An extended error reported through the socket error queue, along
+    /// with the address of the node that generated it, if any.
+    ///
+    /// It is received with `MsgFlags::MSG_ERRQUEUE` on IPv4 sockets with the
+    /// `Ipv4RecvErr` socket option enabled.  See
+    /// [ip(7)](http://man7.org/linux/man-pages/man7/ip.7.html).
+    #[cfg(target_os = "linux")]
+    Ipv4RecvErr(libc::sock_extended_err, Option<sockaddr_in>),
+    /// An extended error reported through the socket error queue, along
+    /// with the address of the node that generated it, if any.
+    ///
+    /// It is received with `MsgFlags::MSG_ERRQUEUE` on IPv6 sockets with the
+    /// `Ipv6RecvErr` socket option enabled.  See
+    /// [ipv6(7)](http://man7.org/linux/man-pages/man7/ipv6.7.html).
+    #[cfg(target_os = "linux")]
+    Ipv6RecvErr(libc::sock_extended_err, Option<sockaddr_in6>),
+    /// A completion notification for `MSG_ZEROCOPY` sends, read from the
+    /// socket error queue with `MsgFlags::MSG_ERRQUEUE`.
+    ///
+    /// Such notifications are reported here rather than as `Ipv4RecvErr` or
+    /// `Ipv6RecvErr`.
+    #[cfg(target_os = "linux")]
+    ZeroCopyCompletion(ZeroCopyCompletion),
+
     /// Catch-all variant for unimplemented cmsg types.
     #[doc(hidden)]
     Unknown(UnknownCmsg),
@@ -579,6 +1056,20 @@ impl ControlMessageOwned {
                 let tv: libc::timeval = ptr::read_unaligned(p as *const _);
                 ControlMessageOwned::ScmTimestamp(TimeVal::from(tv))
             },
+            #[cfg(target_os = "linux")]
+            (libc::SOL_SOCKET, libc::SCM_TIMESTAMPNS) => {
+                let ts: libc::timespec = ptr::read_unaligned(p as *const _);
+                ControlMessageOwned::ScmTimestampNs(crate::sys::time::TimeSpec::from(ts))
+            },
+            #[cfg(target_os = "linux")]
+            (libc::SOL_SOCKET, libc::SCM_TIMESTAMPING) => {
+                let tp = p as *const libc::timespec;
+                ControlMessageOwned::ScmTimestamping(Timestamps {
+                    system: ptr::read_unaligned(tp).into(),
+                    hw_trans: ptr::read_unaligned(tp.add(1)).into(),
+                    hw_raw: ptr::read_unaligned(tp.add(2)).into(),
+                })
+            },
             #[cfg(any(
                 target_os = "android",
                 target_os = "freebsd",
@@ -625,8 +1116,35 @@ impl ControlMessageOwned {
             },
             #[cfg(target_os = "linux")]
             (libc::SOL_UDP, libc::UDP_GRO) => {
-                let gso_size: u16 = ptr::read_unaligned(p as *const _);
-                ControlMessageOwned::UdpGroSegments(gso_size)
+                // The kernel passes the segment size as an int
+                let gso_size: c_int = ptr::read_unaligned(p as *const _);
+                ControlMessageOwned::UdpGroSegments(gso_size as u16)
+            },
+            #[cfg(target_os = "linux")]
+            (libc::IPPROTO_SCTP, libc::SCTP_SNDRCV) => {
+                let info = ptr::read_unaligned(p as *const libc::sctp_sndrcvinfo);
+                ControlMessageOwned::SctpSndRcvInfo(info)
+            },
+            #[cfg(target_os = "linux")]
+            (libc::IPPROTO_SCTP, libc::SCTP_RCVINFO) => {
+                let info = ptr::read_unaligned(p as *const libc::sctp_rcvinfo);
+                ControlMessageOwned::SctpRcvInfo(info)
+            },
+            #[cfg(target_os = "linux")]
+            (libc::SOL_IP, libc::IP_RECVERR) => {
+                let (err, addr) = Self::recv_err_helper::<sockaddr_in>(p, len);
+                match ZeroCopyCompletion::from_extended_err(&err) {
+                    Some(zc) => ControlMessageOwned::ZeroCopyCompletion(zc),
+                    None => ControlMessageOwned::Ipv4RecvErr(err, addr),
+                }
+            },
+            #[cfg(target_os = "linux")]
+            (libc::SOL_IPV6, libc::IPV6_RECVERR) => {
+                let (err, addr) = Self::recv_err_helper::<sockaddr_in6>(p, len);
+                match ZeroCopyCompletion::from_extended_err(&err) {
+                    Some(zc) => ControlMessageOwned::ZeroCopyCompletion(zc),
+                    None => ControlMessageOwned::Ipv6RecvErr(err, addr),
+                }
             },
             (_, _) => {
                 let sl = slice::from_raw_parts(p, len);
@@ -635,6 +1153,31 @@ impl ControlMessageOwned {
             }
         }
     }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn recv_err_helper<T>(p: *const u8, len: usize)
+        -> (libc::sock_extended_err, Option<T>)
+    {
+        let ee = p as *const libc::sock_extended_err;
+        let err = ptr::read_unaligned(ee);
+
+        // The kernel always appends the address of the offender, which
+        // SO_EE_OFFENDER points to, but zeroes it to AF_UNSPEC for local
+        // errors.  Both sockaddr_in and sockaddr_in6 start with the family.
+        // Check the length before computing the address, so that a truncated
+        // message can't make it point past the buffer.
+        let offset = mem::size_of::<libc::sock_extended_err>();
+        if offset + mem::size_of::<T>() > len {
+            return (err, None);
+        }
+        let addrp = p.add(offset) as *const T;
+        let family = ptr::read_unaligned(addrp as *const libc::sa_family_t);
+        if libc::c_int::from(family) == libc::AF_UNSPEC {
+            (err, None)
+        } else {
+            (err, Some(ptr::read_unaligned(addrp)))
+        }
+    }
 }
 
 /// A type-safe zero-copy wrapper around a single control message, as used wih
@@ -1008,7 +1551,8 @@ pub struct SendMmsgData<'a, I, C>
 /// * `flags`:          Optional flags passed directly to the operating system.
 ///
 /// # Returns
-/// `Vec` with numbers of sent bytes on each sent message.
+/// `Vec` with numbers of sent bytes on each sent message.  It may be shorter
+/// than `data` if not all messages could be sent.
 ///
 /// # References
 /// [`sendmsg`](fn.sendmsg.html)
@@ -1058,7 +1602,7 @@ pub fn sendmmsg<'a, I, C>(
     let sent_messages = Errno::result(ret)? as usize;
     let mut sent_bytes = Vec::with_capacity(sent_messages);
 
-    for item in &output {
+    for item in output.iter().take(sent_messages) {
         sent_bytes.push(item.msg_len as usize);
     }
 
@@ -1102,7 +1646,8 @@ pub struct RecvMmsgData<'a, I>
 ///                     [`cmsg_space!`](macro.cmsg_space.html)
 ///
 /// # Returns
-/// A `Vec` with multiple `RecvMsg`, one per received message
+/// A `Vec` with multiple `RecvMsg`, one per received message.  It may be
+/// shorter than `data` if fewer messages were available.
 ///
 /// # References
 /// - [`recvmsg`](fn.recvmsg.html)
@@ -1162,17 +1707,19 @@ pub fn recvmmsg<'a, I>(
 
     let ret = unsafe { libc::recvmmsg(fd, output.as_mut_ptr(), output.len() as _, flags.bits() as _, timeout) };
 
-    let r = Errno::result(ret)?;
+    let r = Errno::result(ret)? as usize;
 
+    // Only the first `r` headers were filled in by the kernel
     Ok(output
         .into_iter()
+        .take(r)
         .zip(addresses.iter().map(|addr| unsafe{addr.assume_init()}))
         .zip(results.into_iter())
         .map(|((mmsghdr, address), (msg_controllen, cmsg_buffer))| {
             unsafe {
                 read_mhdr(
                     mmsghdr.msg_hdr,
-                    r as isize,
+                    mmsghdr.msg_len as isize,
                     msg_controllen,
                     address,
                     cmsg_buffer
@@ -1340,6 +1887,77 @@ pub fn recvmsg<'a>(fd: RawFd, iov: &[IoVec<&mut [u8]>],
     Ok(unsafe { read_mhdr(mhdr, r, msg_controllen, address.assume_init(), &mut cmsg_buffer) })
 }
 
+/// Send `data` on the Unix domain socket `fd`, together with the file
+/// descriptors in `fds`.
+///
+/// This is a convenience wrapper around [`sendmsg`](fn.sendmsg.html) with a
+/// single [`ControlMessage::ScmRights`](enum.ControlMessage.html#variant.ScmRights)
+/// message.  At least one byte of `data` must be sent along with the file
+/// descriptors.  Returns the number of bytes of `data` sent.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// # use nix::unistd::{close, pipe};
+/// let (fd1, fd2) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+///                             SockFlag::empty()).unwrap();
+/// let (r, w) = pipe().unwrap();
+/// send_fds(fd1, &[r, w], b"x").unwrap();
+///
+/// let mut buf = [0u8; 1];
+/// let (bytes, fds) = recv_fds(fd2, &mut buf, 2).unwrap();
+/// assert_eq!(bytes, 1);
+/// assert_eq!(fds.len(), 2);
+/// # for fd in fds.into_iter().chain(vec![r, w, fd1, fd2]) { close(fd).unwrap(); }
+/// ```
+pub fn send_fds(fd: RawFd, fds: &[RawFd], data: &[u8]) -> Result<usize> {
+    let iov = [IoVec::from_slice(data)];
+    if fds.is_empty() {
+        sendmsg(fd, &iov, &[], MsgFlags::empty(), None)
+    } else {
+        let cmsgs = [ControlMessage::ScmRights(fds)];
+        sendmsg(fd, &iov, &cmsgs, MsgFlags::empty(), None)
+    }
+}
+
+/// Receive data into `buf` from the Unix domain socket `fd`, together with up
+/// to `max_fds` file descriptors sent alongside it.
+///
+/// This is a convenience wrapper around [`recvmsg`](fn.recvmsg.html) that
+/// sizes the control message buffer for `max_fds` descriptors.  Returns the
+/// number of bytes received and the received file descriptors, which the
+/// caller is responsible for closing.
+///
+/// If the sender passed more than `max_fds` file descriptors, the descriptors
+/// that did arrive are closed and `EMSGSIZE` is returned.
+pub fn recv_fds(fd: RawFd, buf: &mut [u8], max_fds: usize)
+    -> Result<(usize, Vec<RawFd>)>
+{
+    let iov = [IoVec::from_mut_slice(buf)];
+    let mut cmsg_buffer = cmsg_space!([RawFd; max_fds]);
+    let cmsg_buffer = if max_fds > 0 { Some(&mut cmsg_buffer) } else { None };
+    let msg = recvmsg(fd, &iov, cmsg_buffer, MsgFlags::empty())?;
+
+    let mut fds = Vec::new();
+    for cmsg in msg.cmsgs() {
+        if let ControlMessageOwned::ScmRights(received) = cmsg {
+            fds.extend(received);
+        }
+    }
+
+    // Because of alignment padding, the buffer may have had room for a few
+    // more descriptors than were asked for.
+    if msg.flags.contains(MsgFlags::MSG_CTRUNC) || fds.len() > max_fds {
+        for fd in fds {
+            let _ = crate::unistd::close(fd);
+        }
+        return Err(Error::Sys(Errno::EMSGSIZE));
+    }
+
+    Ok((msg.bytes, fds))
+}
+
 
 /// Create an endpoint for communication
 ///
@@ -1370,6 +1988,9 @@ pub fn socket<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: SockType
 
 /// Create a pair of connected sockets
 ///
+/// See also [`SocketPair`](struct.SocketPair.html), which closes the sockets
+/// when dropped.
+///
 /// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/socketpair.html)
 pub fn socketpair<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: SockType, protocol: T,
                   flags: SockFlag) -> Result<(RawFd, RawFd)> {
@@ -1392,6 +2013,316 @@ pub fn socketpair<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: Sock
     Ok((fds[0], fds[1]))
 }
 
+/// An owned socket file descriptor
+///
+/// This type implements `Drop`, so that when it's consumed or goes out of
+/// scope, the socket is automatically closed.  Use `into_raw_fd` to take
+/// ownership of the descriptor instead.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Socket(OwnedFd);
+
+impl Socket {
+    /// Create a new socket.  The arguments are the same as for
+    /// [`socket`](fn.socket.html).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use nix::sys::socket::*;
+    /// # use std::str::FromStr;
+    /// let listener = Socket::new(AddressFamily::Inet, SockType::Stream,
+    ///                            SockFlag::empty(), None).unwrap();
+    /// let localhost = InetAddr::from_std(&std::net::SocketAddr::from_str("127.0.0.1:0").unwrap());
+    /// listener.bind(&SockAddr::new_inet(localhost)).unwrap();
+    /// listener.listen(10).unwrap();
+    ///
+    /// let client = Socket::new(AddressFamily::Inet, SockType::Stream,
+    ///                          SockFlag::empty(), None).unwrap();
+    /// client.connect(&listener.local_addr().unwrap()).unwrap();
+    /// let server = listener.accept().unwrap();
+    /// client.shutdown(Shutdown::Write).unwrap();
+    /// ```
+    pub fn new<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: SockType,
+                                              flags: SockFlag, protocol: T)
+        -> Result<Socket>
+    {
+        socket(domain, ty, flags, protocol)
+            .map(|fd| unsafe { Socket::from_raw_fd(fd) })
+    }
+
+    /// Bind a name to the socket.  See [`bind`](fn.bind.html).
+    pub fn bind(&self, addr: &SockAddr) -> Result<()> {
+        bind(self.as_raw_fd(), addr)
+    }
+
+    /// Initiate a connection on the socket.  See [`connect`](fn.connect.html).
+    pub fn connect(&self, addr: &SockAddr) -> Result<()> {
+        connect(self.as_raw_fd(), addr)
+    }
+
+    /// Listen for connections on the socket.  See [`listen`](fn.listen.html).
+    pub fn listen(&self, backlog: usize) -> Result<()> {
+        listen(self.as_raw_fd(), backlog)
+    }
+
+    /// Accept a connection on the socket.  See [`accept`](fn.accept.html).
+    pub fn accept(&self) -> Result<Socket> {
+        accept(self.as_raw_fd()).map(|fd| unsafe { Socket::from_raw_fd(fd) })
+    }
+
+    /// Shut down part or all of a full-duplex connection.  See
+    /// [`shutdown`](fn.shutdown.html).
+    pub fn shutdown(&self, how: Shutdown) -> Result<()> {
+        shutdown(self.as_raw_fd(), how)
+    }
+
+    /// Get the address the socket is bound to.  See
+    /// [`getsockname`](fn.getsockname.html).
+    pub fn local_addr(&self) -> Result<SockAddr> {
+        getsockname(self.as_raw_fd())
+    }
+
+    /// Get the address of the peer connected to the socket.  See
+    /// [`getpeername`](fn.getpeername.html).
+    pub fn peer_addr(&self) -> Result<SockAddr> {
+        getpeername(self.as_raw_fd())
+    }
+
+    /// Get the current value of a socket option.  See
+    /// [`getsockopt`](fn.getsockopt.html).
+    pub fn getsockopt<O: GetSockOpt>(&self, opt: O) -> Result<O::Val> {
+        getsockopt(self.as_raw_fd(), opt)
+    }
+
+    /// Set the value of a socket option.  See
+    /// [`setsockopt`](fn.setsockopt.html).
+    pub fn setsockopt<O: SetSockOpt>(&self, opt: O, val: &O::Val) -> Result<()> {
+        setsockopt(self.as_raw_fd(), opt, val)
+    }
+}
+
+impl AsRawFd for Socket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for Socket {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+impl FromRawFd for Socket {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Socket(OwnedFd::from_raw_fd(fd))
+    }
+}
+
+impl io::Read for Socket {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        crate::unistd::read(self.as_raw_fd(), buf).map_err(|e| e.as_errno().unwrap().into())
+    }
+}
+
+impl io::Write for Socket {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        crate::unistd::write(self.as_raw_fd(), buf).map_err(|e| e.as_errno().unwrap().into())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A pair of connected sockets, as created by [`socketpair`](fn.socketpair.html)
+///
+/// Both sockets are closed when the pair is dropped, unless it has been
+/// `split` into its two halves first.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::{AddressFamily, SockFlag, SockType, SocketPair};
+/// # use std::io::{Read, Write};
+/// let pair = SocketPair::new(AddressFamily::Unix, SockType::Stream, None,
+///                            SockFlag::empty()).unwrap();
+/// let (mut a, mut b) = pair.split();
+/// a.write_all(b"hello").unwrap();
+/// let mut buf = [0u8; 5];
+/// b.read_exact(&mut buf).unwrap();
+/// assert_eq!(&buf, b"hello");
+/// ```
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct SocketPair(Socket, Socket);
+
+impl SocketPair {
+    /// Create a pair of connected sockets.  The arguments are the same as for
+    /// [`socketpair`](fn.socketpair.html).
+    pub fn new<T: Into<Option<SockProtocol>>>(domain: AddressFamily, ty: SockType,
+                                              protocol: T, flags: SockFlag)
+        -> Result<SocketPair>
+    {
+        let (fd0, fd1) = socketpair(domain, ty, protocol, flags)?;
+        unsafe {
+            Ok(SocketPair(Socket::from_raw_fd(fd0), Socket::from_raw_fd(fd1)))
+        }
+    }
+
+    /// The first socket of the pair
+    pub fn first(&self) -> &Socket {
+        &self.0
+    }
+
+    /// The second socket of the pair
+    pub fn second(&self) -> &Socket {
+        &self.1
+    }
+
+    /// Split the pair into two independently owned sockets
+    pub fn split(self) -> (Socket, Socket) {
+        (self.0, self.1)
+    }
+}
+
+/// A message-oriented channel between two connected Unix domain sockets.
+///
+/// The channel is built on a `SOCK_SEQPACKET` socket pair, so that message
+/// boundaries are preserved, and every message may carry file descriptors.
+/// It is intended for communication between a process and a child it has
+/// spawned, such as a sandboxed worker and its broker: the child inherits one
+/// end of the pair.
+///
+/// Both ends are created with `SOCK_CLOEXEC` and `SO_PASSCRED`, so every
+/// received message carries the credentials of the process that sent it, as
+/// checked by the kernel.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::UnixSeqpacketChannel;
+/// # use nix::unistd::{close, getpid, pipe};
+/// let (parent, child) = UnixSeqpacketChannel::pair().unwrap();
+/// let (r, w) = pipe().unwrap();
+/// parent.send(b"hello", &[r]).unwrap();
+/// parent.send(b"world", &[]).unwrap();
+///
+/// let msg = child.recv(1).unwrap().unwrap();
+/// assert_eq!(&msg.data[..], b"hello");
+/// assert_eq!(msg.fds.len(), 1);
+/// assert_eq!(msg.credentials.pid(), getpid().as_raw());
+/// let fds = msg.fds;
+/// let msg = child.recv(1).unwrap().unwrap();
+/// assert_eq!(&msg.data[..], b"world");
+///
+/// // Once the other end is closed, there are no more messages
+/// drop(parent);
+/// assert!(child.recv(1).unwrap().is_none());
+/// # for fd in fds.into_iter().chain(vec![r, w]) { close(fd).unwrap(); }
+/// ```
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct UnixSeqpacketChannel(Socket);
+
+/// A message received from a [`UnixSeqpacketChannel`](struct.UnixSeqpacketChannel.html)
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeqpacketMessage {
+    /// The contents of the message
+    pub data: Vec<u8>,
+    /// The file descriptors sent with the message, which the receiver is
+    /// responsible for closing
+    pub fds: Vec<RawFd>,
+    /// The credentials of the process that sent the message
+    pub credentials: UnixCredentials,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl UnixSeqpacketChannel {
+    /// Create both ends of a new channel
+    pub fn pair() -> Result<(UnixSeqpacketChannel, UnixSeqpacketChannel)> {
+        let (a, b) = SocketPair::new(AddressFamily::Unix, SockType::SeqPacket,
+                                     None, SockFlag::SOCK_CLOEXEC)?.split();
+        // Credentials are only attached to messages sent after the receiving
+        // end has enabled them, so do it before either end is handed out.
+        setsockopt(a.as_raw_fd(), sockopt::PassCred, &true)?;
+        setsockopt(b.as_raw_fd(), sockopt::PassCred, &true)?;
+        Ok((UnixSeqpacketChannel(a), UnixSeqpacketChannel(b)))
+    }
+
+    /// Send `data` as a single message, together with the file descriptors in
+    /// `fds`.  See [`send_fds`](fn.send_fds.html).
+    pub fn send(&self, data: &[u8], fds: &[RawFd]) -> Result<usize> {
+        send_fds(self.0.as_raw_fd(), fds, data)
+    }
+
+    /// Receive the next message, together with up to `max_fds` file
+    /// descriptors sent with it and the credentials of its sender.
+    ///
+    /// The returned buffer is sized to hold the whole message.  `None` is
+    /// returned once the other end of the channel is closed.
+    ///
+    /// If the sender passed more than `max_fds` file descriptors, the
+    /// descriptors that did arrive are closed and `EMSGSIZE` is returned.  A
+    /// message that comes without credentials fails with `EPROTO`, which
+    /// happens for a channel built with `from_raw_fd` from a socket that
+    /// doesn't have `SO_PASSCRED` set.
+    pub fn recv(&self, max_fds: usize) -> Result<Option<SeqpacketMessage>> {
+        let fd = self.0.as_raw_fd();
+        let mut data = vec![0; recv_peek_len(fd)?];
+        let iov = [IoVec::from_mut_slice(&mut data)];
+        let mut cmsg_buffer = cmsg_space!(libc::ucred, [RawFd; max_fds]);
+        let msg = recvmsg(fd, &iov, Some(&mut cmsg_buffer), MsgFlags::empty())?;
+
+        let mut fds = Vec::new();
+        let mut credentials = None;
+        for cmsg in msg.cmsgs() {
+            match cmsg {
+                ControlMessageOwned::ScmRights(received) => fds.extend(received),
+                ControlMessageOwned::ScmCredentials(creds) => credentials = Some(creds),
+                _ => (),
+            }
+        }
+
+        let error = if msg.flags.contains(MsgFlags::MSG_CTRUNC) || fds.len() > max_fds {
+            Errno::EMSGSIZE
+        } else if let Some(credentials) = credentials {
+            data.truncate(msg.bytes);
+            return Ok(Some(SeqpacketMessage { data, fds, credentials }));
+        } else if msg.bytes == 0 && fds.is_empty() {
+            // Every message carries credentials, so this is the end of the
+            // channel rather than an empty message.
+            return Ok(None);
+        } else {
+            Errno::EPROTO
+        };
+        for fd in fds {
+            let _ = crate::unistd::close(fd);
+        }
+        Err(Error::Sys(error))
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl AsRawFd for UnixSeqpacketChannel {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl IntoRawFd for UnixSeqpacketChannel {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl FromRawFd for UnixSeqpacketChannel {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        UnixSeqpacketChannel(Socket::from_raw_fd(fd))
+    }
+}
+
 /// Listen for connections on a socket
 ///
 /// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/listen.html)
@@ -1422,12 +2353,18 @@ pub fn accept(sockfd: RawFd) -> Result<RawFd> {
     Errno::result(res)
 }
 
-/// Accept a connection on a socket
+/// Accept a connection on a socket, atomically setting flags on the new
+/// socket.
+///
+/// With `SOCK_CLOEXEC`, the new socket can't leak into a child process that
+/// another thread `exec`s between the `accept` and a later `fcntl` call.
 ///
 /// [Further reading](http://man7.org/linux/man-pages/man2/accept.2.html)
 #[cfg(any(target_os = "android",
+          target_os = "dragonfly",
           target_os = "freebsd",
           target_os = "linux",
+          target_os = "netbsd",
           target_os = "openbsd"))]
 pub fn accept4(sockfd: RawFd, flags: SockFlag) -> Result<RawFd> {
     let res = unsafe { libc::accept4(sockfd, ptr::null_mut(), ptr::null_mut(), flags.bits()) };
@@ -1463,6 +2400,30 @@ pub fn recv(sockfd: RawFd, buf: &mut [u8], flags: MsgFlags) -> Result<usize> {
     }
 }
 
+/// Return the size of the next datagram queued on a socket, without removing
+/// it from the queue.
+///
+/// This is useful for allocating a buffer of the right size before receiving
+/// a message of variable length.  It works with the socket types for which
+/// `MsgFlags::MSG_TRUNC` reports the real length of a datagram, such as
+/// Internet datagram, raw, netlink and UNIX datagram sockets.  Like `recv`, it
+/// blocks until a datagram is available unless the socket is non-blocking.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::socket::*;
+/// let (a, b) = socketpair(AddressFamily::Unix, SockType::Datagram, None,
+///                         SockFlag::empty()).unwrap();
+/// send(a, b"hello", MsgFlags::empty()).unwrap();
+/// let mut buf = vec![0; recv_peek_len(b).unwrap()];
+/// assert_eq!(recv(b, &mut buf, MsgFlags::empty()).unwrap(), 5);
+/// ```
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn recv_peek_len(sockfd: RawFd) -> Result<usize> {
+    recv(sockfd, &mut [], MsgFlags::MSG_PEEK | MsgFlags::MSG_TRUNC)
+}
+
 /// Receive data from a connectionless or connection-oriented socket. Returns
 /// the number of bytes read and, for connectionless sockets,  the socket
 /// address of the sender.
@@ -1584,6 +2545,150 @@ pub fn setsockopt<O: SetSockOpt>(fd: RawFd, opt: O, val: &O::Val) -> Result<()>
     opt.set(fd, val)
 }
 
+/// Get the value of a socket option into a raw byte buffer, returning the
+/// number of bytes written.
+///
+/// This is an escape hatch for options that don't have a typed accessor in
+/// [`sockopt`](sockopt/index.html) yet.  `level` and `name` are passed
+/// directly to the operating system.  See also
+/// [`sockopt!`](../../macro.sockopt.html) for declaring typed options.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/getsockopt.html)
+pub fn getsockopt_raw(fd: RawFd, level: c_int, name: c_int, buf: &mut [u8])
+    -> Result<usize>
+{
+    let mut len = buf.len() as socklen_t;
+    let res = unsafe {
+        libc::getsockopt(fd, level, name, buf.as_mut_ptr() as *mut c_void,
+                         &mut len)
+    };
+
+    Errno::result(res).map(|_| len as usize)
+}
+
+/// Set the value of a socket option from a raw byte buffer.
+///
+/// This is an escape hatch for options that don't have a typed accessor in
+/// [`sockopt`](sockopt/index.html) yet.  `level` and `name` are passed
+/// directly to the operating system.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/setsockopt.html)
+pub fn setsockopt_raw(fd: RawFd, level: c_int, name: c_int, buf: &[u8])
+    -> Result<()>
+{
+    let res = unsafe {
+        libc::setsockopt(fd, level, name, buf.as_ptr() as *const c_void,
+                         buf.len() as socklen_t)
+    };
+
+    Errno::result(res).map(drop)
+}
+
+/// Declare a typed socket option, for use with [`getsockopt`](sys/socket/fn.getsockopt.html)
+/// and [`setsockopt`](sys/socket/fn.setsockopt.html).
+///
+/// The arguments to this macro are:
+///
+/// * `unsafe GetOnly`, `unsafe SetOnly` or `unsafe Both`: whether the option
+///   can be read, written or both
+/// * The name of the option type to generate
+/// * The socket level, like `libc::SOL_SOCKET`
+/// * The option name, like `libc::SO_RCVBUF`
+/// * The type of the option's value
+///
+/// Getting the option fails with `EINVAL` if the kernel returns fewer bytes
+/// than the size of the type, as older kernels may do for structs that have
+/// grown over time.
+///
+/// # Safety
+///
+/// The value is copied to and from the kernel byte by byte, and the generated
+/// `GetSockOpt` and `SetSockOpt` implementations are safe to use.  So the
+/// value type must be a plain C type, like an integer or a `#[repr(C)]`
+/// struct without padding, for which every bit pattern is valid.  Types like
+/// `bool`, enums and references are not.  The `unsafe` keyword in the
+/// invocation is where the caller asserts this.
+///
+/// # Example
+///
+/// ```
+/// # #[macro_use] extern crate nix;
+/// use nix::sys::socket::{getsockopt, socket, AddressFamily, SockFlag, SockType};
+///
+/// use nix::libc;
+///
+/// sockopt!(
+///     /// The type of a socket
+///     unsafe GetOnly, SocketType, libc::SOL_SOCKET, libc::SO_TYPE, libc::c_int
+/// );
+///
+/// # fn main() {
+/// let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+///     .unwrap();
+/// assert_eq!(getsockopt(fd, SocketType).unwrap(), libc::SOCK_DGRAM);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! sockopt {
+    ($(#[$attr:meta])* unsafe GetOnly, $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub struct $name;
+
+        $crate::sockopt!(@get $name, $level, $flag, $ty);
+    };
+
+    ($(#[$attr:meta])* unsafe SetOnly, $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub struct $name;
+
+        $crate::sockopt!(@set $name, $level, $flag, $ty);
+    };
+
+    ($(#[$attr:meta])* unsafe Both, $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub struct $name;
+
+        $crate::sockopt!(@get $name, $level, $flag, $ty);
+        $crate::sockopt!(@set $name, $level, $flag, $ty);
+    };
+
+    (@get $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        impl $crate::sys::socket::GetSockOpt for $name {
+            type Val = $ty;
+
+            fn get(&self, fd: ::std::os::unix::io::RawFd) -> $crate::Result<$ty> {
+                let len = ::std::mem::size_of::<$ty>();
+                let mut val = ::std::mem::MaybeUninit::<$ty>::zeroed();
+                let buf = unsafe {
+                    ::std::slice::from_raw_parts_mut(val.as_mut_ptr() as *mut u8, len)
+                };
+                let res = $crate::sys::socket::getsockopt_raw(fd, $level, $flag, buf)?;
+                if res != len {
+                    return Err($crate::Error::Sys($crate::errno::Errno::EINVAL));
+                }
+                Ok(unsafe { val.assume_init() })
+            }
+        }
+    };
+
+    (@set $name:ident, $level:expr, $flag:expr, $ty:ty) => {
+        impl $crate::sys::socket::SetSockOpt for $name {
+            type Val = $ty;
+
+            fn set(&self, fd: ::std::os::unix::io::RawFd, val: &$ty) -> $crate::Result<()> {
+                let buf = unsafe {
+                    ::std::slice::from_raw_parts(val as *const $ty as *const u8,
+                                                 ::std::mem::size_of::<$ty>())
+                };
+                $crate::sys::socket::setsockopt_raw(fd, $level, $flag, buf)
+            }
+        }
+    };
+}
+
 /// Get the address of the peer connected to the socket `fd`.
 ///
 /// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/getpeername.html)
@@ -1657,10 +2762,18 @@ pub fn sockaddr_storage_to_addr(
             Ok(SockAddr::Inet(InetAddr::V6(sin6)))
         }
         libc::AF_UNIX => {
-            let pathlen = len - offset_of!(sockaddr_un, sun_path);
+            let mut pathlen = len - offset_of!(sockaddr_un, sun_path);
             let sun = unsafe {
                 *(addr as *const _ as *const sockaddr_un)
             };
+            if pathlen > 0 && sun.sun_path[0] != 0 {
+                // The kernel may include the terminating null of a pathname
+                // in `len`, but UnixAddr excludes it.  Abstract names may
+                // legitimately contain nulls, so leave them alone.
+                pathlen = unsafe {
+                    libc::strnlen(sun.sun_path.as_ptr(), pathlen)
+                };
+            }
             Ok(SockAddr::Unix(UnixAddr(sun, pathlen)))
         }
         #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -1679,7 +2792,15 @@ pub fn sockaddr_storage_to_addr(
             };
             Ok(SockAddr::Alg(AlgAddr(salg)))
         }
-        #[cfg(target_os = "linux")]
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        libc::AF_PACKET => {
+            use libc::sockaddr_ll;
+            let sll = unsafe {
+                *(addr as *const _ as *const sockaddr_ll)
+            };
+            Ok(SockAddr::Link(LinkAddr(sll)))
+        }
+        #[cfg(any(target_os = "android", target_os = "linux"))]
         libc::AF_VSOCK => {
             use libc::sockaddr_vm;
             let svm = unsafe {
@@ -215,7 +215,21 @@ macro_rules! sockopt_impl {
 
 sockopt_impl!(Both, ReuseAddr, libc::SOL_SOCKET, libc::SO_REUSEADDR, bool);
 sockopt_impl!(Both, ReusePort, libc::SOL_SOCKET, libc::SO_REUSEPORT, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, IncomingCpu, libc::SOL_SOCKET, libc::SO_INCOMING_CPU, libc::c_int);
+#[cfg(target_os = "linux")]
+sockopt_impl!(SetOnly, AttachReusePortCbpf, libc::SOL_SOCKET, libc::SO_ATTACH_REUSEPORT_CBPF, libc::sock_fprog);
 sockopt_impl!(Both, TcpNoDelay, libc::IPPROTO_TCP, libc::TCP_NODELAY, bool);
+#[cfg(any(target_os = "android", target_os = "linux"))]
+sockopt_impl!(GetOnly, TcpInfo, libc::IPPROTO_TCP, libc::TCP_INFO, super::TcpInfo);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, SctpNoDelay, libc::IPPROTO_SCTP, libc::SCTP_NODELAY, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, SctpInitMsg, libc::IPPROTO_SCTP, libc::SCTP_INITMSG, libc::sctp_initmsg);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, SctpRecvRcvInfo, libc::IPPROTO_SCTP, libc::SCTP_RECVRCVINFO, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, SctpEvents, libc::IPPROTO_SCTP, libc::SCTP_EVENTS, super::SctpEventSubscribe);
 sockopt_impl!(Both, Linger, libc::SOL_SOCKET, libc::SO_LINGER, libc::linger);
 sockopt_impl!(SetOnly, IpAddMembership, libc::IPPROTO_IP, libc::IP_ADD_MEMBERSHIP, super::IpMembershipRequest);
 sockopt_impl!(SetOnly, IpDropMembership, libc::IPPROTO_IP, libc::IP_DROP_MEMBERSHIP, super::IpMembershipRequest);
@@ -235,6 +249,13 @@ cfg_if! {
 }
 sockopt_impl!(Both, IpMulticastTtl, libc::IPPROTO_IP, libc::IP_MULTICAST_TTL, u8);
 sockopt_impl!(Both, IpMulticastLoop, libc::IPPROTO_IP, libc::IP_MULTICAST_LOOP, bool);
+sockopt_impl!(Both, Ipv6MulticastHops, libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_HOPS, libc::c_int);
+sockopt_impl!(Both, Ipv6MulticastLoop, libc::IPPROTO_IPV6, libc::IPV6_MULTICAST_LOOP, bool);
+sockopt_impl!(Both, IpHdrIncl, libc::IPPROTO_IP, libc::IP_HDRINCL, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, Ipv4RecvErr, libc::IPPROTO_IP, libc::IP_RECVERR, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, Ipv6RecvErr, libc::IPPROTO_IPV6, libc::IPV6_RECVERR, bool);
 sockopt_impl!(Both, ReceiveTimeout, libc::SOL_SOCKET, libc::SO_RCVTIMEO, TimeVal);
 sockopt_impl!(Both, SendTimeout, libc::SOL_SOCKET, libc::SO_SNDTIMEO, TimeVal);
 sockopt_impl!(Both, Broadcast, libc::SOL_SOCKET, libc::SO_BROADCAST, bool);
@@ -250,8 +271,25 @@ sockopt_impl!(Both, TcpKeepAlive, libc::IPPROTO_TCP, libc::TCP_KEEPALIVE, u32);
           target_os = "dragonfly",
           target_os = "freebsd",
           target_os = "linux",
-          target_os = "nacl"))]
+          target_os = "nacl",
+          target_os = "netbsd"))]
 sockopt_impl!(Both, TcpKeepIdle, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE, u32);
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+sockopt_impl!(Both, TcpKeepCount, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, u32);
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+sockopt_impl!(Both, TcpKeepInterval, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, u32);
 sockopt_impl!(Both, RcvBuf, libc::SOL_SOCKET, libc::SO_RCVBUF, usize);
 sockopt_impl!(Both, SndBuf, libc::SOL_SOCKET, libc::SO_SNDBUF, usize);
 #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -262,9 +300,15 @@ sockopt_impl!(GetOnly, SockType, libc::SOL_SOCKET, libc::SO_TYPE, super::SockTyp
 sockopt_impl!(GetOnly, AcceptConn, libc::SOL_SOCKET, libc::SO_ACCEPTCONN, bool);
 #[cfg(any(target_os = "android", target_os = "linux"))]
 sockopt_impl!(Both, BindToDevice, libc::SOL_SOCKET, libc::SO_BINDTODEVICE, OsString<[u8; libc::IFNAMSIZ]>);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, BindToIfIndex, libc::SOL_SOCKET, libc::SO_BINDTOIFINDEX, u32);
 #[cfg(any(target_os = "android", target_os = "linux"))]
 sockopt_impl!(GetOnly, OriginalDst, libc::SOL_IP, libc::SO_ORIGINAL_DST, libc::sockaddr_in);
 sockopt_impl!(Both, ReceiveTimestamp, libc::SOL_SOCKET, libc::SO_TIMESTAMP, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, ReceiveTimestampNs, libc::SOL_SOCKET, libc::SO_TIMESTAMPNS, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, Timestamping, libc::SOL_SOCKET, libc::SO_TIMESTAMPING, super::TimestampingFlag);
 #[cfg(any(target_os = "android", target_os = "linux"))]
 sockopt_impl!(Both, IpTransparent, libc::SOL_IP, libc::IP_TRANSPARENT, bool);
 #[cfg(target_os = "openbsd")]
@@ -275,6 +319,8 @@ sockopt_impl!(Both, BindAny, libc::IPPROTO_IP, libc::IP_BINDANY, bool);
 sockopt_impl!(Both, Mark, libc::SOL_SOCKET, libc::SO_MARK, u32);
 #[cfg(any(target_os = "android", target_os = "linux"))]
 sockopt_impl!(Both, PassCred, libc::SOL_SOCKET, libc::SO_PASSCRED, bool);
+#[cfg(target_os = "linux")]
+sockopt_impl!(Both, ZeroCopy, libc::SOL_SOCKET, libc::SO_ZEROCOPY, bool);
 #[cfg(any(target_os = "freebsd", target_os = "linux"))] 
 sockopt_impl!(Both, TcpCongestion, libc::IPPROTO_TCP, libc::TCP_CONGESTION, OsString<[u8; TCP_CA_NAME_MAX]>);
 #[cfg(any(
@@ -610,7 +656,10 @@ unsafe impl<T: AsMut<[u8]>> Get<OsString> for GetOsString<T> {
     unsafe fn assume_init(self) -> OsString {
         let len = self.len as usize;
         let mut v = self.val.assume_init();
-        OsStr::from_bytes(&v.as_mut()[0..len]).to_owned()
+        let bytes = &v.as_mut()[0..len];
+        // The returned length may include the terminating null, and padding
+        let len = bytes.iter().position(|&b| b == 0).unwrap_or(len);
+        OsStr::from_bytes(&bytes[0..len]).to_owned()
     }
 }
 
@@ -640,12 +689,18 @@ mod test {
     #[test]
     fn can_get_peercred_on_unix_socket() {
         use super::super::*;
+        use crate::unistd::{close, getgid, getpid, getuid};
 
         let (a, b) = socketpair(AddressFamily::Unix, SockType::Stream, None, SockFlag::empty()).unwrap();
         let a_cred = getsockopt(a, super::PeerCredentials).unwrap();
         let b_cred = getsockopt(b, super::PeerCredentials).unwrap();
         assert_eq!(a_cred, b_cred);
-        assert!(a_cred.pid() != 0);
+        // Both ends were created by this process
+        assert_eq!(a_cred.pid(), getpid().as_raw());
+        assert_eq!(a_cred.uid(), getuid().as_raw());
+        assert_eq!(a_cred.gid(), getgid().as_raw());
+        close(a).unwrap();
+        close(b).unwrap();
     }
 
     #[test]
@@ -1,5 +1,7 @@
 use std::{cmp, fmt, ops};
-use std::convert::From;
+use std::convert::{From, TryFrom};
+use std::time::Duration;
+use crate::Error;
 use libc::{c_long, timespec, timeval};
 pub use libc::{time_t, suseconds_t};
 
@@ -144,7 +146,7 @@ impl TimeValLike for TimeSpec {
     }
 
     fn num_microseconds(&self) -> i64 {
-        self.num_nanoseconds() / 1_000_000_000
+        self.num_nanoseconds() / 1_000
     }
 
     fn num_nanoseconds(&self) -> i64 {
@@ -218,6 +220,31 @@ impl ops::Div<i32> for TimeSpec {
     }
 }
 
+/// Fails with `EINVAL` if the `Duration` is too long for a `TimeSpec`.
+impl TryFrom<Duration> for TimeSpec {
+    type Error = Error;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        if duration.as_secs() > TS_MAX_SECONDS as u64 {
+            return Err(Error::invalid_argument());
+        }
+        Ok(TimeSpec(timespec {tv_sec: duration.as_secs() as time_t,
+                              tv_nsec: duration.subsec_nanos() as c_long }))
+    }
+}
+
+/// Fails with `EINVAL` if the `TimeSpec` is negative.
+impl TryFrom<TimeSpec> for Duration {
+    type Error = Error;
+
+    fn try_from(timespec: TimeSpec) -> Result<Self, Self::Error> {
+        if timespec.tv_sec() < 0 {
+            return Err(Error::invalid_argument());
+        }
+        Ok(Duration::new(timespec.tv_sec() as u64, timespec.tv_nsec() as u32))
+    }
+}
+
 impl fmt::Display for TimeSpec {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let (abs, sign) = if self.tv_sec() < 0 {
@@ -453,6 +480,34 @@ impl From<timeval> for TimeVal {
     }
 }
 
+/// Fails with `EINVAL` if the `Duration` is too long for a `TimeVal`.  Some
+/// precision will be lost, since the `Duration` is truncated to whole
+/// microseconds.
+impl TryFrom<Duration> for TimeVal {
+    type Error = Error;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        if duration.as_secs() > TV_MAX_SECONDS as u64 {
+            return Err(Error::invalid_argument());
+        }
+        Ok(TimeVal(timeval {tv_sec: duration.as_secs() as time_t,
+                            tv_usec: duration.subsec_micros() as suseconds_t }))
+    }
+}
+
+/// Fails with `EINVAL` if the `TimeVal` is negative.
+impl TryFrom<TimeVal> for Duration {
+    type Error = Error;
+
+    fn try_from(timeval: TimeVal) -> Result<Self, Self::Error> {
+        if timeval.tv_sec() < 0 {
+            return Err(Error::invalid_argument());
+        }
+        Ok(Duration::new(timeval.tv_sec() as u64,
+                         timeval.tv_usec() as u32 * 1_000))
+    }
+}
+
 #[inline]
 fn div_mod_floor_64(this: i64, other: i64) -> (i64, i64) {
     (div_floor_64(this, other), mod_floor_64(this, other))
@@ -483,7 +538,9 @@ fn div_rem_64(this: i64, other: i64) -> (i64, i64) {
 
 #[cfg(test)]
 mod test {
-    use super::{TimeSpec, TimeVal, TimeValLike};
+    use super::{TimeSpec, TimeVal, TimeValLike, TS_MAX_SECONDS, TV_MAX_SECONDS};
+    use std::convert::TryFrom;
+    use std::time::Duration;
 
     #[test]
     pub fn test_timespec() {
@@ -511,6 +568,25 @@ mod test {
         assert!(TimeSpec::seconds(-1) > TimeSpec::nanoseconds(-1_000_000_001));
     }
 
+    #[test]
+    pub fn test_timespec_num() {
+        let ts = TimeSpec::nanoseconds(1_234_567_891);
+        assert_eq!(ts.num_seconds(), 1);
+        assert_eq!(ts.num_milliseconds(), 1_234);
+        assert_eq!(ts.num_microseconds(), 1_234_567);
+        assert_eq!(ts.num_nanoseconds(), 1_234_567_891);
+    }
+
+    #[test]
+    pub fn test_timespec_duration() {
+        let duration = Duration::new(5, 123_456_789);
+        let ts = TimeSpec::try_from(duration).unwrap();
+        assert_eq!(ts, TimeSpec::nanoseconds(5_123_456_789));
+        assert_eq!(Duration::try_from(ts).unwrap(), duration);
+        assert!(Duration::try_from(TimeSpec::nanoseconds(-1)).is_err());
+        assert!(TimeSpec::try_from(Duration::from_secs(TS_MAX_SECONDS as u64 + 1)).is_err());
+    }
+
     #[test]
     pub fn test_timespec_fmt() {
         assert_eq!(TimeSpec::zero().to_string(), "0 seconds");
@@ -547,6 +623,15 @@ mod test {
         assert_eq!(a, -b);
     }
 
+    #[test]
+    pub fn test_timeval_duration() {
+        let tv = TimeVal::try_from(Duration::new(5, 123_456_789)).unwrap();
+        assert_eq!(tv, TimeVal::microseconds(5_123_456));
+        assert_eq!(Duration::try_from(tv).unwrap(), Duration::new(5, 123_456_000));
+        assert!(Duration::try_from(TimeVal::microseconds(-1)).is_err());
+        assert!(TimeVal::try_from(Duration::from_secs(TV_MAX_SECONDS as u64 + 1)).is_err());
+    }
+
     #[test]
     pub fn test_timeval_fmt() {
         assert_eq!(TimeVal::zero().to_string(), "0 seconds");
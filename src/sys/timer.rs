@@ -0,0 +1,135 @@
+//! Per-process timers, which notify their expiration with a signal.
+//!
+//! Unlike a [`TimerFd`](../timerfd/struct.TimerFd.html), a `Timer` delivers
+//! a signal, optionally to a specific thread, and can use real-time signals
+//! whose `si_value` tells timers apart.
+//!
+//! For more documentation, please read [timer_create(2)](http://man7.org/linux/man-pages/man2/timer_create.2.html).
+//!
+//! # Examples
+//!
+//! Create a periodic timer that sends `SIGRTMIN` to the calling thread every
+//! 100 milliseconds.
+//! ```no_run
+//! # use nix::sys::signal::{SigEvent, SigevNotify, SigNum, SigSet};
+//! # use nix::sys::time::{TimeSpec, TimeValLike};
+//! # use nix::sys::timer::{Timer, TimerSetTimeFlags};
+//! # use nix::sys::timerfd::Expiration;
+//! # use nix::time::ClockId;
+//! # use nix::unistd::gettid;
+//! #
+//! let signal = SigNum::rtmin();
+//! let mut mask = SigSet::empty();
+//! mask.add(signal);
+//! mask.thread_block().unwrap();
+//!
+//! let sigevent = SigEvent::new(SigevNotify::SigevThreadIdNum {
+//!     signal,
+//!     thread_id: gettid().as_raw(),
+//!     si_value: 1,
+//! });
+//! let timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+//! timer.set(
+//!     Expiration::Interval(TimeSpec::milliseconds(100)),
+//!     TimerSetTimeFlags::empty()
+//! ).unwrap();
+//!
+//! loop {
+//!     let info = mask.wait_info().unwrap();
+//!     println!("timer {:?} expired", info.si_value().as_ptr());
+//! }
+//! ```
+use crate::sys::signal::SigEvent;
+use crate::sys::timerfd::{Expiration, TimerSpec};
+use crate::time::ClockId;
+use crate::{errno::Errno, Result};
+use bitflags::bitflags;
+use std::{mem, ptr};
+
+bitflags! {
+    /// Flags that are used for arming the timer.
+    pub struct TimerSetTimeFlags: libc::c_int {
+        /// The expiration time is absolute, instead of relative to now.
+        const TIMER_ABSTIME = libc::TIMER_ABSTIME;
+    }
+}
+
+/// A per-process timer, which is deleted when dropped.
+#[derive(Debug)]
+pub struct Timer(libc::timer_t);
+
+// timer_t is a pointer on Linux, but it's only an identifier that the kernel
+// hands out for the whole process.
+unsafe impl Send for Timer {}
+
+impl Timer {
+    /// Creates a new timer based on the clock defined by `clockid`, which
+    /// notifies its expirations as described by `sigevent`.
+    ///
+    /// The timer is disarmed until [`set`](#method.set) is called.
+    pub fn new(clockid: ClockId, sigevent: SigEvent) -> Result<Self> {
+        let mut sigevent = sigevent.sigevent();
+        let mut timer = mem::MaybeUninit::<libc::timer_t>::uninit();
+        Errno::result(unsafe {
+            libc::timer_create(clockid.as_raw(), &mut sigevent, timer.as_mut_ptr())
+        })
+        .map(|_| Timer(unsafe { timer.assume_init() }))
+    }
+
+    /// Arms the timer, replacing any previous alarm.
+    ///
+    /// The `TimeSpec`s of `expiration` are relative, unless `TIMER_ABSTIME`
+    /// is set in `flags`.  See
+    /// [`TimerFd::set`](../timerfd/struct.TimerFd.html#method.set) for the
+    /// meaning of each kind of `Expiration`.
+    pub fn set(&self, expiration: Expiration, flags: TimerSetTimeFlags) -> Result<()> {
+        let timerspec: TimerSpec = expiration.into();
+        Errno::result(unsafe {
+            libc::timer_settime(self.0, flags.bits(), timerspec.as_ref(), ptr::null_mut())
+        })
+        .map(drop)
+    }
+
+    /// Get the parameters for the alarm currently set, if any.  The start
+    /// time is relative to now.
+    pub fn get(&self) -> Result<Option<Expiration>> {
+        let mut timerspec = TimerSpec::none();
+        Errno::result(unsafe { libc::timer_gettime(self.0, &mut timerspec.0) }).map(|_| {
+            if timerspec.0.it_interval.tv_sec == 0
+                && timerspec.0.it_interval.tv_nsec == 0
+                && timerspec.0.it_value.tv_sec == 0
+                && timerspec.0.it_value.tv_nsec == 0
+            {
+                None
+            } else {
+                Some(timerspec.into())
+            }
+        })
+    }
+
+    /// Remove the alarm if any is set.
+    pub fn unset(&self) -> Result<()> {
+        Errno::result(unsafe {
+            libc::timer_settime(
+                self.0,
+                TimerSetTimeFlags::empty().bits(),
+                TimerSpec::none().as_ref(),
+                ptr::null_mut(),
+            )
+        })
+        .map(drop)
+    }
+
+    /// Returns how many extra expirations happened while the signal of the
+    /// last one was still pending, since only one signal per timer is
+    /// queued at a time.
+    pub fn overruns(&self) -> Result<libc::c_int> {
+        Errno::result(unsafe { libc::timer_getoverrun(self.0) })
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        unsafe { libc::timer_delete(self.0) };
+    }
+}
@@ -29,7 +29,7 @@
 //! timer.wait().unwrap();
 //! ```
 use crate::sys::time::TimeSpec;
-use crate::unistd::read;
+use crate::unistd::{read, OwnedFd};
 use crate::{errno::Errno, Error, Result};
 use bitflags::bitflags;
 use libc::c_int;
@@ -37,35 +37,30 @@ use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
 
 /// A timerfd instance. This is also a file descriptor, you can feed it to
 /// other interfaces consuming file descriptors, epoll for example.
-#[derive(Debug, Clone, Copy)]
+///
+/// The file descriptor is closed when the `TimerFd` is dropped.
+#[derive(Debug)]
 pub struct TimerFd {
-    fd: RawFd,
+    fd: OwnedFd,
 }
 
 impl AsRawFd for TimerFd {
     fn as_raw_fd(&self) -> RawFd {
-        self.fd
+        self.fd.as_raw_fd()
     }
 }
 
 impl FromRawFd for TimerFd {
     unsafe fn from_raw_fd(fd: RawFd) -> Self {
-        TimerFd { fd }
+        TimerFd { fd: OwnedFd::from_raw_fd(fd) }
     }
 }
 
-libc_enum! {
-    /// The type of the clock used to mark the progress of the timer. For more
-    /// details on each kind of clock, please refer to [timerfd_create(2)](http://man7.org/linux/man-pages/man2/timerfd_create.2.html).
-    #[repr(i32)]
-    pub enum ClockId {
-        CLOCK_REALTIME,
-        CLOCK_MONOTONIC,
-        CLOCK_BOOTTIME,
-        CLOCK_REALTIME_ALARM,
-        CLOCK_BOOTTIME_ALARM,
-    }
-}
+/// The clock used to mark the progress of the timer.  Only
+/// `CLOCK_REALTIME`, `CLOCK_MONOTONIC`, `CLOCK_BOOTTIME`,
+/// `CLOCK_REALTIME_ALARM` and `CLOCK_BOOTTIME_ALARM` are supported, see
+/// [timerfd_create(2)](http://man7.org/linux/man-pages/man2/timerfd_create.2.html).
+pub use crate::time::ClockId;
 
 libc_bitflags! {
     /// Additional flags to change the behaviour of the file descriptor at the
@@ -84,7 +79,7 @@ bitflags! {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct TimerSpec(libc::itimerspec);
+pub(crate) struct TimerSpec(pub(crate) libc::itimerspec);
 
 impl TimerSpec {
     pub fn none() -> Self {
@@ -168,8 +163,8 @@ impl TimerFd {
     /// underlying fd can be assigned specific flags with `flags` (CLOEXEC,
     /// NONBLOCK).
     pub fn new(clockid: ClockId, flags: TimerFlags) -> Result<Self> {
-        Errno::result(unsafe { libc::timerfd_create(clockid as i32, flags.bits()) })
-            .map(|fd| Self { fd })
+        Errno::result(unsafe { libc::timerfd_create(clockid.as_raw(), flags.bits()) })
+            .map(|fd| unsafe { Self::from_raw_fd(fd) })
     }
 
     /// Sets a new alarm on the timer.
@@ -212,7 +207,7 @@ impl TimerFd {
         let timerspec: TimerSpec = expiration.into();
         Errno::result(unsafe {
             libc::timerfd_settime(
-                self.fd,
+                self.fd.as_raw_fd(),
                 flags.bits(),
                 timerspec.as_ref(),
                 std::ptr::null_mut(),
@@ -226,7 +221,7 @@ impl TimerFd {
         let mut timerspec = TimerSpec::none();
         let timerspec_ptr: *mut libc::itimerspec = &mut timerspec.0;
 
-        Errno::result(unsafe { libc::timerfd_gettime(self.fd, timerspec_ptr) }).map(|_| {
+        Errno::result(unsafe { libc::timerfd_gettime(self.fd.as_raw_fd(), timerspec_ptr) }).map(|_| {
             if timerspec.0.it_interval.tv_sec == 0
                 && timerspec.0.it_interval.tv_nsec == 0
                 && timerspec.0.it_value.tv_sec == 0
@@ -243,7 +238,7 @@ impl TimerFd {
     pub fn unset(&self) -> Result<()> {
         Errno::result(unsafe {
             libc::timerfd_settime(
-                self.fd,
+                self.fd.as_raw_fd(),
                 TimerSetTimeFlags::empty().bits(),
                 TimerSpec::none().as_ref(),
                 std::ptr::null_mut(),
@@ -256,17 +251,22 @@ impl TimerFd {
     ///
     /// Note: If the alarm is unset, then you will wait forever.
     pub fn wait(&self) -> Result<()> {
+        self.read_expirations().map(drop)
+    }
+
+    /// Wait for the configured alarm to expire, and return how many times it
+    /// expired since the last read, which is more than one if an interval
+    /// timer was not read in time.
+    ///
+    /// With `TFD_NONBLOCK`, fails with `EAGAIN` instead of waiting.
+    pub fn read_expirations(&self) -> Result<u64> {
+        let mut buf = [0u8; 8];
         loop {
-            if let Err(e) = read(self.fd, &mut [0u8; 8]) {
-                match e {
-                    Error::Sys(Errno::EINTR) => continue,
-                    _ => return Err(e),
-                }
-            } else {
-                break;
+            match read(self.fd.as_raw_fd(), &mut buf) {
+                Err(Error::Sys(Errno::EINTR)) => continue,
+                Err(e) => return Err(e),
+                Ok(_) => return Ok(u64::from_ne_bytes(buf)),
             }
         }
-
-        Ok(())
     }
 }
@@ -4,6 +4,7 @@
 use crate::Result;
 use crate::errno::Errno;
 use libc::{self, c_int, c_void, size_t, off_t};
+use std::io::{IoSlice, IoSliceMut};
 use std::marker::PhantomData;
 use std::os::unix::io::RawFd;
 
@@ -60,6 +61,65 @@ pub fn preadv(fd: RawFd, iov: &[IoVec<&mut [u8]>],
     Errno::result(res).map(|r| r as usize)
 }
 
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+libc_bitflags! {
+    /// Per-call flags for [`preadv2`](fn.preadv2.html) and
+    /// [`pwritev2`](fn.pwritev2.html).
+    pub struct RwFlags: c_int {
+        /// High priority request, poll if possible.
+        RWF_HIPRI;
+        /// Per-IO equivalent of `O_DSYNC`.
+        RWF_DSYNC;
+        /// Per-IO equivalent of `O_SYNC`.
+        RWF_SYNC;
+        /// Don't wait if the I/O would block, e.g. on a page cache miss.
+        ///
+        /// Only valid for reads.  Fails with `EAGAIN` if the data isn't
+        /// immediately available.
+        RWF_NOWAIT;
+        /// Per-IO equivalent of `O_APPEND`.
+        ///
+        /// Only valid for writes.
+        RWF_APPEND;
+    }
+}
+
+/// Write to `fd` at `offset` from buffers in `iov`, modified by `flags`.
+///
+/// This is the same as [`pwritev`](fn.pwritev.html), except that `flags`
+/// alter the behavior of this call only.  If `offset` is -1, the current
+/// file offset is used and updated instead.
+///
+/// See also [pwritev2(2)](http://man7.org/linux/man-pages/man2/pwritev2.2.html)
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn pwritev2(fd: RawFd, iov: &[IoVec<&[u8]>], offset: off_t,
+                flags: RwFlags) -> Result<usize> {
+    let res = unsafe {
+        libc::pwritev2(fd, iov.as_ptr() as *const libc::iovec, iov.len() as c_int,
+                       offset, flags.bits())
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
+/// Read from `fd` at `offset` filling buffers in `iov`, modified by `flags`.
+///
+/// This is the same as [`preadv`](fn.preadv.html), except that `flags`
+/// alter the behavior of this call only.  If `offset` is -1, the current
+/// file offset is used and updated instead.
+///
+/// See also [preadv2(2)](http://man7.org/linux/man-pages/man2/preadv2.2.html)
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn preadv2(fd: RawFd, iov: &[IoVec<&mut [u8]>], offset: off_t,
+               flags: RwFlags) -> Result<usize> {
+    let res = unsafe {
+        libc::preadv2(fd, iov.as_ptr() as *const libc::iovec, iov.len() as c_int,
+                      offset, flags.bits())
+    };
+
+    Errno::result(res).map(|r| r as usize)
+}
+
 pub fn pwrite(fd: RawFd, buf: &[u8], offset: off_t) -> Result<usize> {
     let res = unsafe {
         libc::pwrite(fd, buf.as_ptr() as *const c_void, buf.len() as size_t,
@@ -200,3 +260,45 @@ impl<'a> IoVec<&'a mut [u8]> {
         }, PhantomData)
     }
 }
+
+impl<'a> From<IoSlice<'a>> for IoVec<&'a [u8]> {
+    fn from(buf: IoSlice<'a>) -> Self {
+        // The IoSlice borrows its buffer for 'a, so the IoVec may too.
+        IoVec(libc::iovec {
+            iov_base: buf.as_ptr() as *mut c_void,
+            iov_len: buf.len() as size_t,
+        }, PhantomData)
+    }
+}
+
+impl<'a> From<IoVec<&'a [u8]>> for IoSlice<'a> {
+    fn from(iov: IoVec<&'a [u8]>) -> Self {
+        use std::slice;
+
+        IoSlice::new(unsafe {
+            slice::from_raw_parts(iov.0.iov_base as *const u8,
+                                  iov.0.iov_len)
+        })
+    }
+}
+
+impl<'a> From<IoSliceMut<'a>> for IoVec<&'a mut [u8]> {
+    fn from(mut buf: IoSliceMut<'a>) -> Self {
+        // The IoSliceMut borrows its buffer for 'a, so the IoVec may too.
+        IoVec(libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut c_void,
+            iov_len: buf.len() as size_t,
+        }, PhantomData)
+    }
+}
+
+impl<'a> From<IoVec<&'a mut [u8]>> for IoSliceMut<'a> {
+    fn from(iov: IoVec<&'a mut [u8]>) -> Self {
+        use std::slice;
+
+        IoSliceMut::new(unsafe {
+            slice::from_raw_parts_mut(iov.0.iov_base as *mut u8,
+                                      iov.0.iov_len)
+        })
+    }
+}
@@ -1,10 +1,17 @@
 use cfg_if::cfg_if;
 use libc::{self, c_int};
+use crate::Error;
 use crate::Result;
 use crate::errno::Errno;
 use crate::unistd::Pid;
-use crate::sys::signal::Signal;
+#[cfg(not(target_os = "redox"))]
+use crate::sys::resource::Rusage;
+use crate::sys::signal::{SigNum, Signal};
 use std::convert::TryFrom;
+#[cfg(not(target_os = "redox"))]
+use std::mem;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::RawFd;
 
 libc_bitflags!(
     pub struct WaitPidFlag: c_int {
@@ -56,9 +63,9 @@ libc_bitflags!(
 /// in a child process `Pid`, such as the process exiting or stopping,
 /// plus additional data about the transition if any.
 ///
-/// Note that there are two Linux-specific enum variants, `PtraceEvent`
-/// and `PtraceSyscall`. Portable code should avoid exhaustively
-/// matching on `WaitStatus`.
+/// Note that there are four Linux-specific enum variants, `RtSignaled`,
+/// `RtStopped`, `PtraceEvent` and `PtraceSyscall`. Portable code should
+/// avoid exhaustively matching on `WaitStatus`.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum WaitStatus {
     /// The process exited normally (as with `exit()` or returning from
@@ -70,11 +77,20 @@ pub enum WaitStatus {
     /// matches the C macro `WIFSIGNALED(status)`; the last two fields
     /// correspond to `WTERMSIG(status)` and `WCOREDUMP(status)`.
     Signaled(Pid, Signal, bool),
+    /// The process was killed by the given real-time signal.  The fields
+    /// have the same meaning as for `Signaled`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    RtSignaled(Pid, SigNum, bool),
     /// The process is alive, but was stopped by the given signal. This
     /// is only reported if `WaitPidFlag::WUNTRACED` was passed. This
     /// case matches the C macro `WIFSTOPPED(status)`; the second field
     /// is `WSTOPSIG(status)`.
     Stopped(Pid, Signal),
+    /// The process is alive, but was stopped by the given real-time signal,
+    /// which can happen while it is traced.  The fields have the same meaning
+    /// as for `Stopped`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    RtStopped(Pid, SigNum),
     /// The traced process was stopped by a `PTRACE_EVENT_*` event. See
     /// [`nix::sys::ptrace`] and [`ptrace`(2)] for more information. All
     /// currently-defined events use `SIGTRAP` as the signal; the third
@@ -112,7 +128,8 @@ impl WaitStatus {
                 Stopped(p, _) | Continued(p) => Some(p),
             StillAlive => None,
             #[cfg(any(target_os = "android", target_os = "linux"))]
-            PtraceEvent(p, _, _) | PtraceSyscall(p) => Some(p),
+            RtSignaled(p, _, _) | RtStopped(p, _) |
+                PtraceEvent(p, _, _) | PtraceSyscall(p) => Some(p),
         }
     }
 }
@@ -129,8 +146,8 @@ fn signaled(status: i32) -> bool {
     unsafe { libc::WIFSIGNALED(status) }
 }
 
-fn term_signal(status: i32) -> Result<Signal> {
-    Signal::try_from(unsafe { libc::WTERMSIG(status) })
+fn term_signal(status: i32) -> c_int {
+    unsafe { libc::WTERMSIG(status) }
 }
 
 fn dumped_core(status: i32) -> bool {
@@ -141,8 +158,30 @@ fn stopped(status: i32) -> bool {
     unsafe { libc::WIFSTOPPED(status) }
 }
 
-fn stop_signal(status: i32) -> Result<Signal> {
-    Signal::try_from(unsafe { libc::WSTOPSIG(status) })
+fn stop_signal(status: i32) -> c_int {
+    unsafe { libc::WSTOPSIG(status) }
+}
+
+fn signaled_status(pid: Pid, signum: c_int, dumped: bool) -> Result<WaitStatus> {
+    let signum = SigNum::from_raw(signum)?;
+    match Signal::try_from(signum) {
+        Ok(signal) => Ok(WaitStatus::Signaled(pid, signal, dumped)),
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        Err(_) => Ok(WaitStatus::RtSignaled(pid, signum, dumped)),
+        #[cfg(not(any(target_os = "android", target_os = "linux")))]
+        Err(e) => Err(e),
+    }
+}
+
+fn stopped_status(pid: Pid, signum: c_int) -> Result<WaitStatus> {
+    let signum = SigNum::from_raw(signum)?;
+    match Signal::try_from(signum) {
+        Ok(signal) => Ok(WaitStatus::Stopped(pid, signal)),
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        Err(_) => Ok(WaitStatus::RtStopped(pid, signum)),
+        #[cfg(not(any(target_os = "android", target_os = "linux")))]
+        Err(e) => Err(e),
+    }
 }
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -185,7 +224,7 @@ impl WaitStatus {
         Ok(if exited(status) {
             WaitStatus::Exited(pid, exit_status(status))
         } else if signaled(status) {
-            WaitStatus::Signaled(pid, term_signal(status)?, dumped_core(status))
+            return signaled_status(pid, term_signal(status), dumped_core(status));
         } else if stopped(status) {
             cfg_if! {
                 if #[cfg(any(target_os = "android", target_os = "linux"))] {
@@ -194,26 +233,69 @@ impl WaitStatus {
                         Ok(if syscall_stop(status) {
                             WaitStatus::PtraceSyscall(pid)
                         } else if status_additional == 0 {
-                            WaitStatus::Stopped(pid, stop_signal(status)?)
+                            return stopped_status(pid, stop_signal(status));
                         } else {
-                            WaitStatus::PtraceEvent(pid, stop_signal(status)?,
+                            WaitStatus::PtraceEvent(pid, Signal::try_from(stop_signal(status))?,
                                                     stop_additional(status))
                         })
                     }
                 } else {
                     fn decode_stopped(pid: Pid, status: i32) -> Result<WaitStatus> {
-                        Ok(WaitStatus::Stopped(pid, stop_signal(status)?))
+                        stopped_status(pid, stop_signal(status))
                     }
                 }
             }
             return decode_stopped(pid, status);
-        } else {
-            assert!(continued(status));
+        } else if continued(status) {
             WaitStatus::Continued(pid)
+        } else {
+            return Err(Error::invalid_argument());
         })
     }
 }
 
+impl WaitStatus {
+    /// Convert a `siginfo_t` as filled in by `waitid` into a `WaitStatus`.
+    ///
+    /// A `si_pid` of 0 means that no child changed state, which `waitid`
+    /// reports when called with `WNOHANG`.
+    #[cfg(any(target_os = "android",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "netbsd"))]
+    fn from_siginfo(siginfo: &libc::siginfo_t) -> Result<WaitStatus> {
+        let si_pid = unsafe { siginfo.si_pid() };
+        if si_pid == 0 {
+            return Ok(WaitStatus::StillAlive);
+        }
+        let pid = Pid::from_raw(si_pid);
+        let si_status = unsafe { siginfo.si_status() };
+
+        match siginfo.si_code {
+            libc::CLD_EXITED => Ok(WaitStatus::Exited(pid, si_status)),
+            libc::CLD_KILLED | libc::CLD_DUMPED => {
+                signaled_status(pid, si_status, siginfo.si_code == libc::CLD_DUMPED)
+            },
+            libc::CLD_STOPPED => stopped_status(pid, si_status),
+            libc::CLD_CONTINUED => Ok(WaitStatus::Continued(pid)),
+            #[cfg(any(target_os = "android", target_os = "linux"))]
+            libc::CLD_TRAPPED => {
+                if si_status == libc::SIGTRAP | 0x80 {
+                    Ok(WaitStatus::PtraceSyscall(pid))
+                } else if si_status >> 8 == 0 {
+                    stopped_status(pid, si_status)
+                } else {
+                    Ok(WaitStatus::PtraceEvent(pid, Signal::try_from(si_status & 0xff)?,
+                                               (si_status >> 8) as c_int))
+                }
+            },
+            _ => Err(Error::invalid_argument()),
+        }
+    }
+}
+
 pub fn waitpid<P: Into<Option<Pid>>>(pid: P, options: Option<WaitPidFlag>) -> Result<WaitStatus> {
     use self::WaitStatus::*;
 
@@ -241,3 +323,128 @@ pub fn waitpid<P: Into<Option<Pid>>>(pid: P, options: Option<WaitPidFlag>) -> Re
 pub fn wait() -> Result<WaitStatus> {
     waitpid(None, None)
 }
+
+/// Wait for a process to change state like `waitpid`, and also return the
+/// resource usage of the child (see
+/// [wait4(2)](http://man7.org/linux/man-pages/man2/wait4.2.html)).
+///
+/// The resource usage covers the child and its own waited-for descendants.
+/// It's only meaningful if the child terminated, and is zeroed if `WNOHANG`
+/// was given and no child has changed state.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::wait::{wait4, WaitStatus};
+/// # use nix::unistd::{fork, ForkResult};
+/// match fork().unwrap() {
+///     ForkResult::Child => unsafe { libc::_exit(0) },
+///     ForkResult::Parent { child } => {
+///         let (status, rusage) = wait4(child, None).unwrap();
+///         assert_eq!(status, WaitStatus::Exited(child, 0));
+///         println!("Child used {} of CPU time", rusage.user_time());
+///     }
+/// }
+/// ```
+#[cfg(not(target_os = "redox"))]
+pub fn wait4<P: Into<Option<Pid>>>(pid: P, options: Option<WaitPidFlag>)
+    -> Result<(WaitStatus, Rusage)>
+{
+    let mut status: i32 = 0;
+    let mut rusage: libc::rusage = unsafe { mem::zeroed() };
+
+    let option_bits = match options {
+        Some(bits) => bits.bits(),
+        None => 0,
+    };
+
+    let res = unsafe {
+        libc::wait4(
+            pid.into().unwrap_or_else(|| Pid::from_raw(-1)).into(),
+            &mut status as *mut c_int,
+            option_bits,
+            &mut rusage as *mut libc::rusage,
+        )
+    };
+
+    let status = match Errno::result(res)? {
+        0 => WaitStatus::StillAlive,
+        res => WaitStatus::from_raw(Pid::from_raw(res), status)?,
+    };
+    Ok((status, Rusage::from(rusage)))
+}
+
+/// The ID argument for `waitid`
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Id {
+    /// Wait for any child
+    All,
+    /// Wait for the child whose process ID matches the given PID
+    Pid(Pid),
+    /// Wait for the child whose process group ID matches the given PID
+    ///
+    /// If the PID is zero, the caller's process group is used since Linux 5.4.
+    PGid(Pid),
+    /// Wait for the child referred to by the given process file descriptor
+    /// (see [`PidFd`](../pidfd/struct.PidFd.html)).  Requires Linux 5.4.
+    #[cfg(target_os = "linux")]
+    PidFd(RawFd),
+}
+
+/// Wait for a process to change state (see
+/// [waitid(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/waitid.html)).
+///
+/// Unlike `waitpid`, `flags` must explicitly request the state changes of
+/// interest with at least one of `WEXITED`, `WSTOPPED` and `WCONTINUED`.  If
+/// `WNOWAIT` is also given, the child is left in a waitable state, so a later
+/// call can wait for it again.  With `WNOHANG`, `WaitStatus::StillAlive` is
+/// returned if no child has changed state yet.
+///
+/// # Examples
+///
+/// Peek at a child's exit status before reaping it.
+/// ```
+/// # use nix::sys::wait::{waitid, waitpid, Id, WaitPidFlag, WaitStatus};
+/// # use nix::unistd::{fork, ForkResult};
+/// match fork().unwrap() {
+///     ForkResult::Child => unsafe { libc::_exit(3) },
+///     ForkResult::Parent { child } => {
+///         let flags = WaitPidFlag::WEXITED | WaitPidFlag::WNOWAIT;
+///         let status = waitid(Id::Pid(child), flags).unwrap();
+///         assert_eq!(status, WaitStatus::Exited(child, 3));
+///         // The child is still a zombie, so it can be reaped normally
+///         assert_eq!(waitpid(child, None).unwrap(), status);
+///     }
+/// }
+/// ```
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+pub fn waitid(id: Id, flags: WaitPidFlag) -> Result<WaitStatus> {
+    let (idtype, idval) = match id {
+        Id::All => (libc::P_ALL, 0),
+        Id::Pid(pid) => (libc::P_PID, pid.as_raw() as libc::id_t),
+        Id::PGid(pid) => (libc::P_PGID, pid.as_raw() as libc::id_t),
+        #[cfg(target_os = "linux")]
+        Id::PidFd(fd) => (libc::P_PIDFD, fd as libc::id_t),
+    };
+
+    let siginfo = unsafe {
+        // Memory is zeroed rather than uninitialized, as not all platforms
+        // initialize the memory in the StillAlive case
+        let mut siginfo: libc::siginfo_t = mem::zeroed();
+        Errno::result(libc::waitid(idtype, idval, &mut siginfo, flags.bits()))?;
+        siginfo
+    };
+
+    WaitStatus::from_siginfo(&siginfo)
+}
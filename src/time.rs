@@ -0,0 +1,625 @@
+//! Clocks and the time they keep.
+//!
+//! See [clock_gettime(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_gettime.html)
+//! for the meaning of each clock.
+use crate::sys::time::TimeSpec;
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+use crate::sys::time::TimeValLike;
+#[cfg(target_os = "linux")]
+use crate::sys::time::TimeVal;
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+use crate::unistd::Pid;
+use crate::{errno::Errno, Result};
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+use crate::Error;
+use libc::{self, clockid_t};
+use std::fmt;
+#[cfg(target_os = "linux")]
+use std::mem;
+use std::mem::MaybeUninit;
+
+/// A clock identifier.
+///
+/// Besides the system-wide clocks listed as associated constants, a
+/// `ClockId` can name the CPU-time clock of another process (see
+/// [`pid_cpu_clock_id`](#method.pid_cpu_clock_id)), so it wraps the raw
+/// `clockid_t` instead of being an enum.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ClockId(clockid_t);
+
+impl ClockId {
+    /// Creates a `ClockId` from a raw `clockid_t`.
+    pub fn from_raw(clk_id: clockid_t) -> Self {
+        ClockId(clk_id)
+    }
+
+    /// Returns the raw `clockid_t` wrapped by `self`.
+    pub fn as_raw(self) -> clockid_t {
+        self.0
+    }
+
+    /// Returns the `ClockId` of the CPU-time clock of process `pid`.
+    ///
+    /// See [`clock_getcpuclockid`](fn.clock_getcpuclockid.html).
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux"))]
+    pub fn pid_cpu_clock_id(pid: Pid) -> Result<Self> {
+        clock_getcpuclockid(pid)
+    }
+
+    /// Returns the resolution of the clock.
+    ///
+    /// See [`clock_getres`](fn.clock_getres.html).
+    #[cfg(not(target_os = "redox"))]
+    pub fn res(self) -> Result<TimeSpec> {
+        clock_getres(self)
+    }
+
+    /// Returns the current time of the clock.
+    ///
+    /// See [`clock_gettime`](fn.clock_gettime.html).
+    pub fn now(self) -> Result<TimeSpec> {
+        clock_gettime(self)
+    }
+
+    /// Sets the time of the clock.
+    ///
+    /// See [`clock_settime`](fn.clock_settime.html).
+    #[cfg(not(any(target_os = "ios", target_os = "redox")))]
+    pub fn set_time(self, timespec: TimeSpec) -> Result<()> {
+        clock_settime(self, timespec)
+    }
+
+    /// System-wide clock that measures real (wall-clock) time.  It follows
+    /// any change of the system time.
+    pub const CLOCK_REALTIME: ClockId = ClockId(libc::CLOCK_REALTIME);
+    /// Like `CLOCK_REALTIME`, but cheaper to read and less precise.
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux"))]
+    pub const CLOCK_REALTIME_COARSE: ClockId = ClockId(libc::CLOCK_REALTIME_COARSE);
+    /// Like `CLOCK_REALTIME`, but timers on this clock wake the system up
+    /// from suspend.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub const CLOCK_REALTIME_ALARM: ClockId = ClockId(libc::CLOCK_REALTIME_ALARM);
+    /// International Atomic Time, which unlike `CLOCK_REALTIME` does not
+    /// have leap seconds.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub const CLOCK_TAI: ClockId = ClockId(libc::CLOCK_TAI);
+    /// Clock that cannot be set and represents monotonic time since some
+    /// unspecified starting point.
+    pub const CLOCK_MONOTONIC: ClockId = ClockId(libc::CLOCK_MONOTONIC);
+    /// Like `CLOCK_MONOTONIC`, but cheaper to read and less precise.
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux"))]
+    pub const CLOCK_MONOTONIC_COARSE: ClockId = ClockId(libc::CLOCK_MONOTONIC_COARSE);
+    /// Like `CLOCK_MONOTONIC`, but not subject to NTP adjustments.
+    #[cfg(any(target_os = "android",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    pub const CLOCK_MONOTONIC_RAW: ClockId = ClockId(libc::CLOCK_MONOTONIC_RAW);
+    /// Like `CLOCK_MONOTONIC`, but also counts the time that the system is
+    /// suspended.
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "linux"))]
+    pub const CLOCK_BOOTTIME: ClockId = ClockId(libc::CLOCK_BOOTTIME);
+    /// Like `CLOCK_BOOTTIME`, but timers on this clock wake the system up
+    /// from suspend.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub const CLOCK_BOOTTIME_ALARM: ClockId = ClockId(libc::CLOCK_BOOTTIME_ALARM);
+    /// Monotonic time that does not count the time that the system is
+    /// suspended.
+    #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+    pub const CLOCK_UPTIME: ClockId = ClockId(libc::CLOCK_UPTIME);
+    /// Like `CLOCK_MONOTONIC_RAW`, but does not count the time that the
+    /// system is suspended.
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    pub const CLOCK_UPTIME_RAW: ClockId = ClockId(libc::CLOCK_UPTIME_RAW);
+    /// CPU time consumed by the calling process.
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos",
+              target_os = "redox"))]
+    pub const CLOCK_PROCESS_CPUTIME_ID: ClockId = ClockId(libc::CLOCK_PROCESS_CPUTIME_ID);
+    /// CPU time consumed by the calling thread.
+    #[cfg(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "freebsd",
+              target_os = "ios",
+              target_os = "linux",
+              target_os = "macos"))]
+    pub const CLOCK_THREAD_CPUTIME_ID: ClockId = ClockId(libc::CLOCK_THREAD_CPUTIME_ID);
+}
+
+impl From<clockid_t> for ClockId {
+    fn from(clk_id: clockid_t) -> Self {
+        ClockId(clk_id)
+    }
+}
+
+impl From<ClockId> for clockid_t {
+    fn from(clock_id: ClockId) -> Self {
+        clock_id.0
+    }
+}
+
+impl fmt::Display for ClockId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Get the resolution of the clock `clock_id`.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_getres.html)
+#[cfg(not(target_os = "redox"))]
+pub fn clock_getres(clock_id: ClockId) -> Result<TimeSpec> {
+    let mut ts = MaybeUninit::<libc::timespec>::uninit();
+    let res = unsafe { libc::clock_getres(clock_id.as_raw(), ts.as_mut_ptr()) };
+    Errno::result(res).map(|_| TimeSpec::from(unsafe { ts.assume_init() }))
+}
+
+/// Get the current time of the clock `clock_id`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::time::{clock_gettime, ClockId};
+/// let start = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+/// let end = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+/// assert!(end >= start);
+/// ```
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_gettime.html)
+pub fn clock_gettime(clock_id: ClockId) -> Result<TimeSpec> {
+    let mut ts = MaybeUninit::<libc::timespec>::uninit();
+    let res = unsafe { libc::clock_gettime(clock_id.as_raw(), ts.as_mut_ptr()) };
+    Errno::result(res).map(|_| TimeSpec::from(unsafe { ts.assume_init() }))
+}
+
+/// Set the time of the clock `clock_id`.
+///
+/// Only settable clocks such as `CLOCK_REALTIME` can be set, and doing so
+/// usually requires special privileges.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_settime.html)
+#[cfg(not(any(target_os = "ios", target_os = "redox")))]
+pub fn clock_settime(clock_id: ClockId, timespec: TimeSpec) -> Result<()> {
+    let res = unsafe { libc::clock_settime(clock_id.as_raw(), timespec.as_ref()) };
+    Errno::result(res).map(drop)
+}
+
+/// Get the clock that measures the CPU time consumed by process `pid`.
+///
+/// The returned clock can be read with [`clock_gettime`](fn.clock_gettime.html)
+/// like any other.  Fails with `ESRCH` if there is no such process.
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_getcpuclockid.html)
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+pub fn clock_getcpuclockid(pid: Pid) -> Result<ClockId> {
+    let mut clk_id = MaybeUninit::<clockid_t>::uninit();
+    let res = unsafe { libc::clock_getcpuclockid(pid.into(), clk_id.as_mut_ptr()) };
+    // clock_getcpuclockid returns the error number instead of setting errno
+    if res == 0 {
+        Ok(ClockId(unsafe { clk_id.assume_init() }))
+    } else {
+        Err(Error::Sys(Errno::from_i32(res)))
+    }
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+libc_bitflags! {
+    /// Flags for [`clock_nanosleep`](fn.clock_nanosleep.html)
+    pub struct ClockNanosleepFlags: libc::c_int {
+        /// The requested time is an absolute time of the clock, instead of
+        /// an interval relative to now.
+        TIMER_ABSTIME;
+    }
+}
+
+/// Suspend the calling thread until the time `request` has elapsed on the
+/// clock `clock_id`, or with `TIMER_ABSTIME` until the clock reaches
+/// `request`.
+///
+/// Returns `None` once the sleep is over.  If a signal handler interrupts the
+/// sleep, returns the time to pass as `request` to resume it: the remaining
+/// time of a relative sleep, or `request` itself for an absolute one.
+/// Sleeping until an absolute deadline lets periodic loops run without
+/// drift, since the time spent in each iteration doesn't delay the next one.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::sys::time::{TimeSpec, TimeValLike};
+/// # use nix::time::{clock_gettime, clock_nanosleep, ClockId, ClockNanosleepFlags};
+/// let period = TimeSpec::milliseconds(10);
+/// let mut deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+/// for _ in 0..3 {
+///     // Do some periodic work here
+///     deadline = deadline + period;
+///     let mut request = Some(deadline);
+///     while let Some(t) = request {
+///         request = clock_nanosleep(ClockId::CLOCK_MONOTONIC,
+///                                   ClockNanosleepFlags::TIMER_ABSTIME,
+///                                   &t).unwrap();
+///     }
+/// }
+/// ```
+///
+/// [Further reading](http://pubs.opengroup.org/onlinepubs/9699919799/functions/clock_nanosleep.html)
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+pub fn clock_nanosleep(clock_id: ClockId,
+                       flags: ClockNanosleepFlags,
+                       request: &TimeSpec) -> Result<Option<TimeSpec>>
+{
+    let mut remain = TimeSpec::zero();
+    // clock_nanosleep returns the error number instead of setting errno
+    let res = unsafe {
+        libc::clock_nanosleep(clock_id.as_raw(), flags.bits(), request.as_ref(),
+                              remain.as_mut())
+    };
+    match res {
+        0 => Ok(None),
+        libc::EINTR if flags.contains(ClockNanosleepFlags::TIMER_ABSTIME) => {
+            Ok(Some(*request))
+        },
+        libc::EINTR => Ok(Some(remain)),
+        _ => Err(Error::Sys(Errno::from_i32(res))),
+    }
+}
+
+#[cfg(target_os = "linux")]
+libc_bitflags! {
+    /// Which fields of a [`TimeX`](struct.TimeX.html) are applied by
+    /// [`adjtimex`](fn.adjtimex.html).
+    ///
+    /// The setters of `TimeX` add the matching flag themselves.
+    pub struct TimexModes: libc::c_uint {
+        /// Apply the time offset
+        ADJ_OFFSET;
+        /// Apply the frequency offset
+        ADJ_FREQUENCY;
+        /// Apply the maximum error
+        ADJ_MAXERROR;
+        /// Apply the estimated error
+        ADJ_ESTERROR;
+        /// Apply the clock status bits
+        ADJ_STATUS;
+        /// Apply the PLL time constant
+        ADJ_TIMECONST;
+        /// Apply the TAI offset
+        ADJ_TAI;
+        /// Add the `time` field to the current time
+        ADJ_SETOFFSET;
+        /// Select microsecond resolution for the offset and time fields
+        ADJ_MICRO;
+        /// Select nanosecond resolution for the offset and time fields
+        ADJ_NANO;
+        /// Apply the tick value
+        ADJ_TICK;
+        /// Old-fashioned `adjtime`: slowly apply the time offset
+        ADJ_OFFSET_SINGLESHOT;
+        /// Return the remaining offset of an `ADJ_OFFSET_SINGLESHOT`
+        /// adjustment
+        ADJ_OFFSET_SS_READ;
+    }
+}
+
+#[cfg(target_os = "linux")]
+libc_bitflags! {
+    /// Status bits of the kernel clock, in a [`TimeX`](struct.TimeX.html).
+    pub struct TimexStatus: libc::c_int {
+        /// Enable phase-locked loop updates
+        STA_PLL;
+        /// Enable PPS frequency discipline
+        STA_PPSFREQ;
+        /// Enable PPS time discipline
+        STA_PPSTIME;
+        /// Select frequency-locked loop mode
+        STA_FLL;
+        /// Insert a leap second at the end of the UTC day
+        STA_INS;
+        /// Delete a leap second at the end of the UTC day
+        STA_DEL;
+        /// The clock is not synchronized
+        STA_UNSYNC;
+        /// Hold the frequency
+        STA_FREQHOLD;
+        /// A valid PPS signal is present (read-only)
+        STA_PPSSIGNAL;
+        /// PPS signal jitter exceeded (read-only)
+        STA_PPSJITTER;
+        /// PPS signal wander exceeded (read-only)
+        STA_PPSWANDER;
+        /// PPS signal calibration error (read-only)
+        STA_PPSERROR;
+        /// Clock hardware fault (read-only)
+        STA_CLOCKERR;
+        /// Resolution is nanoseconds instead of microseconds (read-only)
+        STA_NANO;
+        /// Mode is frequency-locked loop instead of phase-locked loop
+        /// (read-only)
+        STA_MODE;
+        /// Clock source is B instead of A (read-only)
+        STA_CLK;
+    }
+}
+
+#[cfg(target_os = "linux")]
+libc_enum! {
+    /// State of the kernel clock, as returned by
+    /// [`adjtimex`](fn.adjtimex.html).
+    #[repr(i32)]
+    pub enum ClockState {
+        /// The clock is synchronized, with no leap second pending.
+        TIME_OK,
+        /// A leap second will be inserted at the end of the UTC day.
+        TIME_INS,
+        /// A leap second will be deleted at the end of the UTC day.
+        TIME_DEL,
+        /// A leap second insertion is in progress.
+        TIME_OOP,
+        /// A leap second has just happened.
+        TIME_WAIT,
+        /// The clock is not synchronized.
+        TIME_ERROR,
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl ClockState {
+    fn from_raw(state: libc::c_int) -> ClockState {
+        match state {
+            libc::TIME_OK => ClockState::TIME_OK,
+            libc::TIME_INS => ClockState::TIME_INS,
+            libc::TIME_DEL => ClockState::TIME_DEL,
+            libc::TIME_OOP => ClockState::TIME_OOP,
+            libc::TIME_WAIT => ClockState::TIME_WAIT,
+            _ => ClockState::TIME_ERROR,
+        }
+    }
+}
+
+/// Parameters of the kernel clock discipline, read and written by
+/// [`adjtimex`](fn.adjtimex.html).
+///
+/// A new `TimeX` changes nothing, so passing it to `adjtimex` only reads the
+/// current parameters.  Each setter also sets the matching
+/// [`TimexModes`](struct.TimexModes.html) flag, so that only the fields that
+/// were set are applied.
+///
+/// Offsets are in microseconds, or in nanoseconds if `ADJ_NANO` is set.
+/// Frequencies are in parts per million, scaled by 2<sup>16</sup>.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct TimeX(libc::timex);
+
+#[cfg(target_os = "linux")]
+// The fields are c_long, except on x32 where they are i64
+#[allow(clippy::unnecessary_cast)]
+impl TimeX {
+    /// Creates a `TimeX` that doesn't change anything.
+    pub fn new() -> TimeX {
+        TimeX(unsafe { mem::zeroed() })
+    }
+
+    /// Which fields are applied.
+    pub fn modes(&self) -> TimexModes {
+        TimexModes::from_bits_truncate(self.0.modes)
+    }
+
+    /// Sets which fields are applied, like `ADJ_NANO` or `ADJ_SETOFFSET`
+    /// which have no setter of their own.
+    pub fn set_modes(&mut self, modes: TimexModes) {
+        self.0.modes = modes.bits();
+    }
+
+    /// Time offset
+    pub fn offset(&self) -> i64 {
+        self.0.offset as i64
+    }
+
+    /// Sets the time offset, and `ADJ_OFFSET`.
+    pub fn set_offset(&mut self, offset: i64) {
+        self.0.offset = offset as _;
+        self.0.modes |= libc::ADJ_OFFSET;
+    }
+
+    /// Frequency offset
+    pub fn freq(&self) -> i64 {
+        self.0.freq as i64
+    }
+
+    /// Sets the frequency offset, and `ADJ_FREQUENCY`.
+    pub fn set_freq(&mut self, freq: i64) {
+        self.0.freq = freq as _;
+        self.0.modes |= libc::ADJ_FREQUENCY;
+    }
+
+    /// Maximum error, in microseconds
+    pub fn maxerror(&self) -> i64 {
+        self.0.maxerror as i64
+    }
+
+    /// Sets the maximum error, and `ADJ_MAXERROR`.
+    pub fn set_maxerror(&mut self, maxerror: i64) {
+        self.0.maxerror = maxerror as _;
+        self.0.modes |= libc::ADJ_MAXERROR;
+    }
+
+    /// Estimated error, in microseconds
+    pub fn esterror(&self) -> i64 {
+        self.0.esterror as i64
+    }
+
+    /// Sets the estimated error, and `ADJ_ESTERROR`.
+    pub fn set_esterror(&mut self, esterror: i64) {
+        self.0.esterror = esterror as _;
+        self.0.modes |= libc::ADJ_ESTERROR;
+    }
+
+    /// Clock status bits
+    pub fn status(&self) -> TimexStatus {
+        TimexStatus::from_bits_truncate(self.0.status)
+    }
+
+    /// Sets the clock status bits, and `ADJ_STATUS`.  The read-only bits are
+    /// ignored by the kernel.
+    pub fn set_status(&mut self, status: TimexStatus) {
+        self.0.status = status.bits();
+        self.0.modes |= libc::ADJ_STATUS;
+    }
+
+    /// PLL time constant
+    pub fn constant(&self) -> i64 {
+        self.0.constant as i64
+    }
+
+    /// Sets the PLL time constant, and `ADJ_TIMECONST`.
+    pub fn set_constant(&mut self, constant: i64) {
+        self.0.constant = constant as _;
+        self.0.modes |= libc::ADJ_TIMECONST;
+    }
+
+    /// Clock precision, in microseconds (read-only)
+    pub fn precision(&self) -> i64 {
+        self.0.precision as i64
+    }
+
+    /// Maximum frequency error (read-only)
+    pub fn tolerance(&self) -> i64 {
+        self.0.tolerance as i64
+    }
+
+    /// Current time.  With `ADJ_SETOFFSET`, the amount to add to it instead;
+    /// its `tv_usec` then holds nanoseconds if `ADJ_NANO` is set.
+    pub fn time(&self) -> TimeVal {
+        TimeVal::from(self.0.time)
+    }
+
+    /// Sets the `time` field, without changing the modes.
+    pub fn set_time(&mut self, time: TimeVal) {
+        self.0.time = *time.as_ref();
+    }
+
+    /// Microseconds between clock ticks
+    pub fn tick(&self) -> i64 {
+        self.0.tick as i64
+    }
+
+    /// Sets the microseconds between clock ticks, and `ADJ_TICK`.
+    pub fn set_tick(&mut self, tick: i64) {
+        self.0.tick = tick as _;
+        self.0.modes |= libc::ADJ_TICK;
+    }
+
+    /// Offset between TAI and UTC, in seconds
+    pub fn tai(&self) -> libc::c_int {
+        self.0.tai
+    }
+
+    /// Sets the offset between TAI and UTC, and `ADJ_TAI`.
+    ///
+    /// Unlike the other fields, the value is passed in `constant`.
+    pub fn set_tai(&mut self, tai: libc::c_int) {
+        self.0.constant = tai as _;
+        self.0.modes |= libc::ADJ_TAI;
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Default for TimeX {
+    fn default() -> Self {
+        TimeX::new()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl fmt::Debug for TimeX {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TimeX")
+            .field("modes", &self.modes())
+            .field("offset", &self.offset())
+            .field("freq", &self.freq())
+            .field("maxerror", &self.maxerror())
+            .field("esterror", &self.esterror())
+            .field("status", &self.status())
+            .field("constant", &self.constant())
+            .field("precision", &self.precision())
+            .field("tolerance", &self.tolerance())
+            .field("time", &self.time())
+            .field("tick", &self.tick())
+            .field("tai", &self.tai())
+            .finish()
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl AsRef<libc::timex> for TimeX {
+    fn as_ref(&self) -> &libc::timex {
+        &self.0
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl AsMut<libc::timex> for TimeX {
+    fn as_mut(&mut self) -> &mut libc::timex {
+        &mut self.0
+    }
+}
+
+/// Read and discipline the kernel clock.
+///
+/// Applies the fields of `buf` selected by its modes, then fills `buf` with
+/// the current parameters and returns the state of the clock.  Changing
+/// anything requires the `CAP_SYS_TIME` capability.  On Linux, this is the
+/// same as `ntp_adjtime`.
+///
+/// # Examples
+///
+/// ```
+/// # use nix::time::{adjtimex, TimeX, TimexStatus};
+/// let mut tx = TimeX::new();
+/// let state = adjtimex(&mut tx).unwrap();
+/// if tx.status().contains(TimexStatus::STA_UNSYNC) {
+///     println!("the clock is not synchronized ({:?})", state);
+/// }
+/// ```
+///
+/// [Further reading](http://man7.org/linux/man-pages/man2/adjtimex.2.html)
+#[cfg(target_os = "linux")]
+pub fn adjtimex(buf: &mut TimeX) -> Result<ClockState> {
+    let res = unsafe { libc::adjtimex(&mut buf.0) };
+    Errno::result(res).map(ClockState::from_raw)
+}
@@ -1,6 +1,5 @@
 //! Safe wrappers around functions found in libc "unistd.h" header
 
-#[cfg(not(target_os = "redox"))]
 use cfg_if::cfg_if;
 use crate::errno::{self, Errno};
 use crate::{Error, Result, NixPath};
@@ -10,7 +9,7 @@ use crate::fcntl::{FdFlag, OFlag, fcntl};
 use crate::fcntl::FcntlArg::F_SETFD;
 use libc::{self, c_char, c_void, c_int, c_long, c_uint, size_t, pid_t, off_t,
            uid_t, gid_t, mode_t, PATH_MAX};
-use std::{fmt, mem, ptr};
+use std::{cmp, fmt, mem, ptr};
 use std::convert::Infallible;
 use std::ffi::{CStr, OsString};
 #[cfg(not(target_os = "redox"))]
@@ -18,7 +17,8 @@ use std::ffi::{CString, OsStr};
 use std::os::unix::ffi::OsStringExt;
 #[cfg(not(target_os = "redox"))]
 use std::os::unix::ffi::OsStrExt;
-use std::os::unix::io::RawFd;
+use std::io;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::path::PathBuf;
 use crate::sys::stat::Mode;
 
@@ -581,6 +581,12 @@ fn reserve_double_buffer_size<T>(buf: &mut Vec<T>, limit: usize) -> Result<()> {
 /// Err is returned if the current user doesn't have the permission to read or search a component
 /// of the current path.
 ///
+/// The buffer grows until the path fits, up to 64 times `PATH_MAX`, after
+/// which `ENAMETOOLONG` is returned.  Paths longer than `PATH_MAX` also need a
+/// C library that falls back to walking up the directory tree when the system
+/// call fails with `ENAMETOOLONG`, like glibc does on Linux; musl and bionic
+/// don't.
+///
 /// # Example
 ///
 /// ```rust
@@ -594,6 +600,8 @@ fn reserve_double_buffer_size<T>(buf: &mut Vec<T>, limit: usize) -> Result<()> {
 /// ```
 #[inline]
 pub fn getcwd() -> Result<PathBuf> {
+    const MAX_CAPACITY: usize = libc::PATH_MAX as usize * 64;
+
     let mut buf = Vec::with_capacity(512);
     loop {
         unsafe {
@@ -616,8 +624,13 @@ pub fn getcwd() -> Result<PathBuf> {
                 }
             }
 
-            // Trigger the internal buffer resizing logic.
-            reserve_double_buffer_size(&mut buf, PATH_MAX as usize)?;
+            // Double the buffer: the path of the current directory can be
+            // longer than PATH_MAX, but don't grow it forever.
+            let capacity = buf.capacity();
+            if capacity >= MAX_CAPACITY {
+                return Err(Error::Sys(Errno::ENAMETOOLONG));
+            }
+            buf.reserve(cmp::min(capacity * 2, MAX_CAPACITY));
         }
     }
 }
@@ -812,7 +825,12 @@ pub fn execvpe(filename: &CStr, args: &[&CStr], env: &[&CStr]) -> Result<Infalli
 /// the new program will run until it exits.
 ///
 /// This function is similar to `execve`, except that the program to be executed
-/// is referenced as a file descriptor instead of a path.
+/// is referenced as a file descriptor instead of a path.  This allows executing
+/// a program that has no path, like one written to a `memfd`, or that was
+/// opened and checked before being executed.
+///
+/// If the program is a script, `fd` must not have the close-on-exec flag set,
+/// or the interpreter can't open it and the new process fails with `ENOENT`.
 // Note for NetBSD and OpenBSD: although rust-lang/libc includes it (under
 // unix/bsd/netbsdlike/) fexecve is not currently implemented on NetBSD nor on
 // OpenBSD.
@@ -840,7 +858,30 @@ pub fn fexecve(fd: RawFd, args: &[&CStr], env: &[&CStr]) -> Result<Infallible> {
 /// the new program will run until it exits.
 ///
 /// This function is similar to `execve`, except that the program to be executed
-/// is referenced as a file descriptor to the base directory plus a path.
+/// is referenced as a file descriptor to the base directory plus a path.  With
+/// `AT_EMPTY_PATH` and an empty `pathname`, `dirfd` itself is executed, like
+/// with [`fexecve`](fn.fexecve.html).
+///
+/// # Examples
+///
+/// Re-execute the current program from a `memfd`, so that it keeps running
+/// even if its file is replaced or deleted:
+///
+/// ```no_run
+/// # use nix::fcntl::AtFlags;
+/// # use nix::sys::memfd::{memfd_create, MemFdCreateFlag};
+/// # use nix::unistd::{execveat, write};
+/// # use std::ffi::{CStr, CString};
+/// let exe = std::fs::read("/proc/self/exe").unwrap();
+/// let name = CString::new("self").unwrap();
+/// let fd = memfd_create(&name, MemFdCreateFlag::MFD_CLOEXEC).unwrap();
+/// write(fd, &exe).unwrap();
+///
+/// let empty = CString::new("").unwrap();
+/// let arg0 = CString::new("self").unwrap();
+/// let env: [&CStr; 0] = [];
+/// execveat(fd, &empty, &[&arg0], &env, AtFlags::AT_EMPTY_PATH).unwrap();
+/// ```
 #[cfg(any(target_os = "android", target_os = "linux"))]
 #[inline]
 pub fn execveat(dirfd: RawFd, pathname: &CStr, args: &[&CStr],
@@ -850,7 +891,7 @@ pub fn execveat(dirfd: RawFd, pathname: &CStr, args: &[&CStr],
 
     unsafe {
         libc::syscall(libc::SYS_execveat, dirfd, pathname.as_ptr(),
-                      args_p.as_ptr(), env_p.as_ptr(), flags);
+                      args_p.as_ptr(), env_p.as_ptr(), flags.bits());
     };
 
     Err(Error::Sys(Errno::last()))
@@ -873,7 +914,7 @@ pub fn execveat(dirfd: RawFd, pathname: &CStr, args: &[&CStr],
 /// * `nochdir = true`: The current working directory after daemonizing will
 ///    be the current working directory.
 /// *  `nochdir = false`: The current working directory after daemonizing will
-///    be the root direcory, `/`.
+///    be the root directory, `/`.
 ///
 /// `noclose`:
 ///
@@ -892,13 +933,66 @@ pub fn daemon(nochdir: bool, noclose: bool) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// Daemonize this process using the traditional double fork.
+///
+/// Unlike [`daemon`](fn.daemon.html), which forks only once, this also forks
+/// after `setsid` so that the daemon is no longer a session leader and can
+/// never reacquire a controlling terminal.  It is available on every platform,
+/// including those where `daemon(3)` is missing or deprecated.  The steps are:
+///
+/// 1. Fork, and exit the parent with `_exit(0)`.
+/// 2. Call `setsid` to start a new session.
+/// 3. Fork again, and exit the session leader with `_exit(0)`.
+/// 4. Unless `nochdir` is set, change the working directory to `/`.
+/// 5. Unless `noclose` is set, redirect stdin, stdout, and stderr to
+///    `/dev/null`.
+///
+/// On success, only the final process returns from this function.  File
+/// descriptors other than the standard streams are left open.
+///
+/// If the first `fork` fails, the error is returned in the original process.
+/// Any later error is returned in the intermediate child or in the daemon,
+/// after the original process has already exited with status 0, so the
+/// caller should exit rather than carry on as if it were still in the
+/// foreground.
+///
+/// # Notes
+///
+/// The same restrictions as for [`fork`](fn.fork.html) apply: in a
+/// multithreaded program only the calling thread survives, so the daemon should
+/// be started before any other threads are spawned.
+pub fn daemonize(nochdir: bool, noclose: bool) -> Result<()> {
+    if let ForkResult::Parent { .. } = fork()? {
+        unsafe { libc::_exit(0) };
+    }
+    setsid()?;
+    if let ForkResult::Parent { .. } = fork()? {
+        unsafe { libc::_exit(0) };
+    }
+    if !nochdir {
+        chdir("/")?;
+    }
+    if !noclose {
+        let fd = crate::fcntl::open("/dev/null", OFlag::O_RDWR, Mode::empty())?;
+        for stdfd in 0..3 {
+            dup2(fd, stdfd)?;
+        }
+        if fd > 2 {
+            close(fd)?;
+        }
+    }
+    Ok(())
+}
+
 /// Set the system host name (see
 /// [sethostname(2)](http://man7.org/linux/man-pages/man2/gethostname.2.html)).
 ///
 /// Given a name, attempt to update the system host name to the given string.
 /// On some systems, the host name is limited to as few as 64 bytes.  An error
 /// will be return if the name is not valid or the current process does not have
-/// permissions to update the host name.
+/// permissions to update the host name.  On Linux it changes the host name of
+/// the calling process' UTS namespace, which is how container runtimes give
+/// each container its own host name.
 #[cfg(not(target_os = "redox"))]
 pub fn sethostname<S: AsRef<OsStr>>(name: S) -> Result<()> {
     // Handle some differences in type of the len arg across platforms.
@@ -919,34 +1013,95 @@ pub fn sethostname<S: AsRef<OsStr>>(name: S) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
-/// Get the host name and store it in the provided buffer, returning a pointer
-/// the `CStr` in that buffer on success (see
+/// Get the host name (see
 /// [gethostname(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/gethostname.html)).
 ///
-/// This function call attempts to get the host name for the running system and
-/// store it in a provided buffer.  The buffer will be populated with bytes up
-/// to the length of the provided slice including a NUL terminating byte.  If
-/// the hostname is longer than the length provided, no error will be provided.
-/// The posix specification does not specify whether implementations will
-/// null-terminate in this case, but the nix implementation will ensure that the
-/// buffer is null terminated in this case.
+/// This function call attempts to get the host name for the running system.
+/// The buffer is sized from `sysconf(_SC_HOST_NAME_MAX)` where available, and
+/// for 255 bytes otherwise.  A host name that doesn't fit is truncated.
 ///
 /// ```no_run
 /// use nix::unistd;
 ///
-/// let mut buf = [0u8; 64];
-/// let hostname_cstr = unistd::gethostname(&mut buf).expect("Failed getting hostname");
-/// let hostname = hostname_cstr.to_str().expect("Hostname wasn't valid UTF-8");
+/// let hostname = unistd::gethostname().expect("Failed getting hostname");
+/// let hostname = hostname.into_string().expect("Hostname wasn't valid UTF-8");
 /// println!("Hostname: {}", hostname);
 /// ```
-pub fn gethostname(buffer: &mut [u8]) -> Result<&CStr> {
+pub fn gethostname() -> Result<OsString> {
+    cfg_if! {
+        if #[cfg(any(target_os = "dragonfly",
+                     target_os = "freebsd",
+                     target_os = "ios",
+                     target_os = "linux",
+                     target_os = "macos",
+                     target_os = "netbsd",
+                     target_os = "openbsd"))] {
+            let max = match sysconf(SysconfVar::HOST_NAME_MAX) {
+                Ok(Some(max)) if max > 0 => max as usize,
+                _ => 255,
+            };
+        } else {
+            let max = 255;
+        }
+    }
+    // Leave room for the NUL terminator.
+    let mut buffer = vec![0u8; max + 1];
     let ptr = buffer.as_mut_ptr() as *mut c_char;
     let len = buffer.len() as size_t;
 
     let res = unsafe { libc::gethostname(ptr, len) };
     Errno::result(res).map(|_| {
-        buffer[len - 1] = 0; // ensure always null-terminated
-        unsafe { CStr::from_ptr(buffer.as_ptr() as *const c_char) }
+        // POSIX doesn't specify whether a truncated name is null-terminated
+        buffer[len - 1] = 0;
+        let end = buffer.iter().position(|&b| b == 0).unwrap();
+        buffer.truncate(end);
+        OsString::from_vec(buffer)
+    })
+}
+
+/// Set the NIS domain name (see
+/// [setdomainname(2)](http://man7.org/linux/man-pages/man2/setdomainname.2.html)).
+///
+/// Like [`sethostname`](fn.sethostname.html), this requires privileges.  On
+/// Linux it changes the domain name of the calling process' UTS namespace.
+#[cfg(any(target_os = "emscripten",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub fn setdomainname<S: AsRef<OsStr>>(name: S) -> Result<()> {
+    cfg_if! {
+        if #[cfg(any(target_os = "ios", target_os = "macos"))] {
+            type setdomainname_len_t = c_int;
+        } else {
+            type setdomainname_len_t = size_t;
+        }
+    }
+    let ptr = name.as_ref().as_bytes().as_ptr() as *const c_char;
+    let len = name.as_ref().len() as setdomainname_len_t;
+
+    let res = unsafe { libc::setdomainname(ptr, len) };
+    Errno::result(res).map(drop)
+}
+
+/// Get the NIS domain name (see
+/// [getdomainname(2)](http://man7.org/linux/man-pages/man2/getdomainname.2.html)).
+///
+/// A domain name that is longer than 255 bytes is truncated.
+#[cfg(any(target_os = "emscripten",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+pub fn getdomainname() -> Result<OsString> {
+    let mut buffer = vec![0u8; 256];
+    let ptr = buffer.as_mut_ptr() as *mut c_char;
+    let len = buffer.len();
+
+    let res = unsafe { libc::getdomainname(ptr, len as _) };
+    Errno::result(res).map(|_| {
+        buffer[len - 1] = 0;
+        let end = buffer.iter().position(|&b| b == 0).unwrap();
+        buffer.truncate(end);
+        OsString::from_vec(buffer)
     })
 }
 
@@ -1002,6 +1157,122 @@ pub fn write(fd: RawFd, buf: &[u8]) -> Result<usize> {
     Errno::result(res).map(|r| r as usize)
 }
 
+/// An owned file descriptor
+///
+/// This type implements `Drop`, so that when it's consumed or goes out of
+/// scope, the file descriptor is automatically closed, even on early error
+/// paths.  Use `into_raw_fd` to take ownership of the descriptor instead.
+/// For pipes, see [`Pipe`](struct.Pipe.html), and for sockets,
+/// [`Socket`](../sys/socket/struct.Socket.html).
+///
+/// # Examples
+///
+/// ```
+/// # use nix::fcntl::{FdFlag, OFlag};
+/// # use nix::sys::stat::Mode;
+/// # use nix::unistd::OwnedFd;
+/// # use std::io::{Read, Write};
+/// let mut f = OwnedFd::open("/dev/null", OFlag::O_RDWR, Mode::empty()).unwrap();
+/// f.set_fd_flags(FdFlag::FD_CLOEXEC).unwrap();
+/// f.write_all(b"hello").unwrap();
+/// let mut buf = String::new();
+/// f.read_to_string(&mut buf).unwrap();
+/// assert!(buf.is_empty());
+/// ```
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct OwnedFd(RawFd);
+
+impl OwnedFd {
+    /// Open a file.  The arguments are the same as for
+    /// [`open`](../fcntl/fn.open.html).
+    pub fn open<P: ?Sized + NixPath>(path: &P, oflag: OFlag, mode: Mode) -> Result<OwnedFd> {
+        crate::fcntl::open(path, oflag, mode).map(OwnedFd)
+    }
+
+    /// Duplicate `oldfd` into a new, owned file descriptor.  See
+    /// [`dup`](fn.dup.html).
+    pub fn dup(oldfd: RawFd) -> Result<OwnedFd> {
+        dup(oldfd).map(OwnedFd)
+    }
+
+    /// Duplicate `oldfd` into `newfd`, which is closed first if it was open,
+    /// and take ownership of `newfd`.  See [`dup2`](fn.dup2.html).
+    ///
+    /// `newfd` must not be owned by anything else, or it would be closed
+    /// twice.
+    pub fn dup2(oldfd: RawFd, newfd: RawFd) -> Result<OwnedFd> {
+        dup2(oldfd, newfd).map(OwnedFd)
+    }
+
+    /// Like [`OwnedFd::dup2`](#method.dup2), with flags for `newfd`.  See
+    /// [`dup3`](fn.dup3.html).
+    pub fn dup3(oldfd: RawFd, newfd: RawFd, flags: OFlag) -> Result<OwnedFd> {
+        dup3(oldfd, newfd, flags).map(OwnedFd)
+    }
+
+    /// Duplicate this file descriptor.  The new one shares the file offset
+    /// and status flags, but not the file descriptor flags.
+    pub fn try_clone(&self) -> Result<OwnedFd> {
+        OwnedFd::dup(self.0)
+    }
+
+    /// Get the file descriptor flags, like `FD_CLOEXEC`.
+    pub fn fd_flags(&self) -> Result<FdFlag> {
+        fcntl(self.0, crate::fcntl::FcntlArg::F_GETFD).map(FdFlag::from_bits_truncate)
+    }
+
+    /// Set the file descriptor flags, like `FD_CLOEXEC`.
+    pub fn set_fd_flags(&self, flags: FdFlag) -> Result<()> {
+        fcntl(self.0, F_SETFD(flags)).map(drop)
+    }
+}
+
+impl AsRawFd for OwnedFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl IntoRawFd for OwnedFd {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.0;
+        mem::forget(self);
+        fd
+    }
+}
+
+impl FromRawFd for OwnedFd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        OwnedFd(fd)
+    }
+}
+
+impl Drop for OwnedFd {
+    fn drop(&mut self) {
+        // Like for `PtyMaster`, ignore errors other than EBADF, which
+        // indicates a double-close.
+        let e = close(self.0);
+        if e == Err(Error::Sys(Errno::EBADF)) {
+            panic!("Closing an invalid file descriptor!");
+        };
+    }
+}
+
+impl io::Read for OwnedFd {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        read(self.0, buf).map_err(|e| e.as_errno().unwrap().into())
+    }
+}
+
+impl io::Write for OwnedFd {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        write(self.0, buf).map_err(|e| e.as_errno().unwrap().into())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 /// Directive that tells [`lseek`] and [`lseek64`] what the offset is relative to.
 ///
 /// [`lseek`]: ./fn.lseek.html
@@ -1076,29 +1347,110 @@ pub fn pipe() -> Result<(RawFd, RawFd)> {
 #[cfg_attr(target_os = "netbsd", doc = "`O_NOSIGPIPE`: Return `EPIPE` instead of raising `SIGPIPE`.  ")]
 /// `O_NONBLOCK`:   Set the non-blocking flag for the ends of the pipe.
 ///
+/// On platforms without `pipe2`, like macOS, this is emulated with `pipe` and
+/// `fcntl`, so the flags are not set atomically: another thread may fork and
+/// exec before `O_CLOEXEC` is set.  Only `O_CLOEXEC` and `O_NONBLOCK` are
+/// supported there, and other flags fail with `EINVAL`.
+///
 /// See also [pipe(2)](http://man7.org/linux/man-pages/man2/pipe.2.html)
-#[cfg(any(target_os = "android",
-          target_os = "dragonfly",
-          target_os = "emscripten",
-          target_os = "freebsd",
-          target_os = "linux",
-          target_os = "redox",
-          target_os = "netbsd",
-          target_os = "openbsd"))]
 pub fn pipe2(flags: OFlag) -> Result<(RawFd, RawFd)> {
-    let mut fds = mem::MaybeUninit::<[c_int; 2]>::uninit();
+    cfg_if! {
+        if #[cfg(any(target_os = "android",
+                     target_os = "dragonfly",
+                     target_os = "emscripten",
+                     target_os = "freebsd",
+                     target_os = "linux",
+                     target_os = "redox",
+                     target_os = "netbsd",
+                     target_os = "openbsd"))] {
+            let mut fds = mem::MaybeUninit::<[c_int; 2]>::uninit();
 
-    let res = unsafe {
-        libc::pipe2(fds.as_mut_ptr() as *mut c_int, flags.bits())
-    };
+            let res = unsafe {
+                libc::pipe2(fds.as_mut_ptr() as *mut c_int, flags.bits())
+            };
 
-    Errno::result(res)?;
+            Errno::result(res)?;
 
-    unsafe { Ok((fds.assume_init()[0], fds.assume_init()[1])) }
+            unsafe { Ok((fds.assume_init()[0], fds.assume_init()[1])) }
+        } else {
+            pipe2_polyfill(flags)
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "android",
+              target_os = "dragonfly",
+              target_os = "emscripten",
+              target_os = "freebsd",
+              target_os = "linux",
+              target_os = "redox",
+              target_os = "netbsd",
+              target_os = "openbsd")))]
+fn pipe2_polyfill(flags: OFlag) -> Result<(RawFd, RawFd)> {
+    use crate::fcntl::FcntlArg::F_SETFL;
+
+    if !(OFlag::O_CLOEXEC | OFlag::O_NONBLOCK).contains(flags) {
+        return Err(Error::invalid_argument());
+    }
+
+    let (fd0, fd1) = pipe()?;
+    for &fd in &[fd0, fd1] {
+        let mut res = Ok(0);
+        if flags.contains(OFlag::O_CLOEXEC) {
+            res = res.and_then(|_| fcntl(fd, F_SETFD(FdFlag::FD_CLOEXEC)));
+        }
+        if flags.contains(OFlag::O_NONBLOCK) {
+            res = res.and_then(|_| fcntl(fd, F_SETFL(OFlag::O_NONBLOCK)));
+        }
+        if let Err(e) = res {
+            let _ = close(fd0);
+            let _ = close(fd1);
+            return Err(e);
+        }
+    }
+
+    Ok((fd0, fd1))
+}
+
+/// The two ends of a pipe, which are closed when dropped
+///
+/// # Examples
+///
+/// ```
+/// # use nix::fcntl::OFlag;
+/// # use nix::unistd::Pipe;
+/// # use std::io::{Read, Write};
+/// let Pipe { mut read, mut write } = Pipe::new(OFlag::O_CLOEXEC).unwrap();
+/// write.write_all(b"hello").unwrap();
+/// drop(write);
+/// let mut buf = String::new();
+/// read.read_to_string(&mut buf).unwrap();
+/// assert_eq!(buf, "hello");
+/// ```
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Pipe {
+    /// The end of the pipe that data is read from
+    pub read: OwnedFd,
+    /// The end of the pipe that data is written to
+    pub write: OwnedFd,
+}
+
+impl Pipe {
+    /// Create a pipe with `flags`, like [`pipe2`](fn.pipe2.html), on all
+    /// platforms.
+    pub fn new(flags: OFlag) -> Result<Pipe> {
+        let (read, write) = pipe2(flags)?;
+        Ok(Pipe { read: OwnedFd(read), write: OwnedFd(write) })
+    }
 }
 
 /// Truncate a file to a specified length
 ///
+/// If the file is shorter than `len`, it's extended with zeros, usually
+/// without allocating space for them.  `off_t` is 32 bits wide on some 32-bit
+/// platforms, so use [`truncate64`](fn.truncate64.html) there for files of
+/// 2 GiB or more.
+///
 /// See also
 /// [truncate(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/truncate.html)
 #[cfg(not(target_os = "redox"))]
@@ -1112,14 +1464,42 @@ pub fn truncate<P: ?Sized + NixPath>(path: &P, len: off_t) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// Truncate a file to a specified length, with a 64-bit length on all
+/// platforms
+///
+/// See also [`truncate`](fn.truncate.html).
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn truncate64<P: ?Sized + NixPath>(path: &P, len: libc::off64_t) -> Result<()> {
+    let res = path.with_nix_path(|cstr| {
+        unsafe {
+            libc::truncate64(cstr.as_ptr(), len)
+        }
+    })?;
+
+    Errno::result(res).map(drop)
+}
+
 /// Truncate a file to a specified length
 ///
+/// Like [`truncate`](fn.truncate.html), for an open file.  The file must be
+/// open for writing.  Use [`ftruncate64`](fn.ftruncate64.html) for files of
+/// 2 GiB or more on 32-bit platforms.
+///
 /// See also
 /// [ftruncate(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/ftruncate.html)
 pub fn ftruncate(fd: RawFd, len: off_t) -> Result<()> {
     Errno::result(unsafe { libc::ftruncate(fd, len) }).map(drop)
 }
 
+/// Truncate a file to a specified length, with a 64-bit length on all
+/// platforms
+///
+/// See also [`ftruncate`](fn.ftruncate.html).
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn ftruncate64(fd: RawFd, len: libc::off64_t) -> Result<()> {
+    Errno::result(unsafe { libc::ftruncate64(fd, len) }).map(drop)
+}
+
 pub fn isatty(fd: RawFd) -> Result<bool> {
     unsafe {
         // ENOTTY means `fd` is a valid file descriptor, but not a TTY, so
@@ -1236,7 +1616,16 @@ pub fn unlinkat<P: ?Sized + NixPath>(
     Errno::result(res).map(drop)
 }
 
-
+/// Change the root directory of the calling process to `path`
+///
+/// Only absolute paths and symbolic links are resolved differently, so this
+/// is not a security boundary by itself: the current directory isn't changed,
+/// and a privileged process can escape with another `chroot`.  Call
+/// [`chdir("/")`](fn.chdir.html) right after, and drop the privileges that
+/// allow calling it again.  Requires the `CAP_SYS_CHROOT` capability on
+/// Linux, or root elsewhere.
+///
+/// See also [chroot(2)](http://man7.org/linux/man-pages/man2/chroot.2.html)
 #[inline]
 pub fn chroot<P: ?Sized + NixPath>(path: &P) -> Result<()> {
     let res = path.with_nix_path(|cstr| {
@@ -1248,11 +1637,17 @@ pub fn chroot<P: ?Sized + NixPath>(path: &P) -> Result<()> {
 
 /// Commit filesystem caches to disk
 ///
+/// This writes the changes to all file systems.  On some platforms, it only
+/// schedules the writes, without waiting for them to finish.
+///
 /// See also [sync(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/sync.html)
 #[cfg(any(
     target_os = "dragonfly",
+    target_os = "emscripten",
     target_os = "freebsd",
+    target_os = "ios",
     target_os = "linux",
+    target_os = "macos",
     target_os = "netbsd",
     target_os = "openbsd"
 ))]
@@ -1260,8 +1655,28 @@ pub fn sync() {
     unsafe { libc::sync() };
 }
 
+/// Commit the filesystem caches of the file system containing `fd` to disk
+///
+/// Like [`sync`](fn.sync.html), for a single file system, and it waits for
+/// the writes to finish.  Since Linux 5.8, errors writing back files are
+/// reported.
+///
+/// See also [syncfs(2)](http://man7.org/linux/man-pages/man2/syncfs.2.html)
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub fn syncfs(fd: RawFd) -> Result<()> {
+    let res = unsafe { libc::syncfs(fd) };
+
+    Errno::result(res).map(drop)
+}
+
 /// Synchronize changes to a file
 ///
+/// This waits until the data and metadata of the file are written to the
+/// storage device.  On Apple platforms, the device may still only hold them
+/// in its cache; use `fcntl` with
+/// [`F_FULLFSYNC`](../fcntl/enum.FcntlArg.html#variant.F_FULLFSYNC) to
+/// flush it.
+///
 /// See also [fsync(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/fsync.html)
 #[inline]
 pub fn fsync(fd: RawFd) -> Result<()> {
@@ -1272,13 +1687,19 @@ pub fn fsync(fd: RawFd) -> Result<()> {
 
 /// Synchronize the data of a file
 ///
+/// Like [`fsync`](fn.fsync.html), but metadata that isn't needed to read the
+/// data back, like the modification time, isn't written, which saves a write
+/// when the size of the file didn't change.
+///
 /// See also
 /// [fdatasync(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/fdatasync.html)
-// `fdatasync(2) is in POSIX, but in libc it is only defined in `libc::notbsd`.
-// TODO: exclude only Apple systems after https://github.com/rust-lang/libc/pull/211
-#[cfg(any(target_os = "linux",
-          target_os = "android",
-          target_os = "emscripten"))]
+// `fdatasync(2) is in POSIX, but Apple systems and DragonFly don't have it.
+#[cfg(any(target_os = "android",
+          target_os = "emscripten",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
 #[inline]
 pub fn fdatasync(fd: RawFd) -> Result<()> {
     let res = unsafe { libc::fdatasync(fd) };
@@ -1366,6 +1787,33 @@ pub fn setgid(gid: Gid) -> Result<()> {
     Errno::result(res).map(drop)
 }
 
+/// Set the real and effective user IDs
+///
+/// Unlike [`setresuid`](fn.setresuid.html), this isn't clear about the saved
+/// user ID: it's set to the new effective user ID only if the real user ID is
+/// set, or if the effective user ID is set to something other than the real
+/// one.  To drop privileges permanently, prefer `setresuid` where available.
+///
+/// See also [setreuid(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/setreuid.html)
+#[inline]
+pub fn setreuid(ruid: Uid, euid: Uid) -> Result<()> {
+    let res = unsafe { libc::setreuid(ruid.into(), euid.into()) };
+
+    Errno::result(res).map(drop)
+}
+
+/// Set the real and effective group IDs
+///
+/// Like [`setreuid`](fn.setreuid.html), for group IDs.
+///
+/// See also [setregid(2)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/setregid.html)
+#[inline]
+pub fn setregid(rgid: Gid, egid: Gid) -> Result<()> {
+    let res = unsafe { libc::setregid(rgid.into(), egid.into()) };
+
+    Errno::result(res).map(drop)
+}
+
 /// Set the user identity used for filesystem checks per-thread.
 /// On both success and failure, this call returns the previous filesystem user
 /// ID of the caller.
@@ -1411,7 +1859,10 @@ pub fn getgroups() -> Result<Vec<Gid>> {
     // Now actually get the groups. We try multiple times in case the number of
     // groups has changed since the first call to getgroups() and the buffer is
     // now too small.
-    let mut groups = Vec::<Gid>::with_capacity(Errno::result(ngroups)? as usize);
+    // With a size of zero, getgroups() returns the number of groups instead of
+    // filling the buffer, so always ask for at least one.
+    let ngroups = std::cmp::max(Errno::result(ngroups)?, 1);
+    let mut groups = Vec::<Gid>::with_capacity(ngroups as usize);
     loop {
         // FIXME: On the platforms we currently support, the `Gid` struct has
         // the same representation in memory as a bare `gid_t`. This is not
@@ -1517,9 +1968,8 @@ pub fn getgrouplist(user: &CStr, group: Gid) -> Result<Vec<Gid>> {
         Ok(Some(n)) => n as c_int,
         Ok(None) | Err(_) => <c_int>::max_value(),
     };
-    use std::cmp::min;
-    let mut ngroups = min(ngroups_max, 8);
-    let mut groups = Vec::<Gid>::with_capacity(ngroups as usize);
+    use std::cmp::{max, min};
+    let mut groups = Vec::<Gid>::with_capacity(min(ngroups_max, 8) as usize);
     cfg_if! {
         if #[cfg(any(target_os = "ios", target_os = "macos"))] {
             type getgrouplist_group_t = c_int;
@@ -1529,6 +1979,9 @@ pub fn getgrouplist(user: &CStr, group: Gid) -> Result<Vec<Gid>> {
     }
     let gid: gid_t = group.into();
     loop {
+        // The size of the buffer may only be given on input, since some
+        // implementations set ngroups to the number of groups on output.
+        let mut ngroups = groups.capacity() as c_int;
         let ret = unsafe {
             libc::getgrouplist(user.as_ptr(),
                                gid as getgrouplist_group_t,
@@ -1544,9 +1997,15 @@ pub fn getgrouplist(user: &CStr, group: Gid) -> Result<Vec<Gid>> {
             // Returns -1 if ngroups is too small, but does not set errno.
             // BSD systems will still fill the groups buffer with as many
             // groups as possible, but Linux manpages do not mention this
-            // behavior.
-            reserve_double_buffer_size(&mut groups, ngroups_max as usize)
-                .or_else(|_| Err(Error::invalid_argument()))?;
+            // behavior.  glibc sets ngroups to the number of groups that
+            // the buffer needs, which may be more than twice its size.
+            let needed = max(ngroups, 0) as usize;
+            if needed > groups.capacity() && needed <= ngroups_max as usize {
+                groups.reserve(needed);
+            } else {
+                reserve_double_buffer_size(&mut groups, ngroups_max as usize)
+                    .or_else(|_| Err(Error::invalid_argument()))?;
+            }
         }
     }
 }
@@ -1760,6 +2219,70 @@ pub fn mkstemp<P: ?Sized + NixPath>(template: &P) -> Result<(RawFd, PathBuf)> {
     Ok((fd, PathBuf::from(pathname)))
 }
 
+/// Like [`mkstemp`](fn.mkstemp.html), but opens the file with extra `flags`.
+///
+/// Only `O_APPEND`, `O_CLOEXEC` and `O_SYNC` are supported on all platforms.
+/// `O_CLOEXEC` avoids leaking the file to a child process executed by
+/// another thread before the flag could be set with `fcntl`.
+///
+/// See also [mkostemp(3)](http://man7.org/linux/man-pages/man3/mkostemp.3.html)
+///
+/// # Example
+///
+/// ```rust
+/// use nix::fcntl::OFlag;
+/// use nix::unistd;
+///
+/// let (fd, path) = unistd::mkostemp("/tmp/tempfile_XXXXXX", OFlag::O_CLOEXEC).unwrap();
+/// unistd::unlink(path.as_path()).unwrap();
+/// unistd::close(fd).unwrap();
+/// ```
+#[cfg(any(target_os = "emscripten",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+pub fn mkostemp<P: ?Sized + NixPath>(template: &P, flags: OFlag) -> Result<(RawFd, PathBuf)> {
+    let mut path = template.with_nix_path(|path| {path.to_bytes_with_nul().to_owned()})?;
+    let p = path.as_mut_ptr() as *mut _;
+    let fd = unsafe { libc::mkostemp(p, flags.bits()) };
+    let last = path.pop(); // drop the trailing nul
+    debug_assert!(last == Some(b'\0'));
+    let pathname = OsString::from_vec(path);
+    Errno::result(fd)?;
+    Ok((fd, PathBuf::from(pathname)))
+}
+
+/// Creates a directory with a unique name, which only the owner can access
+///
+/// * `template`: a path whose 6 rightmost characters must be X, e.g. `/tmp/tmpdir_XXXXXX`
+/// * returns: the path of the new directory
+///
+/// The directory is not removed automatically.
+///
+/// See also [mkdtemp(3)](http://pubs.opengroup.org/onlinepubs/9699919799/functions/mkdtemp.html)
+///
+/// # Example
+///
+/// ```rust
+/// use nix::unistd;
+///
+/// let path = unistd::mkdtemp("/tmp/tempdir_XXXXXX").unwrap();
+/// // do something in the directory
+/// std::fs::remove_dir(path).unwrap();
+/// ```
+pub fn mkdtemp<P: ?Sized + NixPath>(template: &P) -> Result<PathBuf> {
+    let mut path = template.with_nix_path(|path| {path.to_bytes_with_nul().to_owned()})?;
+    let p = path.as_mut_ptr() as *mut _;
+    let res = unsafe { libc::mkdtemp(p) };
+    if res.is_null() {
+        return Err(Error::Sys(Errno::last()));
+    }
+    let last = path.pop(); // drop the trailing nul
+    debug_assert!(last == Some(b'\0'));
+    Ok(PathBuf::from(OsString::from_vec(path)))
+}
+
 /// Variable names for `pathconf`
 ///
 /// Nix uses the same naming convention for these variables as the
@@ -2399,6 +2922,22 @@ pub enum SysconfVar {
     /// Integer value indicating version of the X/Open Portability Guide to
     /// which the implementation conforms.
     _XOPEN_VERSION = libc::_SC_XOPEN_VERSION,
+    /// The number of processors configured.  Not standardized by POSIX, but
+    /// widely available.
+    #[cfg(not(target_os = "redox"))]
+    _NPROCESSORS_CONF = libc::_SC_NPROCESSORS_CONF,
+    /// The number of processors currently online.  Not standardized by POSIX,
+    /// but widely available.
+    #[cfg(not(target_os = "redox"))]
+    _NPROCESSORS_ONLN = libc::_SC_NPROCESSORS_ONLN,
+    /// The number of pages of physical memory.  Not standardized by POSIX, but
+    /// widely available.
+    #[cfg(not(target_os = "redox"))]
+    _PHYS_PAGES = libc::_SC_PHYS_PAGES,
+    /// The number of currently available pages of physical memory.
+    #[cfg(any(target_os = "android", target_os = "emscripten",
+              target_os = "linux", target_os = "openbsd"))]
+    _AVPHYS_PAGES = libc::_SC_AVPHYS_PAGES,
 }
 
 /// Get configurable system variables (see
@@ -2438,6 +2977,29 @@ mod pivot_root {
     use crate::{Result, NixPath};
     use crate::errno::Errno;
 
+    /// Move the root mount of the calling process's mount namespace to the
+    /// directory `put_old`, and make the mount `new_root` the new root mount
+    /// (see [pivot_root(2)](http://man7.org/linux/man-pages/man2/pivot_root.2.html)).
+    ///
+    /// Unlike [`chroot`](fn.chroot.html), this changes the root of every
+    /// process in the mount namespace whose root was the old root, and the
+    /// old root can then be unmounted.  `new_root` must be a mount point
+    /// that isn't shared, and `put_old` must be under it.  This is usually
+    /// done in a new mount namespace, with `unshare(CLONE_NEWNS)`.
+    ///
+    /// # Examples
+    ///
+    /// A container runtime can switch to the root file system of a container
+    /// without a directory for the old root, by stacking it on the new one:
+    ///
+    /// ```no_run
+    /// # use nix::mount::{umount2, MntFlags};
+    /// # use nix::unistd::{chdir, pivot_root};
+    /// chdir("/path/to/rootfs").unwrap();
+    /// pivot_root(".", ".").unwrap();
+    /// umount2(".", MntFlags::MNT_DETACH).unwrap();
+    /// chdir("/").unwrap();
+    /// ```
     pub fn pivot_root<P1: ?Sized + NixPath, P2: ?Sized + NixPath>(
             new_root: &P1, put_old: &P2) -> Result<()> {
         let res = new_root.with_nix_path(|new_root| {
@@ -2458,6 +3020,72 @@ mod setres {
     use crate::Result;
     use crate::errno::Errno;
     use super::{Uid, Gid};
+    use std::mem;
+
+    /// Real, effective and saved user IDs.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct ResUid {
+        /// The real user ID
+        pub real: Uid,
+        /// The effective user ID
+        pub effective: Uid,
+        /// The saved set-user-ID
+        pub saved: Uid,
+    }
+
+    /// Real, effective and saved group IDs.
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct ResGid {
+        /// The real group ID
+        pub real: Gid,
+        /// The effective group ID
+        pub effective: Gid,
+        /// The saved set-group-ID
+        pub saved: Gid,
+    }
+
+    /// Gets the real, effective, and saved uid.
+    /// ([see getresuid(2)](http://man7.org/linux/man-pages/man2/getresuid.2.html))
+    ///
+    /// A process that dropped its privileges can check that the saved uid
+    /// doesn't allow it to get them back.
+    #[inline]
+    pub fn getresuid() -> Result<ResUid> {
+        let mut ruid = mem::MaybeUninit::uninit();
+        let mut euid = mem::MaybeUninit::uninit();
+        let mut suid = mem::MaybeUninit::uninit();
+        let res = unsafe {
+            libc::getresuid(ruid.as_mut_ptr(), euid.as_mut_ptr(), suid.as_mut_ptr())
+        };
+
+        Errno::result(res).map(|_| unsafe {
+            ResUid {
+                real: Uid::from_raw(ruid.assume_init()),
+                effective: Uid::from_raw(euid.assume_init()),
+                saved: Uid::from_raw(suid.assume_init()),
+            }
+        })
+    }
+
+    /// Gets the real, effective, and saved gid.
+    /// ([see getresgid(2)](http://man7.org/linux/man-pages/man2/getresgid.2.html))
+    #[inline]
+    pub fn getresgid() -> Result<ResGid> {
+        let mut rgid = mem::MaybeUninit::uninit();
+        let mut egid = mem::MaybeUninit::uninit();
+        let mut sgid = mem::MaybeUninit::uninit();
+        let res = unsafe {
+            libc::getresgid(rgid.as_mut_ptr(), egid.as_mut_ptr(), sgid.as_mut_ptr())
+        };
+
+        Errno::result(res).map(|_| unsafe {
+            ResGid {
+                real: Gid::from_raw(rgid.assume_init()),
+                effective: Gid::from_raw(egid.assume_init()),
+                saved: Gid::from_raw(sgid.assume_init()),
+            }
+        })
+    }
 
     /// Sets the real, effective, and saved uid.
     /// ([see setresuid(2)](http://man7.org/linux/man-pages/man2/setresuid.2.html))
@@ -2775,8 +3403,36 @@ impl Group {
     }
 }
 
+/// Get the path of the controlling terminal of the calling process (see
+/// [`ctermid(3)`](http://pubs.opengroup.org/onlinepubs/9699919799/functions/ctermid.html)).
+///
+/// The path is usually `/dev/tty`, which always refers to the controlling
+/// terminal of the process that opens it.  Opening it fails with `ENXIO` if
+/// the process has no controlling terminal; to get the name of the terminal
+/// device itself, use [`ttyname`](fn.ttyname.html) on a file descriptor
+/// referring to it.
+#[cfg(not(any(target_os = "android", target_os = "redox")))]
+pub fn ctermid() -> PathBuf {
+    // libc doesn't declare ctermid on every platform yet
+    extern "C" {
+        fn ctermid(s: *mut c_char) -> *mut c_char;
+    }
+
+    // The buffer must hold at least L_ctermid bytes, which is much smaller
+    // than PATH_MAX everywhere.
+    let mut buf = vec![0_u8; PATH_MAX as usize];
+    unsafe { ctermid(buf.as_mut_ptr() as *mut c_char) };
+
+    let nul = buf.iter().position(|c| *c == b'\0').unwrap();
+    buf.truncate(nul);
+    OsString::from_vec(buf).into()
+}
+
 /// Get the name of the terminal device that is open on file descriptor fd
 /// (see [`ttyname(3)`](http://man7.org/linux/man-pages/man3/ttyname.3.html)).
+///
+/// Unlike `ttyname(3)`, this is thread-safe, since it uses `ttyname_r`.
+/// Fails with `ENOTTY` if `fd` isn't a terminal.
 pub fn ttyname(fd: RawFd) -> Result<PathBuf> {
     const PATH_MAX: usize = libc::PATH_MAX as usize;
     let mut buf = vec![0_u8; PATH_MAX];
@@ -25,13 +25,25 @@ mod test_sysinfo;
 mod test_termios;
 #[cfg(not(target_os = "redox"))]
 mod test_ioctl;
+#[cfg(not(target_os = "redox"))]
+mod test_mman;
+#[cfg(not(target_os = "redox"))]
+mod test_resource;
 mod test_wait;
 mod test_uio;
 
 #[cfg(target_os = "linux")]
 mod test_epoll;
 #[cfg(target_os = "linux")]
+mod test_eventfd;
+#[cfg(target_os = "linux")]
 mod test_inotify;
+#[cfg(target_os = "linux")]
+mod test_ipc;
+#[cfg(target_os = "linux")]
+mod test_memfd;
+#[cfg(target_os = "linux")]
+mod test_pidfd;
 mod test_pthread;
 #[cfg(any(target_os = "android",
           target_os = "dragonfly",
@@ -41,5 +53,7 @@ mod test_pthread;
           target_os = "netbsd",
           target_os = "openbsd"))]
 mod test_ptrace;
+#[cfg(target_os = "linux")]
+mod test_timer;
 #[cfg(any(target_os = "android", target_os = "linux"))]
 mod test_timerfd;
@@ -1,5 +1,7 @@
-use nix::sys::epoll::{EpollCreateFlags, EpollFlags, EpollOp, EpollEvent};
+use nix::sys::epoll::{Epoll, EpollCreateFlags, EpollFlags, EpollOp, EpollEvent};
 use nix::sys::epoll::{epoll_create1, epoll_ctl};
+use nix::sys::signal::SigSet;
+use nix::unistd::{close, pipe, write};
 use nix::Error;
 use nix::errno::Errno;
 
@@ -22,3 +24,52 @@ pub fn test_epoll_ctl() {
     epoll_ctl(efd, EpollOp::EpollCtlAdd, 1, &mut event).unwrap();
     epoll_ctl(efd, EpollOp::EpollCtlDel, 1, None).unwrap();
 }
+
+#[test]
+pub fn test_epoll_owned() {
+    let epoll = Epoll::new(EpollCreateFlags::EPOLL_CLOEXEC).unwrap();
+    let (r, w) = pipe().unwrap();
+    let mut events = [EpollEvent::empty(); 4];
+
+    epoll.add(r, EpollEvent::with_fd(EpollFlags::EPOLLIN | EpollFlags::EPOLLET, r)).unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(0));
+
+    write(w, b"x").unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(1));
+    assert_eq!(events[0].fd(), r);
+    assert_eq!(events[0].events(), EpollFlags::EPOLLIN);
+    // Edge-triggered, so nothing new until more data arrives
+    assert_eq!(epoll.pwait(&mut events, 0, Some(&SigSet::empty())), Ok(0));
+
+    epoll.modify(r, EpollEvent::with_fd(EpollFlags::EPOLLIN, r)).unwrap();
+    assert_eq!(epoll.pwait(&mut events, 0, None), Ok(1));
+
+    epoll.delete(r).unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(0));
+
+    close(r).unwrap();
+    close(w).unwrap();
+}
+
+#[test]
+pub fn test_epoll_oneshot_rearm() {
+    let epoll = Epoll::new(EpollCreateFlags::EPOLL_CLOEXEC).unwrap();
+    let (r, w) = pipe().unwrap();
+    let mut events = [EpollEvent::empty(); 4];
+
+    let event = EpollEvent::with_fd(EpollFlags::EPOLLIN, r);
+    epoll.add(r, EpollEvent::with_fd(EpollFlags::EPOLLIN | EpollFlags::EPOLLONESHOT, r))
+        .unwrap();
+    write(w, b"x").unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(1));
+    // Still readable, but disabled until re-armed
+    assert_eq!(epoll.wait(&mut events, 0), Ok(0));
+
+    epoll.rearm(r, event).unwrap();
+    assert_eq!(epoll.wait(&mut events, 0), Ok(1));
+    assert_eq!(events[0].fd(), r);
+    assert_eq!(epoll.wait(&mut events, 0), Ok(0));
+
+    close(r).unwrap();
+    close(w).unwrap();
+}
@@ -0,0 +1,31 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::eventfd::{EfdFlags, EventFd};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn test_eventfd_counter() {
+    let efd = EventFd::new(1, EfdFlags::EFD_CLOEXEC | EfdFlags::EFD_NONBLOCK).unwrap();
+    efd.write(41).unwrap();
+    assert_eq!(efd.read(), Ok(42));
+    assert_eq!(efd.read(), Err(Error::Sys(Errno::EAGAIN)));
+    assert_eq!(efd.write(!0), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+fn test_eventfd_semaphore() {
+    let efd = EventFd::new(2, EfdFlags::EFD_SEMAPHORE | EfdFlags::EFD_NONBLOCK).unwrap();
+    assert_eq!(efd.read(), Ok(1));
+    assert_eq!(efd.read(), Ok(1));
+    assert_eq!(efd.read(), Err(Error::Sys(Errno::EAGAIN)));
+}
+
+#[test]
+fn test_eventfd_wakeup() {
+    let efd = Arc::new(EventFd::new(0, EfdFlags::EFD_CLOEXEC).unwrap());
+    let efd2 = efd.clone();
+    let waiter = thread::spawn(move || efd2.read().unwrap());
+    efd.write(7).unwrap();
+    assert_eq!(waiter.join().unwrap(), 7);
+}
@@ -0,0 +1,102 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::sys::ipc::*;
+use nix::sys::stat::Mode;
+use std::{mem, slice};
+use tempfile::NamedTempFile;
+
+fn mode() -> Mode {
+    Mode::S_IRUSR | Mode::S_IWUSR
+}
+
+#[test]
+fn test_ftok() {
+    let file = NamedTempFile::new().unwrap();
+    let key = ftok(file.path(), b'n').unwrap();
+    assert_eq!(ftok(file.path(), b'n'), Ok(key));
+    assert_ne!(ftok(file.path(), b'x'), Ok(key));
+
+    let path = file.path().to_owned();
+    drop(file);
+    assert_eq!(ftok(&path, b'n'), Err(Error::Sys(Errno::ENOENT)));
+}
+
+#[test]
+fn test_shm() {
+    let id = shmget(IpcKey::IPC_PRIVATE, 4096, IpcGetFlags::IPC_CREAT, mode()).unwrap();
+    let mut ds: libc::shmid_ds = unsafe { mem::zeroed() };
+    shmctl(id, ShmctlArg::IPC_STAT(&mut ds)).unwrap();
+    assert_eq!(ds.shm_segsz, 4096);
+    assert_eq!(ds.shm_perm.mode & 0o777, 0o600);
+
+    unsafe {
+        // Both attachments share the same memory
+        let first = shmat(id, None, ShmatFlags::empty()).unwrap();
+        let second = shmat(id, None, ShmatFlags::SHM_RDONLY).unwrap();
+        assert_ne!(first, second);
+        let first = slice::from_raw_parts_mut(first as *mut u8, 4096);
+        let second = slice::from_raw_parts(second as *const u8, 4096);
+        assert_eq!(second[0], 0);
+        first[0] = 0xaa;
+        assert_eq!(second[0], 0xaa);
+        shmdt(first.as_ptr() as *const _).unwrap();
+        shmdt(second.as_ptr() as *const _).unwrap();
+    }
+
+    shmctl(id, ShmctlArg::IPC_RMID).unwrap();
+    assert_eq!(shmctl(id, ShmctlArg::IPC_STAT(&mut ds)), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+fn test_sem() {
+    let id = semget(IpcKey::IPC_PRIVATE, 2, IpcGetFlags::IPC_CREAT, mode()).unwrap();
+    assert_eq!(semctl(id, 0, SemctlArg::GETVAL), Ok(0));
+    semctl(id, 0, SemctlArg::SETVAL(2)).unwrap();
+    assert_eq!(semctl(id, 0, SemctlArg::GETVAL), Ok(2));
+
+    // Either all operations are performed, or none of them
+    let ops = [SemBuf::new(0, -1, SemOpFlags::IPC_NOWAIT),
+               SemBuf::new(1, -1, SemOpFlags::IPC_NOWAIT)];
+    assert_eq!(semop(id, &ops), Err(Error::Sys(Errno::EAGAIN)));
+    assert_eq!(semctl(id, 0, SemctlArg::GETVAL), Ok(2));
+
+    let ops = [SemBuf::new(0, -2, SemOpFlags::empty()),
+               SemBuf::new(1, 3, SemOpFlags::empty())];
+    semop(id, &ops).unwrap();
+    assert_eq!(semctl(id, 0, SemctlArg::GETVAL), Ok(0));
+    assert_eq!(semctl(id, 1, SemctlArg::GETVAL), Ok(3));
+    assert_eq!(semctl(id, 1, SemctlArg::GETPID), Ok(nix::unistd::getpid().as_raw()));
+    assert_eq!(semctl(id, 0, SemctlArg::GETNCNT), Ok(0));
+
+    semctl(id, 0, SemctlArg::IPC_RMID).unwrap();
+    assert_eq!(semctl(id, 0, SemctlArg::GETVAL), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+fn test_msg() {
+    let id = msgget(IpcKey::IPC_PRIVATE, IpcGetFlags::IPC_CREAT, mode()).unwrap();
+    msgsnd(id, 1, b"first", MsgFlags::empty()).unwrap();
+    msgsnd(id, 2, b"second", MsgFlags::empty()).unwrap();
+    msgsnd(id, 1, b"third", MsgFlags::empty()).unwrap();
+
+    let mut ds: libc::msqid_ds = unsafe { mem::zeroed() };
+    msgctl(id, MsgctlArg::IPC_STAT(&mut ds)).unwrap();
+    assert_eq!(ds.msg_qnum, 3);
+
+    let mut buf = [0u8; 16];
+    assert_eq!(msgrcv(id, 2, &mut buf, MsgFlags::empty()), Ok((2, 6)));
+    assert_eq!(&buf[..6], b"second");
+    assert_eq!(msgrcv(id, 2, &mut buf, MsgFlags::IPC_NOWAIT),
+               Err(Error::Sys(Errno::ENOMSG)));
+
+    // Messages that don't fit are left in the queue, unless truncated
+    assert_eq!(msgrcv(id, 0, &mut buf[..3], MsgFlags::empty()),
+               Err(Error::Sys(Errno::E2BIG)));
+    assert_eq!(msgrcv(id, 0, &mut buf[..3], MsgFlags::MSG_NOERROR), Ok((1, 3)));
+    assert_eq!(&buf[..3], b"fir");
+    assert_eq!(msgrcv(id, 0, &mut buf, MsgFlags::empty()), Ok((1, 5)));
+    assert_eq!(&buf[..5], b"third");
+
+    msgctl(id, MsgctlArg::IPC_RMID).unwrap();
+    assert_eq!(msgsnd(id, 1, b"gone", MsgFlags::empty()), Err(Error::Sys(Errno::EINVAL)));
+}
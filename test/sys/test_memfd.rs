@@ -0,0 +1,59 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::fcntl::{fcntl, FcntlArg, SealFlag};
+use nix::sys::memfd::SealedMemFd;
+use nix::sys::mman::{mmap, munmap, MapFlags, ProtFlags};
+use nix::sys::stat::fstat;
+use nix::unistd::{ftruncate, write};
+use std::ffi::CString;
+use std::os::unix::io::AsRawFd;
+use std::{ptr, slice};
+
+#[test]
+fn test_sealed_memfd() {
+    let name = CString::new("test_sealed_memfd").unwrap();
+    let memfd = SealedMemFd::new(4096).create(&name, b"hello").unwrap();
+    let fd = memfd.as_raw_fd();
+
+    assert_eq!(fstat(fd).unwrap().st_size, 4096);
+    let seals = SealFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GET_SEALS).unwrap());
+    assert_eq!(seals, SealFlag::F_SEAL_SHRINK | SealFlag::F_SEAL_GROW | SealFlag::F_SEAL_SEAL);
+    assert_eq!(ftruncate(fd, 8192), Err(Error::Sys(Errno::EPERM)));
+    assert_eq!(ftruncate(fd, 0), Err(Error::Sys(Errno::EPERM)));
+
+    // The contents can still be modified through a shared mapping
+    unsafe {
+        let addr = mmap(ptr::null_mut(), 4096,
+                        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                        MapFlags::MAP_SHARED, fd, 0).unwrap();
+        let mem = slice::from_raw_parts_mut(addr as *mut u8, 4096);
+        assert_eq!(&mem[..6], b"hello\0");
+        mem[0] = b'j';
+        munmap(addr, 4096).unwrap();
+    }
+}
+
+#[test]
+fn test_sealed_memfd_read_only() {
+    let name = CString::new("test_sealed_memfd_read_only").unwrap();
+    let memfd = SealedMemFd::new(16)
+        .seals(SealFlag::F_SEAL_SHRINK | SealFlag::F_SEAL_GROW |
+               SealFlag::F_SEAL_WRITE | SealFlag::F_SEAL_SEAL)
+        .create(&name, b"hello")
+        .unwrap();
+    let fd = memfd.as_raw_fd();
+
+    assert_eq!(write(fd, b"j"), Err(Error::Sys(Errno::EPERM)));
+    let res = unsafe {
+        mmap(ptr::null_mut(), 16, ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+             MapFlags::MAP_SHARED, fd, 0)
+    };
+    assert_eq!(res, Err(Error::Sys(Errno::EPERM)));
+}
+
+#[test]
+fn test_sealed_memfd_too_small() {
+    let name = CString::new("test_sealed_memfd_too_small").unwrap();
+    assert_eq!(SealedMemFd::new(2).create(&name, b"hello"),
+               Err(Error::Sys(Errno::EINVAL)));
+}
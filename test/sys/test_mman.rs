@@ -0,0 +1,243 @@
+use nix::sys::mman::{madvise, mmap, munmap, MapFlags, MmapAdvise, ProtFlags};
+use nix::unistd::*;
+use std::ptr;
+use std::slice;
+
+// Map one anonymous page filled with `fill`.
+fn map_page(fill: u8) -> &'static mut [u8] {
+    let len = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    unsafe {
+        let addr = mmap(ptr::null_mut(), len,
+                        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                        MapFlags::MAP_PRIVATE | MapFlags::MAP_ANONYMOUS, -1, 0)
+            .unwrap();
+        let page = slice::from_raw_parts_mut(addr as *mut u8, len);
+        for b in page.iter_mut() {
+            *b = fill;
+        }
+        page
+    }
+}
+
+fn unmap_page(page: &'static mut [u8]) {
+    unsafe { munmap(page.as_mut_ptr() as *mut _, page.len()).unwrap() };
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_madvise_cold_pageout() {
+    use nix::Error;
+    use nix::errno::Errno;
+
+    let page = map_page(0xaa);
+    for &advice in &[MmapAdvise::MADV_COLD, MmapAdvise::MADV_PAGEOUT] {
+        let res = unsafe {
+            madvise(page.as_mut_ptr() as *mut _, page.len(), advice)
+        };
+        if res == Err(Error::Sys(Errno::EINVAL)) {
+            // Kernels before 5.4 don't know these
+            continue;
+        }
+        res.unwrap();
+        // Unlike MADV_DONTNEED, the contents are kept
+        assert!(page.iter().all(|&b| b == 0xaa));
+    }
+    unmap_page(page);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_madvise_wipeonfork() {
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::ForkResult::*;
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let page = map_page(0xaa);
+    unsafe {
+        madvise(page.as_mut_ptr() as *mut _, page.len(), MmapAdvise::MADV_WIPEONFORK)
+    }.unwrap();
+
+    // Safe: the child only reads memory before calling `_exit`
+    match fork().unwrap() {
+        Child => {
+            let wiped = page.iter().all(|&b| b == 0);
+            unsafe { libc::_exit(if wiped { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            assert!(page.iter().all(|&b| b == 0xaa));
+        },
+    }
+    unmap_page(page);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_process_madvise() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::sys::mman::process_madvise;
+    use nix::sys::pidfd::PidFd;
+    use nix::sys::uio::RemoteIoVec;
+    use std::os::unix::io::AsRawFd;
+
+    let page = map_page(0xaa);
+    let pidfd = match PidFd::open(getpid()) {
+        Ok(pidfd) => pidfd,
+        Err(Error::Sys(Errno::ENOSYS)) => {
+            unmap_page(page);
+            skip!("pidfd_open is not supported. Skipping test.");
+        },
+        Err(e) => panic!("pidfd_open failed: {}", e),
+    };
+    let iov = [RemoteIoVec { base: page.as_ptr() as usize, len: page.len() }];
+    match process_madvise(pidfd.as_raw_fd(), &iov, MmapAdvise::MADV_COLD) {
+        Ok(len) => assert_eq!(len, page.len()),
+        Err(Error::Sys(Errno::ENOSYS)) | Err(Error::Sys(Errno::EPERM)) => {
+            unmap_page(page);
+            skip!("process_madvise is not supported. Skipping test.");
+        },
+        Err(e) => panic!("process_madvise failed: {}", e),
+    }
+    assert!(page.iter().all(|&b| b == 0xaa));
+    unmap_page(page);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mremap_grow_and_shrink() {
+    use nix::sys::mman::{mremap, MRemapFlags};
+
+    let page = map_page(0xaa);
+    let len = page.len();
+    let addr = page.as_mut_ptr() as *mut _;
+
+    let grown = unsafe {
+        mremap(addr, len, 2 * len, MRemapFlags::MREMAP_MAYMOVE, None)
+    }.unwrap();
+    let pages = unsafe { slice::from_raw_parts_mut(grown as *mut u8, 2 * len) };
+    // The old contents are kept, and the new page is zero-filled
+    assert!(pages[..len].iter().all(|&b| b == 0xaa));
+    assert!(pages[len..].iter().all(|&b| b == 0));
+
+    // Shrinking never needs to move the mapping
+    let shrunk = unsafe {
+        mremap(grown, 2 * len, len, MRemapFlags::empty(), None)
+    }.unwrap();
+    assert_eq!(shrunk, grown);
+    unmap_page(unsafe { slice::from_raw_parts_mut(shrunk as *mut u8, len) });
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_mremap_fixed() {
+    use nix::sys::mman::{mremap, MRemapFlags};
+
+    let src = map_page(0xaa);
+    let dst = map_page(0x55);
+    let len = src.len();
+    let dst_addr = dst.as_mut_ptr() as *mut _;
+
+    // The mapping at `dst` is replaced by the one moved from `src`
+    let moved = unsafe {
+        mremap(src.as_mut_ptr() as *mut _, len, len,
+               MRemapFlags::MREMAP_MAYMOVE | MRemapFlags::MREMAP_FIXED,
+               Some(dst_addr))
+    }.unwrap();
+    assert_eq!(moved, dst_addr);
+    assert!(dst.iter().all(|&b| b == 0xaa));
+    unmap_page(dst);
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn test_shm_open() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::fcntl::OFlag;
+    use nix::sys::mman::{shm_open, shm_unlink};
+    use nix::sys::stat::Mode;
+
+    let name = format!("/nix-test-shm-{}", getpid());
+    let len = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap() as usize;
+    let map = |fd| unsafe {
+        let addr = mmap(ptr::null_mut(), len,
+                        ProtFlags::PROT_READ | ProtFlags::PROT_WRITE,
+                        MapFlags::MAP_SHARED, fd, 0).unwrap();
+        slice::from_raw_parts_mut(addr as *mut u8, len)
+    };
+
+    let fd = shm_open(name.as_str(), OFlag::O_CREAT | OFlag::O_EXCL | OFlag::O_RDWR,
+                      Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+    ftruncate(fd, len as libc::off_t).unwrap();
+    assert_eq!(shm_open(name.as_str(), OFlag::O_CREAT | OFlag::O_EXCL | OFlag::O_RDWR,
+                        Mode::S_IRUSR | Mode::S_IWUSR),
+               Err(Error::Sys(Errno::EEXIST)));
+
+    // Both mappings share the same memory
+    let fd2 = shm_open(name.as_str(), OFlag::O_RDWR, Mode::empty()).unwrap();
+    let first = map(fd);
+    let second = map(fd2);
+    first[0] = 0xaa;
+    assert_eq!(second[0], 0xaa);
+    close(fd).unwrap();
+    close(fd2).unwrap();
+
+    shm_unlink(name.as_str()).unwrap();
+    assert_eq!(shm_open(name.as_str(), OFlag::O_RDWR, Mode::empty()),
+               Err(Error::Sys(Errno::ENOENT)));
+    // The memory outlives the name
+    assert_eq!(second[0], 0xaa);
+    unmap_page(first);
+    unmap_page(second);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_mlock2() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::sys::mman::{mlock2, munlock, MlockFlags};
+
+    let page = map_page(0xaa);
+    let addr = page.as_ptr() as *const _;
+    match unsafe { mlock2(addr, page.len(), MlockFlags::MLOCK_ONFAULT) } {
+        Ok(()) => (),
+        Err(Error::Sys(Errno::ENOSYS)) => {
+            unmap_page(page);
+            skip!("mlock2 is not supported. Skipping test.");
+        },
+        // Exceeded RLIMIT_MEMLOCK
+        Err(Error::Sys(Errno::ENOMEM)) | Err(Error::Sys(Errno::EPERM)) => {
+            unmap_page(page);
+            skip!("mlock2 is not permitted. Skipping test.");
+        },
+        Err(e) => panic!("mlock2 failed: {}", e),
+    }
+    assert!(page.iter().all(|&b| b == 0xaa));
+    unsafe { munlock(addr, page.len()) }.unwrap();
+    unmap_page(page);
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_mlockall_onfault() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::sys::mman::{mlockall, munlockall, MlockAllFlags};
+
+    // Don't lock future mappings, which could make other tests' allocations
+    // fail.
+    match mlockall(MlockAllFlags::MCL_CURRENT | MlockAllFlags::MCL_ONFAULT) {
+        Ok(()) => (),
+        Err(Error::Sys(Errno::ENOMEM)) | Err(Error::Sys(Errno::EPERM)) => {
+            skip!("mlockall is not permitted. Skipping test.");
+        },
+        Err(e) => panic!("mlockall failed: {}", e),
+    }
+    munlockall().unwrap();
+
+    // MCL_ONFAULT alone is invalid
+    assert_eq!(mlockall(MlockAllFlags::MCL_ONFAULT), Err(Error::Sys(Errno::EINVAL)));
+}
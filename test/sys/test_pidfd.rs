@@ -0,0 +1,106 @@
+use nix::Error;
+use nix::errno::Errno;
+use nix::poll::{poll, PollFd, PollFlags};
+use nix::sys::pidfd::PidFd;
+use nix::sys::signal::*;
+use nix::sys::wait::*;
+use nix::unistd::*;
+use nix::unistd::ForkResult::*;
+use libc::_exit;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+
+// Open a `PidFd` for `child`, or kill and reap it if pidfds aren't supported.
+fn open_pidfd(child: Pid) -> Option<PidFd> {
+    match PidFd::open(child) {
+        Ok(pidfd) => Some(pidfd),
+        Err(Error::Sys(Errno::ENOSYS)) => {
+            kill(child, SIGKILL).unwrap();
+            waitpid(child, None).unwrap();
+            None
+        },
+        Err(e) => panic!("pidfd_open failed: {}", e),
+    }
+}
+
+#[test]
+fn test_pidfd_signal_and_wait() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls `pause`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => loop { pause(); },
+        Parent { child } => {
+            let pidfd = match open_pidfd(child) {
+                Some(pidfd) => pidfd,
+                None => { skip!("pidfd_open is not supported. Skipping test."); }
+            };
+            pidfd.send_signal(None).unwrap();
+            assert_eq!(pidfd.wait(WaitPidFlag::WEXITED | WaitPidFlag::WNOHANG),
+                       Ok(WaitStatus::StillAlive));
+
+            pidfd.send_signal(SIGKILL).unwrap();
+            // The pidfd becomes readable when the child exits
+            let mut fds = [PollFd::new(pidfd.as_raw_fd(), PollFlags::POLLIN)];
+            assert_eq!(poll(&mut fds, 10_000), Ok(1));
+            assert_eq!(pidfd.wait(WaitPidFlag::WEXITED),
+                       Ok(WaitStatus::Signaled(child, SIGKILL, false)));
+            // Once reaped, the process can no longer be signaled
+            assert_eq!(pidfd.send_signal(None), Err(Error::Sys(Errno::ESRCH)));
+        },
+    }
+}
+
+#[test]
+fn test_pidfd_wait_exit() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: Child only calls `_exit`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => unsafe { _exit(12) },
+        Parent { child } => {
+            let pidfd = match open_pidfd(child) {
+                Some(pidfd) => pidfd,
+                None => { skip!("pidfd_open is not supported. Skipping test."); }
+            };
+            assert_eq!(pidfd.wait(WaitPidFlag::WEXITED),
+                       Ok(WaitStatus::Exited(child, 12)));
+        },
+    }
+}
+
+#[test]
+fn test_pidfd_get_fd() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (rd, wr) = pipe().unwrap();
+    // Safe: The child only calls `pause`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => loop { pause(); },
+        Parent { child } => {
+            close(wr).unwrap();
+            let pidfd = match open_pidfd(child) {
+                Some(pidfd) => pidfd,
+                None => { skip!("pidfd_open is not supported. Skipping test."); }
+            };
+            let stolen = pidfd.get_fd(wr);
+            pidfd.send_signal(SIGKILL).unwrap();
+            pidfd.wait(WaitPidFlag::WEXITED).unwrap();
+            let mut stolen = match stolen {
+                Ok(fd) => fd,
+                Err(Error::Sys(Errno::ENOSYS)) | Err(Error::Sys(Errno::EPERM)) => {
+                    skip!("pidfd_getfd is not supported. Skipping test.");
+                },
+                Err(e) => panic!("pidfd_getfd failed: {}", e),
+            };
+
+            // The stolen fd is the child's write end of the pipe
+            stolen.write_all(b"hello").unwrap();
+            drop(stolen);
+            let mut buf = [0u8; 8];
+            assert_eq!(read(rd, &mut buf), Ok(5));
+            assert_eq!(&buf[..5], b"hello");
+            close(rd).unwrap();
+        },
+    }
+}
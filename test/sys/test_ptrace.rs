@@ -174,3 +174,186 @@ fn test_ptrace_syscall() {
         },
     }
 }
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_ptrace_read_write() {
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+    use std::ptr;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // After the fork, the word lives at the same address in the child
+    let word = Box::new(0x1234 as libc::c_long);
+    let addr = &*word as *const libc::c_long as ptrace::AddressType;
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            let value = unsafe { ptr::read_volatile(addr as *const libc::c_long) };
+            unsafe { libc::_exit(if value == 0x5678 { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            assert_eq!(ptrace::read(child, addr), Ok(0x1234));
+            ptrace::write(child, addr, 0x5678).unwrap();
+            assert_eq!(ptrace::read(child, addr), Ok(0x5678));
+            // The parent's copy is unaffected
+            assert_eq!(*word, 0x1234);
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
+
+#[test]
+#[cfg(all(target_os = "linux",
+          target_arch = "x86_64",
+          any(target_env = "gnu", target_env = "musl")))]
+fn test_ptrace_registers() {
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { libc::_exit(0) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+
+            let regs = ptrace::getregs(child).unwrap();
+            // rip is the 17th register in struct user_regs_struct
+            let rip_offset = 16 * mem::size_of::<libc::c_ulonglong>();
+            let rip = ptrace::read_user(child, rip_offset as ptrace::AddressType).unwrap();
+            assert_eq!(rip as libc::c_ulonglong, regs.rip);
+            ptrace::setregs(child, regs).unwrap();
+            assert_eq!(ptrace::getregs(child).unwrap().rip, regs.rip);
+
+            let fpregs = ptrace::getfpregs(child).unwrap();
+            ptrace::setfpregs(child, fpregs).unwrap();
+            assert_eq!(ptrace::getfpregs(child).unwrap().mxcsr, fpregs.mxcsr);
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
+
+#[test]
+#[cfg(all(target_os = "linux",
+          not(any(target_arch = "mips", target_arch = "mips64"))))]
+fn test_ptrace_seize_interrupt_listen() {
+    use nix::sys::ptrace::Event;
+    use nix::sys::signal::{kill, Signal};
+    use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+    use nix::unistd::{fork, pause};
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls `pause`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => loop { pause(); },
+        Parent { child } => {
+            let event_stop = Event::PTRACE_EVENT_STOP as i32;
+
+            // Seizing doesn't stop the tracee
+            ptrace::seize(child, Options::empty()).unwrap();
+            assert_eq!(waitpid(child, Some(WaitPidFlag::WNOHANG)), Ok(WaitStatus::StillAlive));
+
+            // But interrupting does, without sending a signal
+            ptrace::interrupt(child).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGTRAP, event_stop)));
+            ptrace::cont(child, None).unwrap();
+
+            // A group-stop is reported as an event stop once the stopping
+            // signal has been delivered
+            kill(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+            ptrace::cont(child, Signal::SIGSTOP).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGSTOP, event_stop)));
+
+            // Listening leaves the tracee stopped until it's continued
+            ptrace::listen(child).unwrap();
+            assert_eq!(waitpid(child, Some(WaitPidFlag::WNOHANG)), Ok(WaitStatus::StillAlive));
+            kill(child, Signal::SIGCONT).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::PtraceEvent(child, Signal::SIGTRAP, event_stop)));
+
+            kill(child, Signal::SIGKILL).unwrap();
+            assert_eq!(waitpid(child, None),
+                       Ok(WaitStatus::Signaled(child, Signal::SIGKILL, false)));
+        },
+    }
+}
+
+#[test]
+#[cfg(all(target_os = "linux",
+          target_arch = "x86_64",
+          any(target_env = "gnu", target_env = "musl")))]
+fn test_ptrace_regset() {
+    use nix::sys::ptrace::RegisterSet;
+    use nix::sys::signal::{raise, Signal};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::fork;
+    use nix::unistd::ForkResult::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            ptrace::traceme().unwrap();
+            raise(Signal::SIGSTOP).unwrap();
+            unsafe { libc::_exit(0) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Stopped(child, Signal::SIGSTOP)));
+
+            // NT_PRSTATUS holds the same registers as PTRACE_GETREGS
+            let regs_size = mem::size_of::<libc::user_regs_struct>();
+            let mut buf = vec![0u8; regs_size + 64];
+            let len = ptrace::getregset(child, RegisterSet::NT_PRSTATUS, &mut buf).unwrap();
+            assert_eq!(len, regs_size);
+            let regs = ptrace::getregs(child).unwrap();
+            let regs_bytes = unsafe {
+                std::slice::from_raw_parts(&regs as *const _ as *const u8, regs_size)
+            };
+            assert_eq!(&buf[..len], regs_bytes);
+            assert_eq!(ptrace::setregset(child, RegisterSet::NT_PRSTATUS, &buf[..len]),
+                       Ok(regs_size));
+
+            let fpregs_size = mem::size_of::<libc::user_fpregs_struct>();
+            let mut buf = vec![0u8; fpregs_size];
+            assert_eq!(ptrace::getregset(child, RegisterSet::NT_PRFPREG, &mut buf),
+                       Ok(fpregs_size));
+
+            // Unknown register sets are rejected
+            let mut buf = [0u8; 64];
+            assert_eq!(ptrace::getregset(child, RegisterSet::from_raw(0x7fff), &mut buf),
+                       Err(Error::Sys(Errno::EINVAL)));
+
+            ptrace::cont(child, None).unwrap();
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
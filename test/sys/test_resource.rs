@@ -0,0 +1,25 @@
+use nix::sys::resource::{getrusage, UsageWho};
+use nix::sys::time::{TimeVal, TimeValLike};
+
+#[test]
+fn test_getrusage_self() {
+    // Spin for a while so that some CPU time is accounted
+    let mut x: u64 = 0;
+    for i in 0..10_000_000u64 {
+        x = x.wrapping_add(i * i);
+    }
+    assert!(x > 0);
+
+    let usage = getrusage(UsageWho::RUSAGE_SELF).unwrap();
+    assert!(usage.user_time() + usage.system_time() > TimeVal::zero());
+    assert!(usage.max_rss() > 0);
+}
+
+#[test]
+#[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))]
+fn test_getrusage_thread() {
+    let thread = getrusage(UsageWho::RUSAGE_THREAD).unwrap();
+    let process = getrusage(UsageWho::RUSAGE_SELF).unwrap();
+    // A thread can't have used more CPU time than its whole process
+    assert!(thread.user_time() <= process.user_time());
+}
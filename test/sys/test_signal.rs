@@ -11,6 +11,14 @@ fn test_kill_none() {
     kill(getpid(), None).expect("Should be able to send signal to myself.");
 }
 
+#[test]
+fn test_kill_target_none() {
+    kill(KillTarget::Process(getpid()), None).unwrap();
+    kill(KillTarget::ProcessGroup(getpgrp()), None).unwrap();
+    kill(KillTarget::CurrentProcessGroup, None).unwrap();
+    kill(KillTarget::All, None).unwrap();
+}
+
 #[test]
 fn test_killpg_none() {
     killpg(getpgrp(), None)
@@ -112,3 +120,241 @@ fn test_signal() {
     // Restore default signal handler
     unsafe { signal(Signal::SIGINT, SigHandler::SigDfl) }.unwrap();
 }
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_signal_pipe() {
+    use nix::sys::stat::fstat;
+    use std::os::unix::io::AsRawFd;
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut pipe = SignalPipe::new().unwrap();
+    assert_eq!(SignalPipe::new().unwrap_err(), Error::Sys(nix::errno::Errno::EBUSY));
+    unsafe { pipe.add(SIGUSR1) }.unwrap();
+    unsafe { pipe.add(SIGUSR2) }.unwrap();
+    assert_eq!(pipe.read(), Ok(None));
+
+    raise(SIGUSR2).unwrap();
+    raise(SIGUSR1).unwrap();
+    assert_eq!(pipe.read(), Ok(Some(SIGUSR2.into())));
+    assert_eq!(pipe.read(), Ok(Some(SIGUSR1.into())));
+    assert_eq!(pipe.read(), Ok(None));
+
+    // The pipe is closed when dropped, though another test may reuse its
+    // file descriptor
+    let fd = pipe.as_raw_fd();
+    let before = fstat(fd).unwrap();
+    drop(pipe);
+    if let Ok(after) = fstat(fd) {
+        assert_ne!((after.st_dev, after.st_ino), (before.st_dev, before.st_ino));
+    }
+    let act = SigAction::new(SigHandler::SigDfl, SaFlags::empty(), SigSet::empty());
+    let old = unsafe { sigaction(SIGUSR1, &act) }.unwrap();
+    assert_eq!(old.handler(), SigHandler::SigDfl);
+    drop(SignalPipe::new().unwrap());
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_sigsuspend() {
+    static GOT_SIGNAL: AtomicBool = AtomicBool::new(false);
+    extern "C" fn handler(_: libc::c_int) {
+        GOT_SIGNAL.store(true, Ordering::SeqCst);
+    }
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let act = SigAction::new(SigHandler::Handler(handler), SaFlags::empty(),
+                             SigSet::empty());
+    let oact = unsafe { sigaction(SIGUSR2, &act) }.unwrap();
+
+    std::thread::spawn(|| {
+        let mut block = SigSet::empty();
+        block.add(SIGUSR2);
+        let mut mask = block.thread_swap_mask(SigmaskHow::SIG_BLOCK).unwrap();
+        mask.remove(SIGUSR2);
+
+        // The signal stays pending until sigsuspend unblocks it
+        raise(SIGUSR2).unwrap();
+        assert!(!GOT_SIGNAL.load(Ordering::SeqCst));
+        sigsuspend(&mask);
+        assert!(GOT_SIGNAL.load(Ordering::SeqCst));
+        assert!(SigSet::thread_get_mask().unwrap().contains(SIGUSR2));
+    }).join().unwrap();
+
+    unsafe { sigaction(SIGUSR2, &oact) }.unwrap();
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_sigsuspend_until_signal() {
+    use nix::sys::pthread::pthread_self;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    static GOT_SIGNAL: AtomicBool = AtomicBool::new(false);
+    extern "C" fn handler(_: libc::c_int) {
+        GOT_SIGNAL.store(true, Ordering::SeqCst);
+    }
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let act = SigAction::new(SigHandler::Handler(handler), SaFlags::empty(),
+                             SigSet::empty());
+    let oact = unsafe { sigaction(SIGUSR2, &act) }.unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    let child = std::thread::spawn(move || {
+        let mut block = SigSet::empty();
+        block.add(SIGUSR2);
+        let mut mask = block.thread_swap_mask(SigmaskHow::SIG_BLOCK).unwrap();
+        mask.remove(SIGUSR2);
+        tx.send(pthread_self()).unwrap();
+
+        // Nothing is pending yet, so this blocks until the signal is sent
+        sigsuspend(&mask);
+        assert!(GOT_SIGNAL.load(Ordering::SeqCst));
+    });
+
+    let thread = rx.recv().unwrap();
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(!GOT_SIGNAL.load(Ordering::SeqCst));
+    assert_eq!(unsafe { libc::pthread_kill(thread, libc::SIGUSR2) }, 0);
+    child.join().unwrap();
+
+    unsafe { sigaction(SIGUSR2, &oact) }.unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos"))]
+fn test_siginfo_handler() {
+    use std::sync::atomic::AtomicUsize;
+
+    static SENDER: AtomicUsize = AtomicUsize::new(0);
+    extern "C" fn handler(signal: libc::c_int, info: &SigInfo, _: *mut libc::c_void) {
+        if info.si_signo() == signal {
+            SENDER.store(info.si_pid().as_raw() as usize, Ordering::Relaxed);
+        }
+    }
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let act = SigAction::new(SigHandler::SigInfoAction(handler), SaFlags::empty(),
+                             SigSet::empty());
+    assert!(act.flags().contains(SaFlags::SA_SIGINFO));
+    let oact = unsafe { sigaction(SIGUSR2, &act) }.unwrap();
+    raise(SIGUSR2).unwrap();
+    assert_eq!(SENDER.load(Ordering::Relaxed), getpid().as_raw() as usize);
+    unsafe { sigaction(SIGUSR2, &oact) }.unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_rt_signal_handler() {
+    use std::sync::atomic::AtomicUsize;
+
+    static RT_SIGNALS: AtomicUsize = AtomicUsize::new(0);
+    extern "C" fn handler(_: libc::c_int) {
+        RT_SIGNALS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let rt = SigNum::rtmax();
+    let act = SigAction::new(SigHandler::Handler(handler), SaFlags::empty(),
+                             SigSet::empty());
+    let oact = unsafe { sigaction(rt, &act) }.unwrap();
+    raise(rt).unwrap();
+    assert_eq!(RT_SIGNALS.load(Ordering::Relaxed), 1);
+    unsafe { sigaction(rt, &oact) }.unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+fn test_sigqueue() {
+    use nix::sys::wait::{waitpid, WaitStatus};
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // sigqueue sends the signal to the whole process, so let a single-threaded
+    // child send it to itself.  Safe: the child only calls async-signal-safe
+    // functions.
+    match fork().expect("Error: Fork Failed") {
+        ForkResult::Child => {
+            let mut mask = SigSet::empty();
+            mask.add(SIGUSR1);
+            let status = mask.thread_block()
+                .and_then(|_| sigqueue(getpid(), SIGUSR1, SigVal::int(42)))
+                .and_then(|_| mask.wait_info())
+                .map(|info| {
+                    info.si_signo() == SIGUSR1 as libc::c_int &&
+                        info.si_value().as_int() == 42
+                });
+            unsafe { libc::_exit(if status == Ok(true) { 0 } else { 1 }) }
+        },
+        ForkResult::Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        },
+    }
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_sigval() {
+    assert_eq!(SigVal::int(-7).as_int(), -7);
+    let mut x = 0u8;
+    let p = &mut x as *mut u8 as *mut libc::c_void;
+    assert_eq!(SigVal::ptr(p).as_ptr(), p);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_sigaltstack() {
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+
+    static HANDLER_SP: AtomicUsize = AtomicUsize::new(0);
+    extern "C" fn handler(_: libc::c_int) {
+        let local = 0u8;
+        HANDLER_SP.store(&local as *const u8 as usize, Ordering::Relaxed);
+    }
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let act = SigAction::new(SigHandler::Handler(handler), SaFlags::SA_ONSTACK,
+                             SigSet::empty());
+    let oact = unsafe { sigaction(SIGUSR2, &act) }.unwrap();
+
+    // Use a new thread, so the main test thread's stack is left alone
+    thread::spawn(|| {
+        let mut stack = vec![0u8; libc::SIGSTKSZ];
+        let ss = SigStack::new(stack.as_mut_ptr() as *mut libc::c_void, stack.len());
+        // The standard library may have installed a stack of its own
+        let old = unsafe { sigaltstack(Some(&ss)) }.unwrap();
+
+        let cur = unsafe { sigaltstack(None) }.unwrap();
+        assert_eq!(cur.sp(), ss.sp());
+        assert_eq!(cur.size(), ss.size());
+        assert!(!cur.flags().contains(SsFlags::SS_ONSTACK));
+
+        raise(SIGUSR2).unwrap();
+        let sp = HANDLER_SP.load(Ordering::Relaxed);
+        let base = stack.as_ptr() as usize;
+        assert!(sp >= base && sp < base + stack.len());
+
+        unsafe { sigaltstack(Some(&SigStack::disabled())) }.unwrap();
+        let cur = unsafe { sigaltstack(Some(&old)) }.unwrap();
+        assert!(cur.flags().contains(SsFlags::SS_DISABLE));
+    }).join().unwrap();
+
+    unsafe { sigaction(SIGUSR2, &oact) }.unwrap();
+}
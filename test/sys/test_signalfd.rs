@@ -25,3 +25,40 @@ fn test_signalfd() {
     let signo = Signal::try_from(res.ssi_signo as i32).unwrap();
     assert_eq!(signo, signal::SIGUSR1);
 }
+
+#[test]
+fn test_signalfd_epoll() {
+    use nix::sys::epoll::{epoll_create1, epoll_ctl, epoll_wait, EpollCreateFlags,
+                          EpollEvent, EpollFlags, EpollOp};
+    use nix::sys::signalfd::{SfdFlags, SignalFd};
+    use nix::sys::signal::{self, raise, SigSet};
+    use nix::unistd::close;
+    use std::os::unix::io::AsRawFd;
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let mut mask = SigSet::empty();
+    mask.add(signal::SIGUSR2);
+    mask.thread_block().unwrap();
+
+    let mut fd = SignalFd::with_flags(&mask, SfdFlags::SFD_NONBLOCK | SfdFlags::SFD_CLOEXEC)
+        .unwrap();
+    let epfd = epoll_create1(EpollCreateFlags::EPOLL_CLOEXEC).unwrap();
+    let mut event = EpollEvent::new(EpollFlags::EPOLLIN, fd.as_raw_fd() as u64);
+    epoll_ctl(epfd, EpollOp::EpollCtlAdd, fd.as_raw_fd(), &mut event).unwrap();
+
+    let mut events = [EpollEvent::empty(); 1];
+    assert_eq!(epoll_wait(epfd, &mut events, 0).unwrap(), 0);
+
+    raise(signal::SIGUSR2).expect("Error: raise(SIGUSR2) failed");
+    assert_eq!(epoll_wait(epfd, &mut events, 1000).unwrap(), 1);
+    assert_eq!(events[0].data(), fd.as_raw_fd() as u64);
+
+    let info = fd.read_signal().unwrap().unwrap();
+    assert_eq!(info.ssi_signo, signal::SIGUSR2 as u32);
+    assert_eq!(info.ssi_pid, nix::unistd::getpid().as_raw() as u32);
+    assert!(fd.read_signal().unwrap().is_none());
+
+    close(epfd).unwrap();
+    mask.thread_unblock().unwrap();
+}
@@ -55,6 +55,27 @@ pub fn test_inetv6_addr_to_sock_addr() {
     assert_eq!(actual, addr.to_std());
 }
 
+#[test]
+pub fn test_std_socket_addr_conversions() {
+    use nix::sys::socket::{IpAddr, SockAddr};
+    use std::convert::TryFrom;
+
+    let link_local = SocketAddr::V6(SocketAddrV6::new(
+        "fe80::1".parse().unwrap(), 3000, 1, 2));
+    let addr = SockAddr::from(link_local);
+    assert_eq!(addr, SockAddr::Inet(InetAddr::from_std(&link_local)));
+    assert_eq!(SocketAddr::try_from(addr).unwrap(), link_local);
+
+    let v4: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+    let inet = InetAddr::from(v4);
+    assert_eq!(inet.port(), 3000);
+    assert_eq!(SocketAddr::from(inet), v4);
+    assert_eq!(net::IpAddr::from(IpAddr::from(v4.ip())), v4.ip());
+
+    let unix = SockAddr::new_unix("/tmp/sock").unwrap();
+    assert!(SocketAddr::try_from(unix).is_err());
+}
+
 #[test]
 pub fn test_path_to_sock_addr() {
     let path = "/foo/bar";
@@ -132,6 +153,57 @@ pub fn test_abstract_uds_addr() {
     assert_eq!(addr.0.sun_path[0], 0);
 }
 
+#[test]
+pub fn test_unix_addr_kind() {
+    use nix::sys::socket::{socketpair, SockAddr, SockFlag, SockType,
+                           UnixAddrKind};
+
+    let addr = UnixAddr::new("/tmp/sock").unwrap();
+    assert_eq!(addr.kind(), UnixAddrKind::Pathname(Path::new("/tmp/sock")));
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        let addr = UnixAddr::new_abstract(b"nix\0abstract").unwrap();
+        assert_eq!(addr.kind(), UnixAddrKind::Abstract(b"nix\0abstract"));
+    }
+
+    let (fd1, _fd2) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+                                 SockFlag::empty()).unwrap();
+    match getsockname(fd1).unwrap() {
+        SockAddr::Unix(addr) => assert_eq!(addr.kind(), UnixAddrKind::Unnamed),
+        addr => panic!("unexpected address {:?}", addr),
+    }
+}
+
+// Abstract addresses returned by the kernel should compare equal to the ones
+// used to bind the sockets
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_abstract_uds_roundtrip() {
+    use nix::sys::socket::{bind, recvfrom, sendto, socket, MsgFlags,
+                           SockAddr, SockFlag, SockType};
+    use nix::unistd::close;
+
+    let rx_addr = SockAddr::Unix(UnixAddr::new_abstract(b"nix\0rx").unwrap());
+    let tx_addr = SockAddr::Unix(UnixAddr::new_abstract(b"nix\0tx").unwrap());
+    let rx = socket(AddressFamily::Unix, SockType::Datagram,
+                    SockFlag::empty(), None).expect("socket failed");
+    let tx = socket(AddressFamily::Unix, SockType::Datagram,
+                    SockFlag::empty(), None).expect("socket failed");
+    bind(rx, &rx_addr).expect("bind failed");
+    bind(tx, &tx_addr).expect("bind failed");
+    assert_eq!(getsockname(rx).expect("getsockname failed"), rx_addr);
+
+    sendto(tx, b"hello", &rx_addr, MsgFlags::empty()).expect("sendto failed");
+    let mut buf = [0u8; 5];
+    let (len, from) = recvfrom(rx, &mut buf).expect("recvfrom failed");
+    assert_eq!(&buf[..len], b"hello");
+    assert_eq!(from, Some(tx_addr));
+
+    close(tx).unwrap();
+    close(rx).unwrap();
+}
+
 #[test]
 pub fn test_getsockname() {
     use nix::sys::socket::{socket, AddressFamily, SockType, SockFlag};
@@ -145,6 +217,7 @@ pub fn test_getsockname() {
     bind(sock, &sockaddr).expect("bind failed");
     assert_eq!(sockaddr.to_str(),
                getsockname(sock).expect("getsockname failed").to_str());
+    assert_eq!(sockaddr, getsockname(sock).expect("getsockname failed"));
 }
 
 #[test]
@@ -161,6 +234,65 @@ pub fn test_socketpair() {
     assert_eq!(&buf[..], b"hello");
 }
 
+#[test]
+pub fn test_socketpair_owned() {
+    use nix::sys::socket::{AddressFamily, SockFlag, SockType, SocketPair};
+    use nix::unistd::{close, read, write};
+    use std::os::unix::io::{AsRawFd, IntoRawFd};
+
+    let pair = SocketPair::new(AddressFamily::Unix, SockType::Stream, None,
+                               SockFlag::empty()).unwrap();
+    write(pair.first().as_raw_fd(), b"hello").unwrap();
+    let mut buf = [0;5];
+    read(pair.second().as_raw_fd(), &mut buf).unwrap();
+    assert_eq!(&buf[..], b"hello");
+
+    let fd0 = pair.first().as_raw_fd();
+    let (first, second) = pair.split();
+    assert_eq!(first.into_raw_fd(), fd0);
+    // Dropping the second half closes it, so the first one sees EOF
+    drop(second);
+    assert_eq!(read(fd0, &mut buf).unwrap(), 0);
+    close(fd0).unwrap();
+}
+
+#[test]
+pub fn test_socket_owned_half_close() {
+    use nix::sys::socket::{sockopt, AddressFamily, Shutdown, SockAddr,
+                           SockFlag, SockType, Socket};
+    use std::io::{Read, Write};
+
+    let listener = Socket::new(AddressFamily::Inet, SockType::Stream,
+                               SockFlag::empty(), None).unwrap();
+    listener.setsockopt(sockopt::ReuseAddr, &true).unwrap();
+    assert!(listener.getsockopt(sockopt::ReuseAddr).unwrap());
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    listener.bind(&lo).unwrap();
+    listener.listen(1).unwrap();
+    assert!(listener.getsockopt(sockopt::AcceptConn).unwrap());
+
+    let mut client = Socket::new(AddressFamily::Inet, SockType::Stream,
+                                 SockFlag::empty(), None).unwrap();
+    client.connect(&listener.local_addr().unwrap()).unwrap();
+    let mut server = listener.accept().unwrap();
+    assert_eq!(server.peer_addr().unwrap(), client.local_addr().unwrap());
+
+    // After the client shuts down its writing half, the server reads EOF but
+    // can still send data back.
+    client.write_all(b"ping").unwrap();
+    client.shutdown(Shutdown::Write).unwrap();
+    let mut buf = Vec::new();
+    server.read_to_end(&mut buf).unwrap();
+    assert_eq!(&buf[..], b"ping");
+
+    server.write_all(b"pong").unwrap();
+    drop(server);
+    buf.clear();
+    client.read_to_end(&mut buf).unwrap();
+    assert_eq!(&buf[..], b"pong");
+}
+
 mod recvfrom {
     use nix::Result;
     use nix::sys::socket::*;
@@ -303,6 +435,47 @@ mod recvfrom {
             setsockopt(rsock, UdpGroSegment, &true)
                 .expect("setsockopt UDP_GRO failed");
         }
+
+        // Segments sent with GSO over loopback are delivered as a single
+        // coalesced datagram to a socket with GRO enabled.
+        #[test]
+        pub fn gro_segments() {
+            require_kernel_version!(udp_offload::gro_segments, ">= 5.3");
+            use nix::unistd::close;
+
+            let segment_size: u16 = 2;
+            let rsock = socket(AddressFamily::Inet, SockType::Datagram,
+                               SockFlag::empty(), None).unwrap();
+            setsockopt(rsock, UdpGroSegment, &true)
+                .expect("setsockopt UDP_GRO failed");
+            let lo = SockAddr::new_inet(InetAddr::from_std(
+                &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+            bind(rsock, &lo).unwrap();
+            let sock_addr = getsockname(rsock).unwrap();
+
+            let ssock = socket(AddressFamily::Inet, SockType::Datagram,
+                               SockFlag::empty(), None).unwrap();
+            let iov = [IoVec::from_slice(MSG)];
+            let cmsg = ControlMessage::UdpGsoSegments(&segment_size);
+            sendmsg(ssock, &iov, &[cmsg], MsgFlags::empty(), Some(&sock_addr))
+                .expect("sendmsg failed");
+
+            let mut buf = [0u8; 64];
+            let iov = [IoVec::from_mut_slice(&mut buf)];
+            let mut cmsgspace = cmsg_space!(libc::c_int);
+            let msg = recvmsg(rsock, &iov, Some(&mut cmsgspace),
+                              MsgFlags::empty()).expect("recvmsg failed");
+            assert_eq!(msg.bytes, MSG.len());
+            match msg.cmsgs().next() {
+                Some(ControlMessageOwned::UdpGroSegments(size)) =>
+                    assert_eq!(size, segment_size),
+                cmsg => panic!("unexpected control message {:?}", cmsg),
+            }
+            assert_eq!(&buf[..MSG.len()], MSG);
+
+            close(rsock).unwrap();
+            close(ssock).unwrap();
+        }
     }
 
     #[cfg(any(
@@ -383,7 +556,7 @@ mod recvfrom {
         use nix::sys::socket::{MsgFlags, recvmmsg};
 
         const NUM_MESSAGES_SENT: usize = 2;
-        const DATA: [u8; 2] = [1,2];
+        const DATA: [u8; 3] = [1,2,3];
 
         let std_sa = SocketAddr::from_str("127.0.0.1:6798").unwrap();
         let inet_addr = InetAddr::from_std(&std_sa);
@@ -424,7 +597,7 @@ mod recvfrom {
         };
 
         let res = recvmmsg(rsock, &mut msgs, MsgFlags::empty(), None).expect("recvmmsg");
-        assert_eq!(res.len(), DATA.len());
+        assert_eq!(res.len(), NUM_MESSAGES_SENT);
 
         for RecvMsg { address, bytes, .. } in res.into_iter() {
             assert_eq!(AddressFamily::Inet, address.unwrap().family());
@@ -437,6 +610,71 @@ mod recvfrom {
 
         send_thread.join().unwrap();
     }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "netbsd",
+    ))]
+    #[test]
+    pub fn udp_recvmmsg_dontwait_short_vec() {
+        use nix::sys::uio::IoVec;
+        use nix::sys::socket::{MsgFlags, recvmmsg};
+
+        const NUM_MESSAGES_SENT: usize = 2;
+        const DATA: [u8; 4] = [1,2,3,4];
+
+        let std_sa = SocketAddr::from_str("127.0.0.1:6799").unwrap();
+        let inet_addr = InetAddr::from_std(&std_sa);
+        let sock_addr = SockAddr::new_inet(inet_addr);
+
+        let rsock = socket(AddressFamily::Inet,
+            SockType::Datagram,
+            SockFlag::empty(),
+            None
+        ).unwrap();
+        bind(rsock, &sock_addr).unwrap();
+        let ssock = socket(
+            AddressFamily::Inet,
+            SockType::Datagram,
+            SockFlag::empty(),
+            None,
+        ).expect("send socket failed");
+
+        // Messages on the loopback interface are queued synchronously
+        for _ in 0..NUM_MESSAGES_SENT {
+            sendto(ssock, &DATA[..], &sock_addr, MsgFlags::empty()).unwrap();
+        }
+
+        let mut msgs = std::collections::LinkedList::new();
+
+        // Buffers to receive more messages than were sent
+        let mut receive_buffers = [[0u8; 32]; NUM_MESSAGES_SENT + 2];
+        let iovs: Vec<_> = receive_buffers.iter_mut().map(|buf| {
+            [IoVec::from_mut_slice(&mut buf[..])]
+        }).collect();
+
+        for iov in &iovs {
+            msgs.push_back(RecvMmsgData {
+                iov,
+                cmsg_buffer: None,
+            })
+        };
+
+        let res = recvmmsg(rsock, &mut msgs, MsgFlags::MSG_DONTWAIT, None)
+            .expect("recvmmsg");
+        assert_eq!(res.len(), NUM_MESSAGES_SENT);
+
+        for RecvMsg { address, bytes, .. } in res.into_iter() {
+            assert_eq!(AddressFamily::Inet, address.unwrap().family());
+            assert_eq!(DATA.len(), bytes);
+        }
+
+        for buf in &receive_buffers[..NUM_MESSAGES_SENT] {
+            assert_eq!(&buf[..DATA.len()], DATA);
+        }
+    }
 }
 
 // Test error handling of our recvmsg wrapper
@@ -509,6 +747,73 @@ pub fn test_scm_rights() {
     close(w).unwrap();
 }
 
+// Disable the test on emulated platforms due to a bug in QEMU versions <
+// 2.12.0.  https://bugs.launchpad.net/qemu/+bug/1701808
+#[cfg_attr(not(any(target_arch = "x86_64", target_arch = "x86")), ignore)]
+#[test]
+pub fn test_send_recv_fds() {
+    use nix::unistd::{pipe, read, write, close};
+    use nix::sys::socket::{socketpair, send_fds, recv_fds,
+                           AddressFamily, SockType, SockFlag};
+
+    let (fd1, fd2) = socketpair(AddressFamily::Unix, SockType::Stream, None, SockFlag::empty())
+                     .unwrap();
+    let (r1, w1) = pipe().unwrap();
+    let (r2, w2) = pipe().unwrap();
+
+    assert_eq!(send_fds(fd1, &[r1, r2], b"hello").unwrap(), 5);
+    close(r1).unwrap();
+    close(r2).unwrap();
+
+    let mut buf = [0u8; 5];
+    let (bytes, fds) = recv_fds(fd2, &mut buf, 2).unwrap();
+    assert_eq!(bytes, 5);
+    assert_eq!(&buf[..], b"hello");
+    assert_eq!(fds.len(), 2);
+
+    // Ensure that the received file descriptors work
+    write(w1, b"world").unwrap();
+    write(w2, b"again").unwrap();
+    let mut buf = [0u8; 5];
+    read(fds[0], &mut buf).unwrap();
+    assert_eq!(&buf[..], b"world");
+    read(fds[1], &mut buf).unwrap();
+    assert_eq!(&buf[..], b"again");
+
+    for fd in fds {
+        close(fd).unwrap();
+    }
+    close(w1).unwrap();
+    close(w2).unwrap();
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+}
+
+#[cfg_attr(not(any(target_arch = "x86_64", target_arch = "x86")), ignore)]
+#[test]
+pub fn test_recv_fds_truncated() {
+    use nix::Error;
+    use nix::errno::Errno;
+    use nix::unistd::{pipe, close};
+    use nix::sys::socket::{socketpair, send_fds, recv_fds,
+                           AddressFamily, SockType, SockFlag};
+
+    let (fd1, fd2) = socketpair(AddressFamily::Unix, SockType::Stream, None, SockFlag::empty())
+                     .unwrap();
+    let (r, w) = pipe().unwrap();
+
+    assert_eq!(send_fds(fd1, &[r, w], b"x").unwrap(), 1);
+
+    let mut buf = [0u8; 1];
+    assert_eq!(recv_fds(fd2, &mut buf, 1).unwrap_err(),
+               Error::Sys(Errno::EMSGSIZE));
+
+    close(r).unwrap();
+    close(w).unwrap();
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+}
+
 // Disable the test on emulated platforms due to not enabled support of AF_ALG in QEMU from rust cross
 #[cfg_attr(not(any(target_arch = "x86_64", target_arch = "i686")), ignore)]
 #[cfg(any(target_os = "linux", target_os= "android"))]
@@ -577,6 +882,45 @@ pub fn test_af_alg_cipher() {
     assert_eq!(decrypted, payload);
 }
 
+// Disable the test on emulated platforms due to not enabled support of AF_ALG in QEMU from rust cross
+#[cfg_attr(not(any(target_arch = "x86_64", target_arch = "i686")), ignore)]
+#[cfg(any(target_os = "linux", target_os= "android"))]
+#[test]
+pub fn test_af_alg_hash() {
+    use nix::errno::Errno;
+    use nix::sys::socket::{accept, bind, send, socket, AddressFamily,
+                           MsgFlags, SockAddr, SockFlag, SockType};
+    use nix::unistd::{close, read};
+    use nix::Error;
+
+    // Travis's seccomp profile blocks AF_ALG
+    // https://docs.docker.com/engine/security/seccomp/
+    skip_if_seccomp!(test_af_alg_hash);
+
+    let sock = match socket(AddressFamily::Alg, SockType::SeqPacket, SockFlag::empty(), None) {
+        Err(Error::Sys(Errno::EAFNOSUPPORT)) => {
+            skip!("AF_ALG is not supported by the kernel. Skipping test.");
+        },
+        sock => sock.expect("socket failed"),
+    };
+    bind(sock, &SockAddr::new_alg("hash", "sha256")).expect("bind failed");
+    let session_socket = accept(sock).expect("accept failed");
+
+    // A hash can be computed over several writes with MSG_MORE
+    send(session_socket, b"a", MsgFlags::MSG_MORE).expect("send failed");
+    send(session_socket, b"bc", MsgFlags::empty()).expect("send failed");
+    let mut digest = [0u8; 32];
+    assert_eq!(read(session_socket, &mut digest).expect("read failed"), 32);
+    assert_eq!(digest, [
+        0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea,
+        0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22, 0x23,
+        0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c,
+        0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad]);
+
+    close(session_socket).unwrap();
+    close(sock).unwrap();
+}
+
 // Disable the test on emulated platforms due to not enabled support of AF_ALG in QEMU from rust cross
 #[cfg_attr(not(any(target_arch = "x86_64", target_arch = "i686")), ignore)]
 #[cfg(any(target_os = "linux", target_os= "android"))]
@@ -821,6 +1165,42 @@ fn test_scm_rights_single_cmsg_multiple_fds() {
     thread.join().unwrap();
 }
 
+// Receive a number of fds that is only known at runtime
+#[test]
+fn test_scm_rights_runtime_cmsg_space() {
+    use nix::sys::socket::{ControlMessage, ControlMessageOwned, MsgFlags,
+                           SockFlag, SockType, recvmsg, sendmsg, socketpair};
+    use nix::sys::uio::IoVec;
+    use nix::unistd::close;
+    use std::os::unix::io::RawFd;
+
+    let (fd1, fd2) = socketpair(AddressFamily::Unix, SockType::Datagram, None,
+                                SockFlag::empty()).unwrap();
+    for nfds in 1..5 {
+        let fds = vec![libc::STDIN_FILENO; nfds];
+        let iov = [IoVec::from_slice(b"x")];
+        let cmsg = [ControlMessage::ScmRights(&fds)];
+        sendmsg(fd1, &iov, &cmsg, MsgFlags::empty(), None).unwrap();
+
+        let mut buf = [0u8; 1];
+        let iov = [IoVec::from_mut_slice(&mut buf)];
+        let mut space = cmsg_space!([RawFd; nfds]);
+        let msg = recvmsg(fd2, &iov, Some(&mut space), MsgFlags::empty()).unwrap();
+        assert!(!msg.flags.contains(MsgFlags::MSG_CTRUNC));
+        match msg.cmsgs().next() {
+            Some(ControlMessageOwned::ScmRights(received)) => {
+                assert_eq!(received.len(), nfds);
+                for fd in received {
+                    close(fd).unwrap();
+                }
+            },
+            cmsg => panic!("unexpected control message {:?}", cmsg),
+        }
+    }
+    close(fd1).unwrap();
+    close(fd2).unwrap();
+}
+
 // Verify `sendmsg` builds a valid `msghdr` when passing an empty
 // `cmsgs` argument.  This should result in a msghdr with a nullptr
 // msg_control field and a msg_controllen of 0 when calling into the
@@ -1019,6 +1399,44 @@ fn test_impl_scm_credentials_and_rights(mut space: Vec<u8>) {
     close(w).unwrap();
 }
 
+// Test that accept4 sets the requested flags on the new socket
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+#[test]
+pub fn test_accept4() {
+    use nix::fcntl::{fcntl, FcntlArg, FdFlag, OFlag};
+    use nix::sys::socket::{accept4, bind, connect, listen, socket, SockAddr,
+                           SockFlag, SockType};
+    use nix::unistd::close;
+
+    let tempdir = tempfile::tempdir().unwrap();
+    let sockname = tempdir.path().join("sock");
+    let sockaddr = SockAddr::new_unix(&sockname).unwrap();
+    let listener = socket(AddressFamily::Unix, SockType::Stream,
+                          SockFlag::empty(), None).expect("socket failed");
+    bind(listener, &sockaddr).expect("bind failed");
+    listen(listener, 10).expect("listen failed");
+
+    let client = socket(AddressFamily::Unix, SockType::Stream,
+                        SockFlag::empty(), None).expect("socket failed");
+    connect(client, &sockaddr).expect("connect failed");
+
+    let fd = accept4(listener, SockFlag::SOCK_CLOEXEC | SockFlag::SOCK_NONBLOCK)
+        .expect("accept4 failed");
+    let fdflags = FdFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFD).unwrap());
+    assert!(fdflags.contains(FdFlag::FD_CLOEXEC));
+    let oflags = OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL).unwrap());
+    assert!(oflags.contains(OFlag::O_NONBLOCK));
+
+    close(fd).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
+
 // Test creating and using named unix domain sockets
 #[test]
 pub fn test_unixdomain() {
@@ -1072,6 +1490,417 @@ pub fn test_syscontrol() {
     // connect(fd, &sockaddr).expect("connect failed");
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_recvmsg_timestampns() {
+    use nix::sys::socket::*;
+    use nix::sys::socket::sockopt::ReceiveTimestampNs;
+    use nix::sys::time::*;
+    use nix::sys::uio::IoVec;
+    use std::time::*;
+
+    // Set up
+    let message = "Ohayō!".as_bytes();
+    let in_socket = socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None).unwrap();
+    setsockopt(in_socket, ReceiveTimestampNs, &true).unwrap();
+    assert!(getsockopt(in_socket, ReceiveTimestampNs).unwrap());
+    let localhost = InetAddr::new(IpAddr::new_v4(127, 0, 0, 1), 0);
+    bind(in_socket, &SockAddr::new_inet(localhost)).unwrap();
+    let address = getsockname(in_socket).unwrap();
+    // Get initial time
+    let time0 = SystemTime::now();
+    // Send the message
+    let iov = [IoVec::from_slice(message)];
+    let flags = MsgFlags::empty();
+    let l = sendmsg(in_socket, &iov, &[], flags, Some(&address)).unwrap();
+    assert_eq!(message.len(), l);
+    // Receive the message
+    let mut buffer = vec![0u8; message.len()];
+    let mut cmsgspace = cmsg_space!(TimeSpec);
+    let iov = [IoVec::from_mut_slice(&mut buffer)];
+    let r = recvmsg(in_socket, &iov, Some(&mut cmsgspace), flags).unwrap();
+    let rtime = match r.cmsgs().next() {
+        Some(ControlMessageOwned::ScmTimestampNs(rtime)) => rtime,
+        Some(_) => panic!("Unexpected control message"),
+        None => panic!("No control message")
+    };
+    // Check the final time
+    let time1 = SystemTime::now();
+    // the packet's received timestamp should lie in-between the two system
+    // times, unless the system clock was adjusted in the meantime.
+    let rduration = Duration::new(rtime.tv_sec() as u64,
+                                  rtime.tv_nsec() as u32);
+    assert!(time0.duration_since(UNIX_EPOCH).unwrap() <= rduration);
+    assert!(rduration <= time1.duration_since(UNIX_EPOCH).unwrap());
+    // Close socket
+    nix::unistd::close(in_socket).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_recvmsg_timestamping() {
+    use nix::sys::socket::*;
+    use nix::sys::socket::sockopt::Timestamping;
+    use nix::sys::time::*;
+    use nix::sys::uio::IoVec;
+    use std::time::*;
+
+    // Set up
+    let message = "Ohayō!".as_bytes();
+    let in_socket = socket(
+        AddressFamily::Inet,
+        SockType::Datagram,
+        SockFlag::empty(),
+        None).unwrap();
+    let flags = TimestampingFlag::SOF_TIMESTAMPING_SOFTWARE |
+                TimestampingFlag::SOF_TIMESTAMPING_RX_SOFTWARE;
+    setsockopt(in_socket, Timestamping, &flags).unwrap();
+    assert_eq!(getsockopt(in_socket, Timestamping).unwrap(), flags);
+    let localhost = InetAddr::new(IpAddr::new_v4(127, 0, 0, 1), 0);
+    bind(in_socket, &SockAddr::new_inet(localhost)).unwrap();
+    let address = getsockname(in_socket).unwrap();
+    // Get initial time
+    let time0 = SystemTime::now();
+    // Send the message
+    let iov = [IoVec::from_slice(message)];
+    let flags = MsgFlags::empty();
+    let l = sendmsg(in_socket, &iov, &[], flags, Some(&address)).unwrap();
+    assert_eq!(message.len(), l);
+    // Receive the message
+    let mut buffer = vec![0u8; message.len()];
+    let mut cmsgspace = cmsg_space!([TimeSpec; 3]);
+    let iov = [IoVec::from_mut_slice(&mut buffer)];
+    let r = recvmsg(in_socket, &iov, Some(&mut cmsgspace), flags).unwrap();
+    let rtime = match r.cmsgs().next() {
+        Some(ControlMessageOwned::ScmTimestamping(timestamps)) => {
+            // No hardware timestamps were requested
+            assert_eq!(timestamps.hw_raw, TimeSpec::zero());
+            timestamps.system
+        },
+        Some(_) => panic!("Unexpected control message"),
+        None => panic!("No control message")
+    };
+    // Check the final time
+    let time1 = SystemTime::now();
+    // the packet's received timestamp should lie in-between the two system
+    // times, unless the system clock was adjusted in the meantime.
+    let rduration = Duration::new(rtime.tv_sec() as u64,
+                                  rtime.tv_nsec() as u32);
+    assert!(time0.duration_since(UNIX_EPOCH).unwrap() <= rduration);
+    assert!(rduration <= time1.duration_since(UNIX_EPOCH).unwrap());
+    // Close socket
+    nix::unistd::close(in_socket).unwrap();
+}
+
+// Verify that an ICMP error for a UDP datagram can be read from the socket
+// error queue
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_recverr_v4() {
+    use nix::errno::Errno;
+    use nix::sys::socket::sockopt::Ipv4RecvErr;
+    use nix::sys::socket::{bind, connect, getsockname, recvmsg, send,
+                           setsockopt, socket, ControlMessageOwned, MsgFlags,
+                           SockAddr, SockFlag, SockType};
+    use nix::sys::uio::IoVec;
+    use nix::unistd::close;
+
+    // Find a local port that nothing listens on, by binding and closing a socket
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    let tmp = socket(AddressFamily::Inet, SockType::Datagram,
+                     SockFlag::empty(), None).unwrap();
+    bind(tmp, &lo).unwrap();
+    let dst = getsockname(tmp).unwrap();
+    close(tmp).unwrap();
+
+    let sock = socket(AddressFamily::Inet, SockType::Datagram,
+                      SockFlag::empty(), None).unwrap();
+    setsockopt(sock, Ipv4RecvErr, &true).unwrap();
+    connect(sock, &dst).unwrap();
+    send(sock, b"hello", MsgFlags::empty()).unwrap();
+
+    let mut buf = [0u8; 8];
+    let iov = [IoVec::from_mut_slice(&mut buf)];
+    let mut cmsgspace = cmsg_space!(libc::sock_extended_err, libc::sockaddr_in);
+    let msg = recvmsg(sock, &iov, Some(&mut cmsgspace), MsgFlags::MSG_ERRQUEUE)
+        .expect("recvmsg failed");
+    match msg.cmsgs().next() {
+        Some(ControlMessageOwned::Ipv4RecvErr(err, addr)) => {
+            assert_eq!(err.ee_errno, Errno::ECONNREFUSED as u32);
+            assert_eq!(err.ee_origin, libc::SO_EE_ORIGIN_ICMP);
+            let addr = addr.expect("no offender address");
+            assert_eq!(u32::from_be(addr.sin_addr.s_addr), 0x7f00_0001);
+        },
+        cmsg => panic!("unexpected control message {:?}", cmsg),
+    }
+
+    close(sock).unwrap();
+}
+
+// Ping the loopback address with a raw ICMP socket
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_icmp_echo() {
+    use nix::sys::socket::sockopt::ReceiveTimeout;
+    use nix::sys::socket::{recv, sendto, setsockopt, socket, MsgFlags,
+                           SockAddr, SockFlag, SockProtocol, SockType};
+    use nix::sys::time::{TimeVal, TimeValLike};
+    use nix::unistd::{close, getpid};
+
+    require_capability!(CAP_NET_RAW);
+
+    let sock = socket(AddressFamily::Inet, SockType::Raw, SockFlag::empty(),
+                      SockProtocol::Icmp).unwrap();
+    setsockopt(sock, ReceiveTimeout, &TimeVal::seconds(1)).unwrap();
+
+    // Echo request: type, code, checksum, identifier, sequence number, data
+    let id = (getpid().as_raw() as u16).to_be_bytes();
+    let mut request = [8, 0, 0, 0, id[0], id[1], 0, 1, b'n', b'i', b'x', 0];
+    let mut sum = request.chunks(2)
+        .map(|w| u32::from(u16::from_be_bytes([w[0], w[1]])))
+        .sum::<u32>();
+    sum = (sum & 0xffff) + (sum >> 16);
+    let checksum = !(sum as u16);
+    request[2..4].copy_from_slice(&checksum.to_be_bytes());
+
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    sendto(sock, &request, &lo, MsgFlags::empty()).unwrap();
+
+    // Raw sockets see the request as well as the reply, with the IP header
+    let mut buf = [0u8; 128];
+    loop {
+        let len = recv(sock, &mut buf, MsgFlags::empty()).unwrap();
+        let icmp = &buf[usize::from(buf[0] & 0xf) * 4..len];
+        if icmp[0] == 0 && icmp[4..6] == id {
+            assert_eq!(&icmp[8..], &request[8..]);
+            break;
+        }
+    }
+
+    close(sock).unwrap();
+}
+
+// MSG_ZEROCOPY sends are acknowledged through the error queue
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_zerocopy_completion() {
+    use nix::poll::{poll, PollFd, PollFlags};
+    use nix::sys::socket::sockopt::ZeroCopy;
+    use nix::sys::socket::{accept, bind, connect, getsockname, listen,
+                           recvmsg, send, setsockopt, socket,
+                           ControlMessageOwned, MsgFlags, SockAddr, SockFlag,
+                           SockType};
+    use nix::sys::uio::IoVec;
+    use nix::unistd::close;
+
+    require_kernel_version!(test_zerocopy_completion, ">= 4.14");
+
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    let listener = socket(AddressFamily::Inet, SockType::Stream,
+                          SockFlag::empty(), None).unwrap();
+    bind(listener, &lo).unwrap();
+    listen(listener, 1).unwrap();
+    let client = socket(AddressFamily::Inet, SockType::Stream,
+                        SockFlag::empty(), None).unwrap();
+    connect(client, &getsockname(listener).unwrap()).unwrap();
+    let server = accept(listener).unwrap();
+
+    setsockopt(client, ZeroCopy, &true).unwrap();
+    for _ in 0..2 {
+        send(client, b"hello", MsgFlags::MSG_ZEROCOPY).unwrap();
+    }
+
+    // Completions of consecutive sends may be coalesced
+    let mut completed = 0;
+    while completed < 2 {
+        let mut fds = [PollFd::new(client, PollFlags::empty())];
+        assert_eq!(poll(&mut fds, 1000).unwrap(), 1);
+        let iov: [IoVec<&mut [u8]>; 0] = [];
+        let mut cmsgspace = cmsg_space!(libc::sock_extended_err, libc::sockaddr_in);
+        let msg = recvmsg(client, &iov, Some(&mut cmsgspace), MsgFlags::MSG_ERRQUEUE)
+            .expect("recvmsg failed");
+        match msg.cmsgs().next() {
+            Some(ControlMessageOwned::ZeroCopyCompletion(zc)) => {
+                assert_eq!(zc.first, completed);
+                assert!(zc.last >= zc.first);
+                completed = zc.last + 1;
+            },
+            cmsg => panic!("unexpected control message {:?}", cmsg),
+        }
+    }
+    assert_eq!(completed, 2);
+
+    close(server).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
+
+// One-to-many style SCTP sockets, receiving the association and stream info
+// of each message as ancillary data.
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_sctp_seqpacket_rcvinfo() {
+    use nix::sys::socket::sockopt::{SctpEvents, SctpInitMsg, SctpNoDelay,
+                                    SctpRecvRcvInfo};
+    use nix::sys::socket::{bind, getsockname, getsockopt, listen, recvmsg,
+                           sendto, setsockopt, socket, ControlMessageOwned,
+                           MsgFlags, SctpEventSubscribe, SockAddr, SockFlag,
+                           SockProtocol, SockType};
+    use nix::sys::uio::IoVec;
+    use nix::unistd::close;
+
+    let server = match socket(AddressFamily::Inet, SockType::SeqPacket,
+                              SockFlag::empty(), SockProtocol::Sctp) {
+        Ok(fd) => fd,
+        Err(_) => {
+            skip!("SCTP is not supported by the kernel. Skipping test.");
+        }
+    };
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    bind(server, &lo).expect("bind failed");
+    listen(server, 10).expect("listen failed");
+    setsockopt(server, SctpRecvRcvInfo, &true).unwrap();
+    assert!(getsockopt(server, SctpRecvRcvInfo).unwrap());
+    let addr = getsockname(server).unwrap();
+
+    let client = socket(AddressFamily::Inet, SockType::SeqPacket,
+                        SockFlag::empty(), SockProtocol::Sctp).unwrap();
+    setsockopt(client, SctpNoDelay, &true).unwrap();
+    assert!(getsockopt(client, SctpNoDelay).unwrap());
+    let mut initmsg = getsockopt(client, SctpInitMsg).unwrap();
+    initmsg.sinit_num_ostreams = 4;
+    setsockopt(client, SctpInitMsg, &initmsg).unwrap();
+    assert_eq!(getsockopt(client, SctpInitMsg).unwrap().sinit_num_ostreams, 4);
+    let events = SctpEventSubscribe {
+        sctp_association_event: 1,
+        sctp_shutdown_event: 1,
+        ..Default::default()
+    };
+    setsockopt(client, SctpEvents, &events).unwrap();
+    assert_eq!(getsockopt(client, SctpEvents).unwrap(), events);
+    sendto(client, b"hello", &addr, MsgFlags::empty()).expect("sendto failed");
+
+    let mut buf = [0u8; 8];
+    let iov = [IoVec::from_mut_slice(&mut buf)];
+    let mut cmsgspace = cmsg_space!(libc::sctp_rcvinfo);
+    let msg = recvmsg(server, &iov, Some(&mut cmsgspace), MsgFlags::empty())
+        .expect("recvmsg failed");
+    assert_eq!(msg.bytes, 5);
+    match msg.cmsgs().next() {
+        Some(ControlMessageOwned::SctpRcvInfo(info)) => {
+            assert_eq!(info.rcv_sid, 0);
+            assert_ne!(info.rcv_assoc_id, 0);
+        },
+        cmsg => panic!("unexpected control message {:?}", cmsg),
+    }
+    assert_eq!(&buf[..5], b"hello");
+
+    close(client).unwrap();
+    close(server).unwrap();
+}
+
+#[test]
+pub fn test_getaddrinfo_numeric() {
+    use nix::sys::socket::{getaddrinfo, AddrInfoFlags, AddrInfoHints,
+                           GaiError, SockAddr, SockProtocol, SockType};
+    use std::ffi::CString;
+
+    let hints = AddrInfoHints {
+        flags: AddrInfoFlags::AI_NUMERICHOST | AddrInfoFlags::AI_NUMERICSERV,
+        family: Some(AddressFamily::Inet),
+        socktype: Some(SockType::Stream),
+        ..AddrInfoHints::default()
+    };
+    let node = CString::new("127.0.0.1").unwrap();
+    let service = CString::new("4242").unwrap();
+    let addrs = getaddrinfo(Some(&node), Some(&service), Some(&hints))
+        .expect("getaddrinfo failed")
+        .collect::<Vec<_>>();
+    let expected = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:4242").unwrap()));
+    assert_eq!(addrs, vec![(expected, SockType::Stream, Some(SockProtocol::Tcp))]);
+
+    // Name resolution is disabled, so a host name can't be resolved
+    let node = CString::new("localhost").unwrap();
+    let err = getaddrinfo(Some(&node), None, Some(&hints)).unwrap_err();
+    assert_eq!(err, GaiError::Gai(libc::EAI_NONAME));
+}
+
+#[cfg(not(target_os = "dragonfly"))]
+#[test]
+pub fn test_getnameinfo_numeric() {
+    use nix::sys::socket::{getnameinfo, NameInfoFlags, SockAddr};
+
+    let addr = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("[::1]:4242").unwrap()));
+    let flags = NameInfoFlags::NI_NUMERICHOST | NameInfoFlags::NI_NUMERICSERV;
+    let (host, service) = getnameinfo(&addr, flags).expect("getnameinfo failed");
+    assert_eq!(host, "::1");
+    assert_eq!(service, "4242");
+}
+
+// Verify that several different control messages can be received by a single
+// recvmsg call.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_recv_ipv4pktinfo_and_timestamp() {
+    use libc;
+    use nix::sys::socket::sockopt::{Ipv4PacketInfo, ReceiveTimestamp};
+    use nix::sys::socket::{bind, getsockname, recvmsg, sendto, setsockopt,
+                           socket, ControlMessageOwned, MsgFlags, SockAddr,
+                           SockFlag, SockType};
+    use nix::sys::time::{TimeVal, TimeValLike};
+    use nix::sys::uio::IoVec;
+    use nix::unistd::close;
+
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    let receive = socket(AddressFamily::Inet, SockType::Datagram,
+                         SockFlag::empty(), None)
+        .expect("receive socket failed");
+    bind(receive, &lo).expect("bind failed");
+    let sa = getsockname(receive).expect("getsockname failed");
+    setsockopt(receive, Ipv4PacketInfo, &true).expect("setsockopt failed");
+    setsockopt(receive, ReceiveTimestamp, &true).expect("setsockopt failed");
+
+    let send = socket(AddressFamily::Inet, SockType::Datagram,
+                      SockFlag::empty(), None)
+        .expect("send socket failed");
+    sendto(send, b"hello", &sa, MsgFlags::empty()).expect("sendto failed");
+
+    let mut buf = [0u8; 5];
+    let iov = [IoVec::from_mut_slice(&mut buf)];
+    let mut space = cmsg_space!(libc::in_pktinfo, TimeVal);
+    let msg = recvmsg(receive, &iov, Some(&mut space), MsgFlags::empty())
+        .expect("recvmsg failed");
+    assert!(!msg.flags.intersects(MsgFlags::MSG_TRUNC | MsgFlags::MSG_CTRUNC));
+    assert_eq!(msg.bytes, 5);
+
+    let mut pktinfo = None;
+    let mut timestamp = None;
+    for cmsg in msg.cmsgs() {
+        match cmsg {
+            ControlMessageOwned::Ipv4PacketInfo(pi) => pktinfo = Some(pi),
+            ControlMessageOwned::ScmTimestamp(ts) => timestamp = Some(ts),
+            cmsg => panic!("unexpected control message {:?}", cmsg),
+        }
+    }
+    let pktinfo = pktinfo.expect("no Ipv4PacketInfo received");
+    assert_eq!(u32::from_be(pktinfo.ipi_addr.s_addr), 0x7f00_0001);
+    assert!(timestamp.expect("no ScmTimestamp received") > TimeVal::seconds(0));
+
+    close(send).unwrap();
+    close(receive).unwrap();
+}
+
 #[cfg(any(
     target_os = "android",
     target_os = "freebsd",
@@ -1411,6 +2240,55 @@ pub fn test_recv_ipv6pktinfo() {
     }
 }
 
+// Verify that `recvfrom` reports the link-layer source address of frames
+// captured on an `AF_PACKET` socket.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_recvfrom_packet() {
+    use libc;
+    use nix::net::if_::if_nametoindex;
+    use nix::sys::socket::{bind, recvfrom, sendto, socket, LinkAddr, MsgFlags,
+                           SockAddr, SockFlag, SockType};
+    use nix::unistd::close;
+    use std::mem;
+
+    require_capability!(CAP_NET_RAW);
+
+    let lo = if_nametoindex("lo").expect("if_nametoindex failed");
+    let proto = (libc::ETH_P_IP as u16).to_be();
+
+    let mut sll: libc::sockaddr_ll = unsafe { mem::zeroed() };
+    sll.sll_family = libc::AF_PACKET as u16;
+    sll.sll_protocol = proto;
+    sll.sll_ifindex = lo as i32;
+    let packet = socket(AddressFamily::Packet, SockType::Datagram,
+                        SockFlag::SOCK_NONBLOCK, None)
+        .expect("socket failed");
+    bind(packet, &SockAddr::Link(LinkAddr(sll))).expect("bind failed");
+
+    let send = socket(AddressFamily::Inet, SockType::Datagram,
+                      SockFlag::empty(), None)
+        .expect("send socket failed");
+    let sock_addr = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:6800").unwrap()));
+    sendto(send, b"hello", &sock_addr, MsgFlags::empty()).expect("sendto failed");
+
+    let mut buf = [0u8; 1500];
+    let (len, from) = recvfrom(packet, &mut buf).expect("recvfrom failed");
+    assert!(len > 0);
+    match from {
+        Some(SockAddr::Link(addr)) => {
+            assert_eq!(addr.family(), AddressFamily::Packet);
+            assert_eq!(addr.ifindex(), lo as usize);
+            assert_eq!(addr.protocol(), proto);
+        },
+        other => panic!("unexpected address {:?}", other),
+    }
+
+    close(send).unwrap();
+    close(packet).unwrap();
+}
+
 #[cfg(target_os = "linux")]
 #[test]
 pub fn test_vsock() {
@@ -1462,3 +2340,151 @@ pub fn test_vsock() {
     close(s1).unwrap();
     thr.join().unwrap();
 }
+
+// Exercise bind/connect/accept end to end over the vsock loopback transport.
+// That transport first appeared in Linux 5.6, so skip if it's not available.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_vsock_loopback() {
+    use nix::sys::socket::{AddressFamily, socket, accept, bind, connect,
+                           getpeername, getsockname, listen, SockAddr,
+                           SockType, SockFlag, VMADDR_CID_ANY,
+                           VMADDR_CID_LOCAL};
+    use nix::unistd::{close, read, write};
+
+    let port: u32 = 3001;
+
+    let listener = socket(AddressFamily::Vsock, SockType::Stream,
+                          SockFlag::empty(), None)
+                   .expect("socket failed");
+    bind(listener, &SockAddr::new_vsock(VMADDR_CID_ANY, port))
+        .expect("bind failed");
+    listen(listener, 10).expect("listen failed");
+    match getsockname(listener).expect("getsockname failed") {
+        SockAddr::Vsock(addr) => assert_eq!(addr.port(), port),
+        addr => panic!("unexpected address {:?}", addr),
+    }
+
+    let client = socket(AddressFamily::Vsock, SockType::Stream,
+                        SockFlag::empty(), None)
+                 .expect("socket failed");
+    if connect(client, &SockAddr::new_vsock(VMADDR_CID_LOCAL, port)).is_err() {
+        close(client).unwrap();
+        close(listener).unwrap();
+        skip!("vsock loopback is not available. Skipping test.");
+    }
+
+    let server = accept(listener).expect("accept failed");
+    match getpeername(server).expect("getpeername failed") {
+        SockAddr::Vsock(addr) => assert_eq!(addr.cid(), VMADDR_CID_LOCAL),
+        addr => panic!("unexpected address {:?}", addr),
+    }
+
+    assert_eq!(write(client, b"hello").unwrap(), 5);
+    let mut buf = [0u8; 5];
+    assert_eq!(read(server, &mut buf).unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+
+    close(server).unwrap();
+    close(client).unwrap();
+    close(listener).unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_recv_peek_len() {
+    use nix::sys::socket::{bind, getsockname, recv, recv_peek_len, sendto,
+                           socket, MsgFlags, SockAddr, SockFlag, SockType};
+    use nix::unistd::close;
+
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    let rsock = socket(AddressFamily::Inet, SockType::Datagram,
+                       SockFlag::empty(), None).unwrap();
+    bind(rsock, &lo).unwrap();
+    let addr = getsockname(rsock).unwrap();
+    let ssock = socket(AddressFamily::Inet, SockType::Datagram,
+                       SockFlag::empty(), None).unwrap();
+    sendto(ssock, &[0xa5; 1000], &addr, MsgFlags::empty()).unwrap();
+    sendto(ssock, b"hi", &addr, MsgFlags::empty()).unwrap();
+
+    // Peeking does not consume the datagram
+    assert_eq!(recv_peek_len(rsock).unwrap(), 1000);
+    assert_eq!(recv_peek_len(rsock).unwrap(), 1000);
+    let mut buf = vec![0; 1000];
+    assert_eq!(recv(rsock, &mut buf, MsgFlags::empty()).unwrap(), 1000);
+    assert_eq!(recv_peek_len(rsock).unwrap(), 2);
+
+    close(ssock).unwrap();
+    close(rsock).unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_unix_seqpacket_channel() {
+    use nix::sys::socket::UnixSeqpacketChannel;
+    use nix::unistd::{close, getpid, getuid, pipe, read, write};
+
+    let (broker, worker) = UnixSeqpacketChannel::pair().unwrap();
+
+    // Message boundaries are kept, even for messages larger than usual
+    // buffers, and descriptors arrive with their message.
+    let (r, w) = pipe().unwrap();
+    let big = vec![0x5a; 10000];
+    broker.send(&big, &[]).unwrap();
+    broker.send(b"pipe", &[w]).unwrap();
+    broker.send(b"", &[]).unwrap();
+    close(w).unwrap();
+
+    let msg = worker.recv(1).unwrap().unwrap();
+    assert_eq!(msg.data, big);
+    assert!(msg.fds.is_empty());
+    assert_eq!(msg.credentials.pid(), getpid().as_raw());
+    assert_eq!(msg.credentials.uid(), getuid().as_raw());
+    let msg = worker.recv(1).unwrap().unwrap();
+    assert_eq!(&msg.data[..], b"pipe");
+    assert_eq!(msg.fds.len(), 1);
+    write(msg.fds[0], b"x").unwrap();
+    close(msg.fds[0]).unwrap();
+    let mut buf = [0u8; 1];
+    assert_eq!(read(r, &mut buf).unwrap(), 1);
+    close(r).unwrap();
+
+    // An empty message is not the end of the channel
+    let msg = worker.recv(1).unwrap().unwrap();
+    assert!(msg.data.is_empty());
+
+    // Closing one end ends the channel for the other
+    drop(broker);
+    assert!(worker.recv(1).unwrap().is_none());
+}
+
+// Each message carries the credentials of the process that sent it, even
+// when both ends of the channel were created by another one.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+pub fn test_unix_seqpacket_channel_child_credentials() {
+    use nix::sys::socket::UnixSeqpacketChannel;
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{fork, getpid, ForkResult};
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (broker, worker) = UnixSeqpacketChannel::pair().unwrap();
+    // Safe: the child only calls `sendmsg` and `_exit`, which are
+    // async-signal-safe.  Sending without descriptors doesn't allocate.
+    match fork().unwrap() {
+        ForkResult::Child => {
+            let res = worker.send(b"hi", &[]);
+            unsafe { libc::_exit(res.is_err() as i32) };
+        },
+        ForkResult::Parent { child } => {
+            drop(worker);
+            assert_eq!(waitpid(child, None).unwrap(), WaitStatus::Exited(child, 0));
+            let msg = broker.recv(0).unwrap().unwrap();
+            assert_eq!(&msg.data[..], b"hi");
+            assert_eq!(msg.credentials.pid(), child.as_raw());
+            assert_ne!(msg.credentials.pid(), getpid().as_raw());
+        },
+    }
+}
@@ -67,3 +67,188 @@ fn test_bindtodevice() {
         val
     );
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_bindtoifindex() {
+    use nix::net::if_::if_nametoindex;
+
+    skip_if_not_root!("test_bindtoifindex");
+    require_kernel_version!(test_bindtoifindex, ">= 5.0");
+
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::BindToIfIndex).unwrap(), 0);
+
+    let lo = if_nametoindex("lo").unwrap();
+    setsockopt(fd, sockopt::BindToIfIndex, &lo).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::BindToIfIndex).unwrap(), lo);
+    // The binding is also visible by interface name
+    let name = getsockopt(fd, sockopt::BindToDevice).unwrap();
+    assert_eq!(name, "lo");
+}
+
+#[test]
+fn test_sockopt_raw() {
+    use nix::sys::socket::{getsockopt_raw, setsockopt_raw};
+
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+             .unwrap();
+    let mut buf = [0u8; 4];
+    assert_eq!(getsockopt_raw(fd, libc::SOL_SOCKET, libc::SO_TYPE, &mut buf).unwrap(), 4);
+    assert_eq!(libc::c_int::from_ne_bytes(buf), libc::SOCK_DGRAM);
+
+    setsockopt_raw(fd, libc::SOL_SOCKET, libc::SO_BROADCAST, &1i32.to_ne_bytes()).unwrap();
+    assert!(getsockopt(fd, sockopt::Broadcast).unwrap());
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_ip_hdrincl() {
+    require_capability!(CAP_NET_RAW);
+
+    // IPPROTO_RAW sockets always include the IP header
+    let fd = socket(AddressFamily::Inet, SockType::Raw, SockFlag::empty(), SockProtocol::Raw)
+             .unwrap();
+    assert!(getsockopt(fd, sockopt::IpHdrIncl).unwrap());
+
+    let fd = socket(AddressFamily::Inet, SockType::Raw, SockFlag::empty(), SockProtocol::Icmp)
+             .unwrap();
+    assert!(!getsockopt(fd, sockopt::IpHdrIncl).unwrap());
+    setsockopt(fd, sockopt::IpHdrIncl, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::IpHdrIncl).unwrap());
+}
+
+sockopt!(unsafe Both, UserRcvBuf, libc::SOL_SOCKET, libc::SO_RCVBUF, libc::c_int);
+
+#[test]
+fn test_user_sockopt() {
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+             .unwrap();
+    let bufsize: libc::c_int = thread_rng().gen_range(4096, 131_072);
+    setsockopt(fd, UserRcvBuf, &bufsize).unwrap();
+    let actual = getsockopt(fd, UserRcvBuf).unwrap();
+    assert!(actual >= bufsize);
+    assert_eq!(actual as usize, getsockopt(fd, sockopt::RcvBuf).unwrap());
+}
+
+// SO_TYPE is an int, so the kernel fills in only part of a larger value
+sockopt!(unsafe GetOnly, OversizedSocketType, libc::SOL_SOCKET, libc::SO_TYPE, [libc::c_int; 2]);
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_user_sockopt_short() {
+    use nix::Error;
+    use nix::errno::Errno;
+
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+             .unwrap();
+    assert_eq!(getsockopt(fd, OversizedSocketType), Err(Error::Sys(Errno::EINVAL)));
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+fn test_tcp_keepalive() {
+    let fd = socket(AddressFamily::Inet, SockType::Stream, SockFlag::empty(), None).unwrap();
+    setsockopt(fd, sockopt::KeepAlive, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::KeepAlive).unwrap());
+
+    #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+    {
+        setsockopt(fd, sockopt::TcpKeepIdle, &60).unwrap();
+        assert_eq!(getsockopt(fd, sockopt::TcpKeepIdle).unwrap(), 60);
+    }
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        setsockopt(fd, sockopt::TcpKeepAlive, &60).unwrap();
+        assert_eq!(getsockopt(fd, sockopt::TcpKeepAlive).unwrap(), 60);
+    }
+
+    setsockopt(fd, sockopt::TcpKeepCount, &5).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::TcpKeepCount).unwrap(), 5);
+    setsockopt(fd, sockopt::TcpKeepInterval, &10).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::TcpKeepInterval).unwrap(), 10);
+}
+
+#[test]
+fn test_ipv6_multicast() {
+    let fd = socket(AddressFamily::Inet6, SockType::Datagram, SockFlag::empty(), None)
+             .unwrap();
+
+    setsockopt(fd, sockopt::Ipv6MulticastHops, &5).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::Ipv6MulticastHops).unwrap(), 5);
+
+    setsockopt(fd, sockopt::Ipv6MulticastLoop, &false).unwrap();
+    assert!(!getsockopt(fd, sockopt::Ipv6MulticastLoop).unwrap());
+    setsockopt(fd, sockopt::Ipv6MulticastLoop, &true).unwrap();
+    assert!(getsockopt(fd, sockopt::Ipv6MulticastLoop).unwrap());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_incoming_cpu() {
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+             .unwrap();
+    setsockopt(fd, sockopt::IncomingCpu, &0).unwrap();
+    assert_eq!(getsockopt(fd, sockopt::IncomingCpu).unwrap(), 0);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_attach_reuseport_cbpf() {
+    use nix::sys::socket::{bind, InetAddr, SockAddr};
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    let fd = socket(AddressFamily::Inet, SockType::Datagram, SockFlag::empty(), None)
+             .unwrap();
+    setsockopt(fd, sockopt::ReusePort, &true).unwrap();
+    let addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
+    bind(fd, &SockAddr::new_inet(InetAddr::from_std(&addr))).unwrap();
+
+    // Always select the first socket of the group: "ret #0"
+    let mut filter = [libc::sock_filter {
+        code: 0x06,     // BPF_RET | BPF_K
+        jt: 0,
+        jf: 0,
+        k: 0,
+    }];
+    let prog = libc::sock_fprog {
+        len: filter.len() as libc::c_ushort,
+        filter: filter.as_mut_ptr(),
+    };
+    setsockopt(fd, sockopt::AttachReusePortCbpf, &prog).unwrap();
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_tcp_info() {
+    use nix::sys::socket::{accept, bind, connect, getsockname, listen, InetAddr, SockAddr};
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+
+    let lo = SockAddr::new_inet(InetAddr::from_std(
+        &SocketAddr::from_str("127.0.0.1:0").unwrap()));
+    let listener = socket(AddressFamily::Inet, SockType::Stream, SockFlag::empty(), None).unwrap();
+    bind(listener, &lo).unwrap();
+    listen(listener, 1).unwrap();
+    // TCP_LISTEN
+    assert_eq!(getsockopt(listener, sockopt::TcpInfo).unwrap().state(), 10);
+
+    let client = socket(AddressFamily::Inet, SockType::Stream, SockFlag::empty(), None).unwrap();
+    connect(client, &getsockname(listener).unwrap()).unwrap();
+    let server = accept(listener).unwrap();
+    for &fd in &[client, server] {
+        let info = getsockopt(fd, sockopt::TcpInfo).unwrap();
+        // TCP_ESTABLISHED
+        assert_eq!(info.state(), 1);
+        assert!(info.snd_mss() > 0);
+        assert!(info.snd_cwnd() > 0);
+        assert_eq!(info.total_retrans(), 0);
+    }
+}
@@ -0,0 +1,62 @@
+use nix::sys::signal::{SigEvent, SigevNotify, SigNum, SigSet, Signal};
+use nix::sys::time::{TimeSpec, TimeValLike};
+use nix::sys::timer::{Timer, TimerSetTimeFlags};
+use nix::sys::timerfd::Expiration;
+use nix::time::ClockId;
+use nix::unistd::gettid;
+
+#[test]
+fn test_timer_thread_id() {
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let signal = SigNum::rt(1).unwrap();
+    let mut mask = SigSet::empty();
+    mask.add(signal);
+    // Only this thread blocks the signal, so it must be delivered to us
+    mask.thread_block().unwrap();
+
+    let sigevent = SigEvent::new(SigevNotify::SigevThreadIdNum {
+        signal,
+        thread_id: gettid().as_raw(),
+        si_value: 42,
+    });
+    let timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+    assert!(timer.get().unwrap().is_none());
+
+    timer
+        .set(
+            Expiration::OneShot(TimeSpec::milliseconds(10)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+
+    let info = mask.wait_info().unwrap();
+    assert_eq!(info.si_signo(), signal.as_raw());
+    assert_eq!(info.si_value().as_ptr() as usize, 42);
+    assert!(timer.get().unwrap().is_none());
+    assert_eq!(timer.overruns().unwrap(), 0);
+
+    mask.thread_unblock().unwrap();
+}
+
+#[test]
+fn test_timer_unset() {
+    // Some kernels keep reporting SIGEV_NONE timers as armed after they are
+    // disarmed, so use a signal that is never sent since the timer is unset.
+    let sigevent = SigEvent::new(SigevNotify::SigevSignal {
+        signal: Signal::SIGUSR2,
+        si_value: 0,
+    });
+    let timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+
+    timer
+        .set(
+            Expiration::OneShot(TimeSpec::seconds(10)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+    assert!(timer.get().unwrap().is_some());
+
+    timer.unset().unwrap();
+    assert!(timer.get().unwrap().is_none());
+}
@@ -1,5 +1,7 @@
 use nix::sys::time::{TimeSpec, TimeValLike};
 use nix::sys::timerfd::{ClockId, Expiration, TimerFd, TimerFlags, TimerSetTimeFlags};
+use nix::Error;
+use nix::errno::Errno;
 use std::time::Instant;
 
 #[test]
@@ -59,3 +61,19 @@ pub fn test_timerfd_unset() {
 
     assert!(timer.get().unwrap() == None);
 }
+
+#[test]
+pub fn test_timerfd_read_expirations() {
+    let timer = TimerFd::new(ClockId::CLOCK_BOOTTIME, TimerFlags::TFD_NONBLOCK).unwrap();
+    assert_eq!(timer.read_expirations(), Err(Error::Sys(Errno::EAGAIN)));
+
+    timer
+        .set(
+            Expiration::Interval(TimeSpec::milliseconds(10)),
+            TimerSetTimeFlags::empty(),
+        )
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    assert!(timer.read_expirations().unwrap() >= 2);
+}
@@ -99,6 +99,31 @@ fn test_readv() {
     assert!(close_res.is_ok());
 }
 
+#[test]
+fn test_iovec_from_ioslice() {
+    use std::io::IoSlice;
+
+    let buf = [1u8, 2, 3, 4];
+    let iov: IoVec<&[u8]> = IoSlice::new(&buf[1..3]).into();
+    assert_eq!(iov.as_slice(), &[2, 3]);
+
+    let slice: IoSlice = iov.into();
+    assert_eq!(&*slice, &[2, 3]);
+}
+
+#[test]
+fn test_iovec_from_ioslicemut() {
+    use std::io::IoSliceMut;
+
+    let mut buf = [0u8; 4];
+    {
+        let iov: IoVec<&mut [u8]> = IoSliceMut::new(&mut buf[1..3]).into();
+        let mut slice: IoSliceMut = iov.into();
+        slice.copy_from_slice(&[5, 6]);
+    }
+    assert_eq!(buf, [0, 5, 6, 0]);
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_pwrite() {
@@ -195,6 +220,71 @@ fn test_preadv() {
     assert_eq!(all, expected);
 }
 
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_pwritev2() {
+    use std::io::Read;
+
+    let to_write: Vec<u8> = (0..128).collect();
+    let expected: Vec<u8> = [vec![0;100], to_write.clone()].concat();
+
+    let iovecs = [
+        IoVec::from_slice(&to_write[0..17]),
+        IoVec::from_slice(&to_write[17..128]),
+    ];
+
+    let tempdir = tempdir().unwrap();
+
+    let path = tempdir.path().join("pwritev2_test_file");
+    let mut file = OpenOptions::new().write(true).read(true).create(true)
+                                    .truncate(true).open(path).unwrap();
+
+    let written = pwritev2(file.as_raw_fd(), &iovecs, 100, RwFlags::RWF_DSYNC)
+        .unwrap();
+    assert_eq!(written, to_write.len());
+
+    // Read the data back and make sure it matches
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents).unwrap();
+    assert_eq!(contents, expected);
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_preadv2() {
+    use std::io::Write;
+
+    let to_write: Vec<u8> = (0..200).collect();
+    let expected: Vec<u8> = (100..200).collect();
+
+    let tempdir = tempdir().unwrap();
+
+    let path = tempdir.path().join("preadv2_test_file");
+
+    let mut file = OpenOptions::new().read(true).write(true).create(true)
+                                    .truncate(true).open(path).unwrap();
+    file.write_all(&to_write).unwrap();
+
+    let mut buffers: Vec<Vec<u8>> = vec![
+        vec![0; 25],
+        vec![0; 75],
+    ];
+
+    {
+        let iovecs: Vec<_> = buffers.iter_mut().map(
+            |buf| IoVec::from_mut_slice(&mut buf[..])).collect();
+        // RWF_NOWAIT isn't supported by every filesystem, and the data may
+        // not be cached yet anyway, so don't insist on it.
+        if preadv2(file.as_raw_fd(), &iovecs, 100, RwFlags::RWF_NOWAIT) != Ok(100) {
+            assert_eq!(Ok(100), preadv2(file.as_raw_fd(), &iovecs, 100,
+                                        RwFlags::empty()));
+        }
+    }
+
+    let all = buffers.concat();
+    assert_eq!(all, expected);
+}
+
 #[test]
 #[cfg(target_os = "linux")]
 // FIXME: qemu-user doesn't implement process_vm_readv/writev on most arches
@@ -244,3 +334,59 @@ fn test_process_vm_readv() {
         },
     }
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+// FIXME: qemu-user doesn't implement process_vm_readv/writev on most arches
+#[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), ignore)]
+fn test_process_vm_writev() {
+    use nix::unistd::ForkResult::*;
+    use nix::sys::wait::*;
+
+    require_capability!(CAP_SYS_PTRACE);
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Pre-allocate memory in the child, since allocation isn't safe
+    // post-fork (~= async-signal-safe)
+    let mut vector = [0u8; 5];
+
+    let (ready_r, ready_w) = pipe().unwrap();
+    let (go_r, go_w) = pipe().unwrap();
+    match fork().expect("Error: Fork Failed") {
+        Parent { child } => {
+            close(ready_w).unwrap();
+            close(go_r).unwrap();
+            // wait for child
+            read(ready_r, &mut [0u8]).unwrap();
+            close(ready_r).unwrap();
+
+            let ptr = vector.as_mut_ptr() as usize;
+            let remote_iov = RemoteIoVec { base: ptr, len: 5 };
+            let buf = [1u8, 2, 3, 4, 5];
+
+            let ret = process_vm_writev(child,
+                                        &[IoVec::from_slice(&buf)],
+                                        &[remote_iov]);
+
+            // let the child check its memory
+            write(go_w, b"\0").unwrap();
+            close(go_w).unwrap();
+
+            assert_eq!(Ok(5), ret);
+            assert_eq!(Ok(WaitStatus::Exited(child, 0)), waitpid(child, None));
+        },
+        Child => {
+            let _ = close(ready_r);
+            let _ = close(go_w);
+            let _ = write(ready_w, b"\0");
+            let _ = close(ready_w);
+            let _ = read(go_r, &mut [0u8]);
+            // The parent wrote the memory behind the compiler's back, so
+            // read it with volatile loads to keep them from being folded
+            let sum: u8 = (0..vector.len()).map(|i| unsafe {
+                std::ptr::read_volatile(vector.as_ptr().add(i))
+            }).sum();
+            unsafe { libc::_exit(if sum == 15 { 0 } else { 1 }) };
+        },
+    }
+}
@@ -1,4 +1,5 @@
 use nix::Error;
+use nix::errno::Errno;
 use nix::unistd::*;
 use nix::unistd::ForkResult::*;
 use nix::sys::signal::*;
@@ -42,6 +43,56 @@ fn test_waitstatus_from_raw() {
     assert_eq!(WaitStatus::from_raw(pid, 0x0002), Ok(WaitStatus::Signaled(pid, Signal::SIGINT, false)));
     assert_eq!(WaitStatus::from_raw(pid, 0x0200), Ok(WaitStatus::Exited(pid, 2)));
     assert_eq!(WaitStatus::from_raw(pid, 0x7f7f), Err(Error::invalid_argument()));
+    assert_eq!(WaitStatus::from_raw(pid, 0x00ff), Err(Error::invalid_argument()));
+    assert_eq!(WaitStatus::from_raw(pid, 0x137f), Ok(WaitStatus::Stopped(pid, Signal::SIGSTOP)));
+    assert_eq!(WaitStatus::from_raw(pid, 0xffff), Ok(WaitStatus::Continued(pid)));
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_waitstatus_from_raw_ptrace() {
+    use nix::sys::ptrace::Event;
+
+    let pid = Pid::from_raw(1);
+    // Syscall stops have SIGTRAP | 0x80 as their stop signal
+    assert_eq!(WaitStatus::from_raw(pid, 0x857f), Ok(WaitStatus::PtraceSyscall(pid)));
+    // Event stops carry the event number above the stop signal
+    let status = (Event::PTRACE_EVENT_FORK as i32) << 16 | 0x057f;
+    assert_eq!(WaitStatus::from_raw(pid, status),
+               Ok(WaitStatus::PtraceEvent(pid, Signal::SIGTRAP, Event::PTRACE_EVENT_FORK as i32)));
+    // A plain SIGTRAP is an ordinary stop
+    assert_eq!(WaitStatus::from_raw(pid, 0x057f), Ok(WaitStatus::Stopped(pid, Signal::SIGTRAP)));
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_waitstatus_from_raw_rt() {
+    let pid = Pid::from_raw(1);
+    let rtmin = SigNum::rtmin();
+    assert_eq!(WaitStatus::from_raw(pid, rtmin.as_raw()),
+               Ok(WaitStatus::RtSignaled(pid, rtmin, false)));
+    assert_eq!(WaitStatus::from_raw(pid, rtmin.as_raw() << 8 | 0x7f),
+               Ok(WaitStatus::RtStopped(pid, rtmin)));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_waitid_rt_signal() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls `pause`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => loop { pause(); },
+        Parent { child } => {
+            // The default action of a real-time signal is to terminate
+            let rtmin = SigNum::rtmin();
+            kill_num(child, rtmin).unwrap();
+            let flags = WaitPidFlag::WEXITED | WaitPidFlag::WNOWAIT;
+            let status = waitid(Id::Pid(child), flags).unwrap();
+            assert_eq!(status, WaitStatus::RtSignaled(child, rtmin, false));
+            assert_eq!(waitpid(child, None), Ok(status));
+        },
+    }
 }
 
 #[test]
@@ -57,6 +108,115 @@ fn test_waitstatus_pid() {
     }
 }
 
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_waitpid_stop_continue() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls `pause`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => loop { pause(); },
+        Parent { child } => {
+            let flags = WaitPidFlag::WUNTRACED | WaitPidFlag::WCONTINUED;
+            kill(child, SIGSTOP).unwrap();
+            assert_eq!(waitpid(child, Some(flags)), Ok(WaitStatus::Stopped(child, SIGSTOP)));
+            kill(child, SIGCONT).unwrap();
+            assert_eq!(waitpid(child, Some(flags)), Ok(WaitStatus::Continued(child)));
+            kill(child, SIGKILL).unwrap();
+            assert_eq!(waitpid(child, Some(flags)),
+                       Ok(WaitStatus::Signaled(child, SIGKILL, false)));
+        },
+    }
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_wait4() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls `pause` and `_exit`, which are
+    // async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            pause();
+            unsafe { _exit(12) }
+        },
+        Parent { child } => {
+            let (status, _) = wait4(child, Some(WaitPidFlag::WNOHANG)).unwrap();
+            assert_eq!(status, WaitStatus::StillAlive);
+            kill(child, SIGKILL).unwrap();
+            let (status, rusage) = wait4(child, None).unwrap();
+            assert_eq!(status, WaitStatus::Signaled(child, SIGKILL, false));
+            // The child touched at least some memory
+            assert!(rusage.max_rss() > 0);
+        },
+    }
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+fn test_waitid_exit_wnowait() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: Child only calls `_exit`, which is async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => unsafe { _exit(12) },
+        Parent { child } => {
+            let flags = WaitPidFlag::WEXITED | WaitPidFlag::WNOWAIT;
+            // With WNOWAIT, the child can be waited for repeatedly
+            assert_eq!(waitid(Id::Pid(child), flags), Ok(WaitStatus::Exited(child, 12)));
+            assert_eq!(waitid(Id::Pid(child), flags), Ok(WaitStatus::Exited(child, 12)));
+            assert_eq!(waitid(Id::Pid(child), WaitPidFlag::WEXITED),
+                       Ok(WaitStatus::Exited(child, 12)));
+            // Now it has been reaped
+            assert_eq!(waitid(Id::Pid(child), WaitPidFlag::WEXITED),
+                       Err(Error::Sys(Errno::ECHILD)));
+        },
+    }
+}
+
+#[test]
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "ios",
+          target_os = "linux",
+          target_os = "macos",
+          target_os = "netbsd"))]
+fn test_waitid_stop_continue() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls `setpgid` and `pause`, which are
+    // async-signal-safe.
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            let _ = setpgid(Pid::from_raw(0), Pid::from_raw(0));
+            loop { pause(); }
+        },
+        Parent { child } => {
+            // Wait by process group, in a group containing only the child
+            setpgid(child, child).unwrap();
+            let id = Id::PGid(child);
+            assert_eq!(waitid(id, WaitPidFlag::WEXITED | WaitPidFlag::WNOHANG),
+                       Ok(WaitStatus::StillAlive));
+
+            kill(child, SIGSTOP).unwrap();
+            assert_eq!(waitid(id, WaitPidFlag::WSTOPPED),
+                       Ok(WaitStatus::Stopped(child, SIGSTOP)));
+            kill(child, SIGCONT).unwrap();
+            assert_eq!(waitid(id, WaitPidFlag::WCONTINUED),
+                       Ok(WaitStatus::Continued(child)));
+            kill(child, SIGKILL).unwrap();
+            assert_eq!(waitid(id, WaitPidFlag::WEXITED),
+                       Ok(WaitStatus::Signaled(child, SIGKILL, false)));
+        },
+    }
+}
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 // FIXME: qemu-user doesn't implement ptrace on most arches
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -93,6 +253,20 @@ mod ptrace {
         assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
     }
 
+    fn ptrace_waitid_parent(child: Pid) {
+        let id = Id::Pid(child);
+        let flags = WaitPidFlag::WSTOPPED | WaitPidFlag::WEXITED;
+        assert_eq!(waitid(id, flags), Ok(WaitStatus::Stopped(child, SIGTRAP)));
+        assert!(ptrace::setoptions(child, Options::PTRACE_O_TRACESYSGOOD | Options::PTRACE_O_TRACEEXIT).is_ok());
+
+        assert!(ptrace::syscall(child, None).is_ok());
+        assert_eq!(waitid(id, flags), Ok(WaitStatus::PtraceSyscall(child)));
+        assert!(ptrace::cont(child, None).is_ok());
+        assert_eq!(waitid(id, flags), Ok(WaitStatus::PtraceEvent(child, SIGTRAP, Event::PTRACE_EVENT_EXIT as i32)));
+        assert!(ptrace::cont(child, None).is_ok());
+        assert_eq!(waitid(id, flags), Ok(WaitStatus::Exited(child, 0)));
+    }
+
     #[test]
     fn test_wait_ptrace() {
         require_capability!(CAP_SYS_PTRACE);
@@ -103,4 +277,15 @@ mod ptrace {
             Parent { child } => ptrace_parent(child),
         }
     }
+
+    #[test]
+    fn test_waitid_ptrace() {
+        require_capability!(CAP_SYS_PTRACE);
+        let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+        match fork().expect("Error: Fork Failed") {
+            Child => ptrace_child(),
+            Parent { child } => ptrace_waitid_parent(child),
+        }
+    }
 }
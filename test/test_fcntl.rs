@@ -59,6 +59,33 @@ fn test_renameat() {
     assert!(new_dir.path().join("new").exists());
 }
 
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_renameat2() {
+    use nix::fcntl::{renameat2, RenameFlags};
+
+    let dir = tempfile::tempdir().unwrap();
+    let dirfd = open(dir.path(), OFlag::empty(), Mode::empty()).unwrap();
+    File::create(dir.path().join("a")).unwrap().write_all(b"a").unwrap();
+    File::create(dir.path().join("b")).unwrap().write_all(b"b").unwrap();
+
+    match renameat2(Some(dirfd), "a", Some(dirfd), "b", RenameFlags::RENAME_NOREPLACE) {
+        Err(Error::Sys(Errno::EINVAL)) => {
+            skip!("The file system doesn't support renameat2 flags. Skipping test.");
+        },
+        res => assert_eq!(res, Err(Error::Sys(Errno::EEXIST))),
+    }
+
+    renameat2(Some(dirfd), "a", Some(dirfd), "b", RenameFlags::RENAME_EXCHANGE).unwrap();
+    assert_eq!(std::fs::read(dir.path().join("a")).unwrap(), b"b");
+    assert_eq!(std::fs::read(dir.path().join("b")).unwrap(), b"a");
+
+    renameat2(Some(dirfd), "a", Some(dirfd), "c", RenameFlags::RENAME_NOREPLACE).unwrap();
+    assert!(!dir.path().join("a").exists());
+    assert!(dir.path().join("c").exists());
+    close(dirfd).unwrap();
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_readlink() {
@@ -77,15 +104,50 @@ fn test_readlink() {
 
 }
 
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_flock_guard() {
+    use nix::fcntl::{flock, FlockArg, FlockGuard};
+    use std::os::unix::io::AsRawFd;
+
+    let tmp = NamedTempFile::new().unwrap();
+    // flock locks belong to the open file description, so the second one
+    // conflicts with the first
+    let fd1 = tmp.as_raw_fd();
+    let file2 = tmp.reopen().unwrap();
+    let fd2 = file2.as_raw_fd();
+
+    assert_eq!(FlockGuard::lock(fd1, FlockArg::Unlock).unwrap_err(),
+               Error::Sys(Errno::EINVAL));
+
+    let guard = FlockGuard::lock(fd1, FlockArg::LockExclusiveNonblock).unwrap();
+    assert_eq!(flock(fd2, FlockArg::LockSharedNonblock),
+               Err(Error::Sys(Errno::EAGAIN)));
+    drop(guard);
+
+    let guard = FlockGuard::lock(fd2, FlockArg::LockSharedNonblock).unwrap();
+    let guard1 = FlockGuard::lock(fd1, FlockArg::LockSharedNonblock).unwrap();
+    // On Linux, this failed upgrade also drops fd1's shared lock, so
+    // unlocking guard1 below has nothing left to release.
+    assert_eq!(flock(fd1, FlockArg::LockExclusiveNonblock),
+               Err(Error::Sys(Errno::EAGAIN)));
+    guard.unlock().unwrap();
+    guard1.unlock().unwrap();
+    flock(fd2, FlockArg::LockExclusiveNonblock).unwrap();
+}
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod linux_android {
     use std::fs::File;
     use std::io::prelude::*;
     use std::io::{BufRead, BufReader, SeekFrom};
     use std::os::unix::prelude::*;
+    #[cfg(target_os = "linux")]
+    use std::os::unix::fs::symlink;
 
     use libc::loff_t;
 
+    use nix::errno::Errno;
     use nix::fcntl::*;
     use nix::sys::stat::fstat;
     use nix::sys::uio::IoVec;
@@ -131,6 +193,131 @@ mod linux_android {
         assert_eq!(from_offset, 6);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_openat2() {
+        use nix::sys::stat::Mode;
+
+        let tempdir = tempfile::tempdir().unwrap();
+        File::create(tempdir.path().join("file")).unwrap();
+        std::fs::create_dir(tempdir.path().join("dir")).unwrap();
+        symlink("file", tempdir.path().join("link")).unwrap();
+        symlink("..", tempdir.path().join("dir/up")).unwrap();
+        let dirfd = open(tempdir.path(), OFlag::O_DIRECTORY, Mode::empty()).unwrap();
+
+        let how = OpenHow::new().flags(OFlag::O_RDONLY | OFlag::O_CLOEXEC);
+        let fd = match openat2(dirfd, "file", how.resolve(ResolveFlags::RESOLVE_BENEATH)) {
+            Err(nix::Error::Sys(Errno::ENOSYS)) => {
+                skip!("openat2 is not supported by this kernel. Skipping test.");
+            },
+            res => res.unwrap(),
+        };
+        close(fd).unwrap();
+
+        // Staying beneath dirfd through a symlink is fine, escaping isn't
+        let beneath = how.resolve(ResolveFlags::RESOLVE_BENEATH);
+        let fd = openat2(dirfd, "dir/up/file", beneath).unwrap();
+        close(fd).unwrap();
+        assert_eq!(openat2(dirfd, "dir/up/..", beneath),
+                   Err(nix::Error::Sys(Errno::EXDEV)));
+        assert_eq!(openat2(dirfd, "/", beneath),
+                   Err(nix::Error::Sys(Errno::EXDEV)));
+
+        let fd = openat2(dirfd, "link", how).unwrap();
+        close(fd).unwrap();
+        assert_eq!(openat2(dirfd, "link", how.resolve(ResolveFlags::RESOLVE_NO_SYMLINKS)),
+                   Err(nix::Error::Sys(Errno::ELOOP)));
+
+        // A mode without O_CREAT is invalid
+        assert_eq!(openat2(dirfd, "file", how.mode(Mode::S_IRUSR)),
+                   Err(nix::Error::Sys(Errno::EINVAL)));
+
+        close(dirfd).unwrap();
+    }
+
+    #[test]
+    fn test_file_handle() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let path = tempdir.path().join("file");
+        File::create(&path).unwrap().write_all(b"foobar").unwrap();
+
+        let (handle, _mount_id) = match name_to_handle_at(None, &path, AtFlags::empty()) {
+            Err(nix::Error::Sys(Errno::EOPNOTSUPP)) => {
+                skip!("The file system doesn't support file handles. Skipping test.");
+            },
+            res => res.unwrap(),
+        };
+        let copy = FileHandle::new(handle.handle_type(), handle.as_bytes()).unwrap();
+        assert_eq!(copy, handle);
+
+        // Moving the file doesn't change its handle
+        let new_path = tempdir.path().join("moved");
+        std::fs::rename(&path, &new_path).unwrap();
+        let (moved, _) = name_to_handle_at(None, &new_path, AtFlags::empty()).unwrap();
+        assert_eq!(moved, handle);
+
+        require_capability!(CAP_DAC_READ_SEARCH);
+        let dirfd = nix::fcntl::open(tempdir.path(), OFlag::O_DIRECTORY,
+                                     nix::sys::stat::Mode::empty()).unwrap();
+        let fd = open_by_handle_at(dirfd, &copy, OFlag::O_RDONLY).unwrap();
+        let mut buf = [0u8; 6];
+        assert_eq!(read(fd, &mut buf).unwrap(), 6);
+        assert_eq!(&buf, b"foobar");
+
+        close(fd).unwrap();
+        close(dirfd).unwrap();
+    }
+
+    #[test]
+    fn test_fcntl_dupfd_cloexec() {
+        let (rd, wr) = pipe().unwrap();
+        let fd = fcntl(rd, FcntlArg::F_DUPFD_CLOEXEC(100)).unwrap();
+        assert!(fd >= 100);
+        let flags = FdFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFD).unwrap());
+        assert!(flags.contains(FdFlag::FD_CLOEXEC));
+
+        for fd in &[fd, rd, wr] {
+            close(*fd).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_fcntl_pipe_size() {
+        let (rd, wr) = pipe().unwrap();
+        let size = fcntl(rd, FcntlArg::F_GETPIPE_SZ).unwrap();
+        assert!(size > 0);
+        let new_size = fcntl(wr, FcntlArg::F_SETPIPE_SZ(size * 2)).unwrap();
+        assert!(new_size >= size * 2);
+        assert_eq!(fcntl(rd, FcntlArg::F_GETPIPE_SZ).unwrap(), new_size);
+
+        close(rd).unwrap();
+        close(wr).unwrap();
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_fcntl_seals() {
+        use nix::sys::memfd::{memfd_create, MemFdCreateFlag};
+        use std::ffi::CString;
+
+        let name = CString::new("test_fcntl_seals").unwrap();
+        let fd = memfd_create(&name, MemFdCreateFlag::MFD_ALLOW_SEALING).unwrap();
+        let seals = SealFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GET_SEALS).unwrap());
+        assert_eq!(seals, SealFlag::empty());
+
+        write(fd, b"abc").unwrap();
+        fcntl(fd, FcntlArg::F_ADD_SEALS(SealFlag::F_SEAL_GROW | SealFlag::F_SEAL_SEAL)).unwrap();
+        let seals = SealFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GET_SEALS).unwrap());
+        assert_eq!(seals, SealFlag::F_SEAL_GROW | SealFlag::F_SEAL_SEAL);
+
+        // The file can't grow, and no more seals can be added
+        assert_eq!(write(fd, b"def"), Err(nix::Error::Sys(Errno::EPERM)));
+        assert_eq!(fcntl(fd, FcntlArg::F_ADD_SEALS(SealFlag::F_SEAL_WRITE)),
+                   Err(nix::Error::Sys(Errno::EPERM)));
+
+        close(fd).unwrap();
+    }
+
     #[test]
     fn test_splice() {
         const CONTENTS: &[u8] = b"abcdef123456";
@@ -153,6 +340,33 @@ mod linux_android {
         close(wr).unwrap();
     }
 
+    #[test]
+    fn test_splice_socket() {
+        use nix::sys::socket::{socketpair, AddressFamily, SockFlag, SockType};
+
+        let (s1, s2) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+                                  SockFlag::empty()).unwrap();
+        let (rd, wr) = pipe().unwrap();
+
+        // An empty pipe doesn't block with SPLICE_F_NONBLOCK
+        let err = splice(rd, None, s1, None, 3, SpliceFFlags::SPLICE_F_NONBLOCK)
+            .unwrap_err();
+        assert_eq!(err, nix::Error::Sys(Errno::EAGAIN));
+
+        write(wr, b"abc").unwrap();
+        let res = splice(rd, None, s1, None, 3, SpliceFFlags::SPLICE_F_MORE)
+            .unwrap();
+        assert_eq!(3, res);
+
+        let mut buf = [0u8; 32];
+        assert_eq!(3, read(s2, &mut buf).unwrap());
+        assert_eq!(b"abc", &buf[0..3]);
+
+        for fd in &[rd, wr, s1, s2] {
+            close(*fd).unwrap();
+        }
+    }
+
     #[test]
     fn test_tee() {
         let (rd1, wr1) = pipe().unwrap();
@@ -214,6 +428,122 @@ mod linux_android {
         assert_eq!(100, read(fd, &mut buf).unwrap());
     }
 
+    #[test]
+    fn test_ofd_flock_range() {
+        let tmp = NamedTempFile::new().unwrap();
+        // Two open file descriptions of the same file
+        let fd1 = tmp.as_raw_fd();
+        let file2 = tmp.reopen().unwrap();
+        let fd2 = file2.as_raw_fd();
+
+        let lock = FlockRange::new(LockType::Write, nix::unistd::Whence::SeekSet, 10, 20);
+        assert_eq!(lock.lock_type(), LockType::Write);
+        assert_eq!(lock.start(), 10);
+        assert_eq!(lock.length(), 20);
+        match fcntl(fd1, FcntlArg::F_OFD_SETLK(lock.as_ref())) {
+            Err(nix::Error::Sys(Errno::EINVAL)) => {
+                skip!("OFD locks are not supported");
+            },
+            res => res.unwrap(),
+        };
+
+        // The lock conflicts with the other open file description, even in
+        // the same process
+        let err = fcntl(fd2, FcntlArg::F_OFD_SETLK(FlockRange::whole_file(LockType::Read).as_ref()))
+            .unwrap_err();
+        assert!(err == nix::Error::Sys(Errno::EAGAIN) || err == nix::Error::Sys(Errno::EACCES));
+
+        let mut query = FlockRange::whole_file(LockType::Write);
+        fcntl(fd2, FcntlArg::F_OFD_GETLK(query.as_mut())).unwrap();
+        assert_eq!(query.lock_type(), LockType::Write);
+        assert_eq!(query.start(), 10);
+        assert_eq!(query.length(), 20);
+        assert_eq!(query.pid(), None);
+
+        let unlock = FlockRange::whole_file(LockType::Unlock);
+        fcntl(fd1, FcntlArg::F_OFD_SETLK(unlock.as_ref())).unwrap();
+
+        let mut query = FlockRange::whole_file(LockType::Write);
+        fcntl(fd2, FcntlArg::F_OFD_GETLK(query.as_mut())).unwrap();
+        assert_eq!(query.lock_type(), LockType::Unlock);
+    }
+
+    #[test]
+    fn test_tmpfile_link() {
+        use nix::sys::stat::Mode;
+
+        let dir = tempfile::tempdir().unwrap();
+        let fd = match open_tmpfile(None, dir.path(), OFlag::O_RDWR | OFlag::O_CLOEXEC,
+                                    Mode::S_IRUSR | Mode::S_IWUSR) {
+            Err(nix::Error::Sys(Errno::EOPNOTSUPP)) | Err(nix::Error::Sys(Errno::EISDIR)) => {
+                skip!("O_TMPFILE is not supported by the file system");
+            },
+            res => res.unwrap(),
+        };
+        write(fd, b"contents").unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let path = dir.path().join("published");
+        link_tmpfile(fd, None, &path).unwrap();
+        close(fd).unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"contents");
+
+        // The name must not exist yet
+        let fd = open_tmpfile(None, dir.path(), OFlag::O_WRONLY,
+                              Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+        assert_eq!(link_tmpfile(fd, None, &path), Err(nix::Error::Sys(Errno::EEXIST)));
+        close(fd).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_readahead() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(&[0u8; 8192]).unwrap();
+        readahead(tmp.as_raw_fd(), 0, 8192).unwrap();
+
+        let (rd, wr) = pipe().unwrap();
+        assert_eq!(readahead(rd, 0, 8192), Err(nix::Error::Sys(Errno::EINVAL)));
+        close(rd).unwrap();
+        close(wr).unwrap();
+    }
+
+    #[test]
+    fn test_fallocate_keep_size() {
+        let tmp = NamedTempFile::new().unwrap();
+
+        let fd = tmp.as_raw_fd();
+        match fallocate(fd, FallocateFlags::FALLOC_FL_KEEP_SIZE, 0, 4096) {
+            Err(nix::Error::Sys(Errno::EOPNOTSUPP)) => {
+                skip!("The file system doesn't support this fallocate mode. Skipping test.");
+            },
+            res => res.unwrap(),
+        }
+        assert_eq!(fstat(fd).unwrap().st_size, 0);
+    }
+
+    #[test]
+    fn test_fallocate_punch_hole() {
+        let mut tmp = NamedTempFile::new().unwrap();
+        tmp.write_all(&[0xffu8; 8192]).unwrap();
+
+        let fd = tmp.as_raw_fd();
+        let mode = FallocateFlags::FALLOC_FL_PUNCH_HOLE | FallocateFlags::FALLOC_FL_KEEP_SIZE;
+        match fallocate(fd, mode, 0, 4096) {
+            Err(nix::Error::Sys(Errno::EOPNOTSUPP)) => {
+                skip!("The file system doesn't support this fallocate mode. Skipping test.");
+            },
+            res => res.unwrap(),
+        }
+        assert_eq!(fstat(fd).unwrap().st_size, 8192);
+
+        let mut buf = vec![0u8; 8192];
+        tmp.seek(SeekFrom::Start(0)).unwrap();
+        tmp.read_exact(&mut buf).unwrap();
+        assert!(buf[..4096].iter().all(|&b| b == 0));
+        assert!(buf[4096..].iter().all(|&b| b == 0xff));
+    }
+
     // The tests below are disabled for the listed targets
     // due to OFD locks not being available in the kernel/libc
     // versions used in the CI environment, probably because
@@ -333,25 +663,25 @@ mod test_posix_fadvise {
 
     use tempfile::NamedTempFile;
     use std::os::unix::io::{RawFd, AsRawFd};
+    use nix::Error;
     use nix::errno::Errno;
     use nix::fcntl::*;
-    use nix::unistd::pipe;
+    use nix::unistd::{close, pipe};
 
     #[test]
     fn test_success() {
         let tmp = NamedTempFile::new().unwrap();
         let fd = tmp.as_raw_fd();
-        let res = posix_fadvise(fd, 0, 100, PosixFadviseAdvice::POSIX_FADV_WILLNEED).unwrap();
-
-        assert_eq!(res, 0);
+        posix_fadvise(fd, 0, 100, PosixFadviseAdvice::POSIX_FADV_WILLNEED).unwrap();
     }
 
     #[test]
     fn test_errno() {
-        let (rd, _wr) = pipe().unwrap();
-        let errno = posix_fadvise(rd as RawFd, 0, 100, PosixFadviseAdvice::POSIX_FADV_WILLNEED)
-                                 .unwrap();
-        assert_eq!(errno, Errno::ESPIPE as i32);
+        let (rd, wr) = pipe().unwrap();
+        let res = posix_fadvise(rd as RawFd, 0, 100, PosixFadviseAdvice::POSIX_FADV_WILLNEED);
+        assert_eq!(res, Err(Error::Sys(Errno::ESPIPE)));
+        close(rd).unwrap();
+        close(wr).unwrap();
     }
 }
 
@@ -10,3 +10,81 @@ const LOOPBACK: &[u8] = b"lo0";
 fn test_if_nametoindex() {
     assert!(if_nametoindex(&LOOPBACK[..]).is_ok());
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_interface_query() {
+    use nix::sys::socket::{InetAddr, IpAddr, SockAddr};
+
+    let lo = Interface::new(LOOPBACK).unwrap();
+    assert_eq!(lo.name().to_bytes(), LOOPBACK);
+    assert_eq!(lo.index().unwrap(), if_nametoindex(LOOPBACK).unwrap());
+    let flags = lo.flags().unwrap();
+    assert!(flags.contains(InterfaceFlags::IFF_UP | InterfaceFlags::IFF_LOOPBACK));
+    assert!(lo.mtu().unwrap() > 0);
+    assert_eq!(lo.hw_addr().unwrap(), [0; 6]);
+    assert_eq!(lo.addr().unwrap(),
+               SockAddr::new_inet(InetAddr::new(IpAddr::new_v4(127, 0, 0, 1), 0)));
+
+    assert!(Interface::new("a_much_too_long_interface_name").is_err());
+    let missing = Interface::new("nonexistent0").unwrap();
+    assert!(missing.mtu().is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_interface_set_mtu() {
+    require_capability!(CAP_NET_ADMIN);
+
+    let lo = Interface::new(LOOPBACK).unwrap();
+    let mtu = lo.mtu().unwrap();
+    // Set the MTU to its current value, so as not to disturb other tests
+    lo.set_mtu(mtu).unwrap();
+    assert_eq!(lo.mtu().unwrap(), mtu);
+    lo.set_flags(lo.flags().unwrap()).unwrap();
+}
+
+#[test]
+fn test_if_indextoname() {
+    let index = if_nametoindex(LOOPBACK).unwrap();
+    assert_eq!(if_indextoname(index).unwrap().as_bytes(), LOOPBACK);
+    assert!(if_indextoname(0).is_err());
+}
+
+#[cfg(not(target_os = "android"))]
+#[test]
+fn test_if_nameindex() {
+    let index = if_nametoindex(LOOPBACK).unwrap();
+    let interfaces = if_nameindex().unwrap().collect::<Vec<_>>();
+    assert!(interfaces.iter().any(|&(i, ref name)| {
+        i == index && name.as_bytes() == LOOPBACK
+    }));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_tun_alloc() {
+    use nix::net::tun::{tun_alloc, TunFlags};
+    use nix::unistd::close;
+
+    require_capability!(CAP_NET_ADMIN);
+
+    let flags = TunFlags::IFF_TUN | TunFlags::IFF_NO_PI;
+    let (fd, name) = match tun_alloc(Some("nixtun%d"), flags) {
+        Ok(tun) => tun,
+        // /dev/net/tun may be missing in containers
+        Err(_) => { skip!("test_tun_alloc: cannot create TUN device. Skipping test."); }
+    };
+    assert!(name.as_bytes().starts_with(b"nixtun"));
+    let tun = Interface::new(name.as_c_str()).unwrap();
+    assert!(!tun.flags().unwrap().contains(InterfaceFlags::IFF_LOOPBACK));
+    close(fd).unwrap();
+
+    // Without a name, the kernel picks one
+    let (fd, name) = tun_alloc(None, flags).unwrap();
+    assert!(!name.as_bytes().is_empty());
+    close(fd).unwrap();
+
+    assert!(tun_alloc(Some("a_much_too_long_interface_name"), flags).is_err());
+    assert!(tun_alloc(Some("nix\0tun"), flags).is_err());
+}
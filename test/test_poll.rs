@@ -1,5 +1,5 @@
 use nix::poll::{PollFlags, poll, PollFd};
-use nix::unistd::{write, pipe};
+use nix::unistd::{close, write, pipe};
 
 #[test]
 fn test_poll() {
@@ -19,6 +19,47 @@ fn test_poll() {
     assert!(fds[0].revents().unwrap().contains(PollFlags::POLLIN));
 }
 
+#[test]
+fn test_pollfd_update() {
+    use std::os::unix::io::AsRawFd;
+
+    let (r, w) = pipe().unwrap();
+    let mut fds = [PollFd::new(w, PollFlags::empty())];
+    assert_eq!(fds[0].as_raw_fd(), w);
+
+    // Nothing is watched, so the writable pipe is not reported
+    assert_eq!(poll(&mut fds, 0).unwrap(), 0);
+
+    fds[0].set_events(PollFlags::POLLOUT);
+    assert_eq!(fds[0].events(), PollFlags::POLLOUT);
+    assert_eq!(poll(&mut fds, 0).unwrap(), 1);
+    assert!(fds[0].revents().unwrap().contains(PollFlags::POLLOUT));
+
+    fds[0].clear_revents();
+    assert_eq!(fds[0].revents(), Some(PollFlags::empty()));
+    close(r).unwrap();
+    close(w).unwrap();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[test]
+fn test_poll_rdhup() {
+    use nix::sys::socket::{socketpair, shutdown, AddressFamily, Shutdown,
+                           SockFlag, SockType};
+
+    let (a, b) = socketpair(AddressFamily::Unix, SockType::Stream, None,
+                            SockFlag::empty()).unwrap();
+    let mut fds = [PollFd::new(a, PollFlags::POLLRDHUP)];
+    assert_eq!(poll(&mut fds, 0).unwrap(), 0);
+
+    shutdown(b, Shutdown::Write).unwrap();
+    assert_eq!(poll(&mut fds, 0).unwrap(), 1);
+    assert!(fds[0].revents().unwrap().contains(PollFlags::POLLRDHUP));
+
+    close(a).unwrap();
+    close(b).unwrap();
+}
+
 // ppoll(2) is the same as poll except for how it handles timeouts and signals.
 // Repeating the test for poll(2) should be sufficient to check that our
 // bindings are correct.
@@ -47,4 +88,8 @@ fn test_ppoll() {
     let nfds = ppoll(&mut fds, Some(timeout), SigSet::empty()).unwrap();
     assert_eq!(nfds, 1);
     assert!(fds[0].revents().unwrap().contains(PollFlags::POLLIN));
+
+    // Without a signal mask, and with a nanosecond timeout
+    let nfds = ppoll(&mut fds, Some(TimeSpec::nanoseconds(1)), None).unwrap();
+    assert_eq!(nfds, 1);
 }
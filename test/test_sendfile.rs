@@ -1,17 +1,47 @@
 use std::io::prelude::*;
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
 use std::os::unix::prelude::*;
 
 use libc::off_t;
 use nix::sys::sendfile::*;
 use tempfile::tempfile;
 
-cfg_if! {
-    if #[cfg(any(target_os = "android", target_os = "linux"))] {
-        use nix::unistd::{close, pipe, read};
-    } else if #[cfg(any(target_os = "freebsd", target_os = "ios", target_os = "macos"))] {
-        use std::net::Shutdown;
-        use std::os::unix::net::UnixStream;
-    }
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use nix::unistd::{close, pipe, read};
+
+#[test]
+fn test_send_file() {
+    let header_strings = vec!["HTTP/1.1 200 OK\n", "Content-Type: text/plain\n", "\n"];
+    let body = "Xabcdef123456";
+    let trailer_strings = vec!["\n", "Served by Make Believe\n"];
+
+    let mut tmp = tempfile().unwrap();
+    tmp.write_all(body.as_bytes()).unwrap();
+
+    let headers: Vec<&[u8]> = header_strings.iter().map(|s| s.as_bytes()).collect();
+    let trailers: Vec<&[u8]> = trailer_strings.iter().map(|s| s.as_bytes()).collect();
+
+    let (mut rd, wr) = UnixStream::pair().unwrap();
+
+    // A count of 0 sends the rest of the file
+    let (res, sent) = send_file(wr.as_raw_fd(), tmp.as_raw_fd(), 1, 0,
+                                Some(headers.as_slice()), Some(trailers.as_slice()));
+    res.unwrap();
+    let (res, sent2) = send_file(wr.as_raw_fd(), tmp.as_raw_fd(), 1, 6,
+                                 Some(headers.as_slice()), None);
+    res.unwrap();
+    let (res, sent3) = send_file(wr.as_raw_fd(), tmp.as_raw_fd(), 7, 3, None, None);
+    res.unwrap();
+    wr.shutdown(Shutdown::Both).unwrap();
+
+    let expected = header_strings.concat() + &body[1..] + &trailer_strings.concat()
+        + &header_strings.concat() + &body[1..7] + &body[7..10];
+    assert_eq!(sent + sent2 + sent3, expected.len());
+
+    let mut read_string = String::new();
+    rd.read_to_string(&mut read_string).unwrap();
+    assert_eq!(expected, read_string);
 }
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
@@ -36,6 +66,28 @@ fn test_sendfile_linux() {
     close(wr).unwrap();
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_sendfile64_linux() {
+    const CONTENTS: &[u8] = b"abcdef123456";
+    let mut tmp = tempfile().unwrap();
+    tmp.write_all(CONTENTS).unwrap();
+
+    let (rd, wr) = pipe().unwrap();
+    let mut offset: libc::off64_t = 5;
+    let res = sendfile64(wr, tmp.as_raw_fd(), Some(&mut offset), 2).unwrap();
+
+    assert_eq!(2, res);
+
+    let mut buf = [0u8; 1024];
+    assert_eq!(2, read(rd, &mut buf).unwrap());
+    assert_eq!(b"f1", &buf[0..2]);
+    assert_eq!(7, offset);
+
+    close(rd).unwrap();
+    close(wr).unwrap();
+}
+
 #[cfg(target_os = "freebsd")]
 #[test]
 fn test_sendfile_freebsd() {
@@ -0,0 +1,80 @@
+use nix::errno::Errno;
+use nix::fcntl::OFlag;
+use nix::spawn::*;
+use nix::sys::signal::{SigSet, Signal};
+use nix::sys::stat::Mode;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::{close, getpgid, pipe, Pid};
+use nix::Error;
+use std::ffi::{CStr, CString};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_posix_spawn_file_actions() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let tempdir = tempdir().unwrap();
+    let path = tempdir.path().join("out");
+    let mut actions = PosixSpawnFileActions::new().unwrap();
+    actions.add_open(1, &path, OFlag::O_WRONLY | OFlag::O_CREAT,
+                     Mode::S_IRUSR | Mode::S_IWUSR).unwrap();
+    let attr = PosixSpawnAttr::new().unwrap();
+
+    let sh = CString::new("/bin/sh").unwrap();
+    let args = [sh.as_c_str(),
+                &CString::new("-c").unwrap(),
+                &CString::new("echo foo=$foo").unwrap()];
+    let env = [CString::new("foo=bar").unwrap()];
+    let env: Vec<&CStr> = env.iter().map(|s| s.as_c_str()).collect();
+    let pid = posix_spawn(&sh, &actions, &attr, &args, &env).unwrap();
+
+    assert_eq!(waitpid(pid, None), Ok(WaitStatus::Exited(pid, 0)));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "foo=bar\n");
+}
+
+#[test]
+fn test_posix_spawn_attr() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // The child blocks reading its standard input until `w` is closed
+    let (r, w) = pipe().unwrap();
+    let mut actions = PosixSpawnFileActions::new().unwrap();
+    actions.add_dup2(r, 0).unwrap();
+    actions.add_close(r).unwrap();
+    actions.add_close(w).unwrap();
+
+    let mut attr = PosixSpawnAttr::new().unwrap();
+    let flags = PosixSpawnFlags::POSIX_SPAWN_SETPGROUP | PosixSpawnFlags::POSIX_SPAWN_SETSIGMASK;
+    attr.set_flags(flags).unwrap();
+    assert_eq!(attr.flags().unwrap(), flags);
+    attr.set_pgroup(Pid::from_raw(0)).unwrap();
+    assert_eq!(attr.pgroup().unwrap(), Pid::from_raw(0));
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGUSR1);
+    attr.set_sigmask(&mask).unwrap();
+
+    let sh = CString::new("sh").unwrap();
+    let args = [sh.as_c_str(), &CString::new("-c").unwrap(), &CString::new("read x; exit 0").unwrap()];
+    let env: [&CStr; 0] = [];
+    let pid = posix_spawnp(&sh, &actions, &attr, &args, &env).unwrap();
+    close(r).unwrap();
+
+    // The child leads a new process group
+    assert_eq!(getpgid(Some(pid)), Ok(pid));
+
+    close(w).unwrap();
+    assert_eq!(waitpid(pid, None), Ok(WaitStatus::Exited(pid, 0)));
+}
+
+#[test]
+fn test_posix_spawnp_enoent() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let actions = PosixSpawnFileActions::new().unwrap();
+    let attr = PosixSpawnAttr::new().unwrap();
+    let file = CString::new("nix-test-nonexistent-program").unwrap();
+    let env: [&CStr; 0] = [];
+    assert_eq!(posix_spawnp(&file, &actions, &attr, &[&file], &env),
+               Err(Error::Sys(Errno::ENOENT)));
+}
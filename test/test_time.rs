@@ -0,0 +1,157 @@
+#[cfg(target_os = "linux")]
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigEvent, SigHandler,
+                       SigSet, SigevNotify, Signal};
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+use nix::sys::time::{TimeSpec, TimeValLike};
+#[cfg(target_os = "linux")]
+use nix::sys::timer::{Timer, TimerSetTimeFlags};
+#[cfg(target_os = "linux")]
+use nix::sys::timerfd::Expiration;
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+use nix::time::clock_getcpuclockid;
+use nix::time::{clock_getres, clock_gettime, ClockId};
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+use nix::time::{clock_nanosleep, ClockNanosleepFlags};
+#[cfg(target_os = "linux")]
+use nix::time::{adjtimex, TimeX, TimexModes};
+#[cfg(target_os = "linux")]
+use nix::unistd::{getuid, gettid};
+#[cfg(target_os = "linux")]
+use nix::Error;
+#[cfg(target_os = "linux")]
+use nix::errno::Errno;
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+use nix::unistd::Pid;
+
+#[test]
+pub fn test_clock_gettime() {
+    let a = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    let b = ClockId::CLOCK_MONOTONIC.now().unwrap();
+    assert!(b >= a);
+}
+
+#[test]
+pub fn test_clock_getres() {
+    let res = clock_getres(ClockId::CLOCK_REALTIME).unwrap();
+    assert!(res.tv_sec() > 0 || res.tv_nsec() > 0);
+    assert_eq!(ClockId::CLOCK_REALTIME.res().unwrap(), res);
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "dragonfly",
+          target_os = "freebsd",
+          target_os = "linux"))]
+#[test]
+pub fn test_clock_getcpuclockid() {
+    let clock_id = clock_getcpuclockid(Pid::this()).unwrap();
+    clock_id.now().unwrap();
+    assert_eq!(ClockId::pid_cpu_clock_id(Pid::this()).unwrap(), clock_id);
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+#[test]
+pub fn test_clock_nanosleep_relative() {
+    let start = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    let request = TimeSpec::milliseconds(10);
+    let remain = clock_nanosleep(ClockId::CLOCK_MONOTONIC,
+                                 ClockNanosleepFlags::empty(),
+                                 &request).unwrap();
+    assert_eq!(remain, None);
+    let end = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap();
+    assert!(end - start >= request);
+}
+
+#[cfg(any(target_os = "android",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd"))]
+#[test]
+pub fn test_clock_nanosleep_absolute() {
+    let deadline = clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap()
+        + TimeSpec::milliseconds(10);
+    let remain = clock_nanosleep(ClockId::CLOCK_MONOTONIC,
+                                 ClockNanosleepFlags::TIMER_ABSTIME,
+                                 &deadline).unwrap();
+    assert_eq!(remain, None);
+    assert!(clock_gettime(ClockId::CLOCK_MONOTONIC).unwrap() >= deadline);
+
+    // A deadline in the past returns immediately
+    let remain = clock_nanosleep(ClockId::CLOCK_MONOTONIC,
+                                 ClockNanosleepFlags::TIMER_ABSTIME,
+                                 &deadline).unwrap();
+    assert_eq!(remain, None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_clock_nanosleep_interrupted() {
+    extern "C" fn handler(_: libc::c_int) {}
+
+    let _m = crate::SIGNAL_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let act = SigAction::new(SigHandler::Handler(handler), SaFlags::empty(),
+                             SigSet::empty());
+    let oact = unsafe { sigaction(Signal::SIGUSR2, &act) }.unwrap();
+
+    // Interrupt the sleep of this thread after 10 milliseconds
+    let sigevent = SigEvent::new(SigevNotify::SigevThreadId {
+        signal: Signal::SIGUSR2,
+        thread_id: gettid().as_raw(),
+        si_value: 0,
+    });
+    let timer = Timer::new(ClockId::CLOCK_MONOTONIC, sigevent).unwrap();
+    timer.set(Expiration::OneShot(TimeSpec::milliseconds(10)),
+              TimerSetTimeFlags::empty()).unwrap();
+
+    let request = TimeSpec::seconds(10);
+    let remain = clock_nanosleep(ClockId::CLOCK_MONOTONIC,
+                                 ClockNanosleepFlags::empty(),
+                                 &request).unwrap().unwrap();
+    assert!(remain < request);
+    assert!(remain > TimeSpec::seconds(5));
+
+    drop(timer);
+    unsafe { sigaction(Signal::SIGUSR2, &oact) }.unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_adjtimex_read() {
+    let mut tx = TimeX::new();
+    assert_eq!(tx.modes(), TimexModes::empty());
+    adjtimex(&mut tx).unwrap();
+    assert!(tx.tick() > 0);
+    assert!(tx.tolerance() > 0);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+pub fn test_adjtimex_set_unprivileged() {
+    if getuid().is_root() {
+        skip!("Cannot test adjtimex permission errors as root");
+    }
+
+    let mut tx = TimeX::new();
+    adjtimex(&mut tx).unwrap();
+    let freq = tx.freq();
+
+    let mut tx = TimeX::new();
+    tx.set_freq(freq);
+    assert_eq!(tx.modes(), TimexModes::ADJ_FREQUENCY);
+    assert_eq!(adjtimex(&mut tx), Err(Error::Sys(Errno::EPERM)));
+}
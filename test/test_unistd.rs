@@ -92,6 +92,42 @@ fn test_mkstemp_directory() {
     assert!(mkstemp(&env::temp_dir()).is_err());
 }
 
+#[test]
+#[cfg(any(target_os = "emscripten",
+          target_os = "freebsd",
+          target_os = "linux",
+          target_os = "netbsd",
+          target_os = "openbsd"))]
+fn test_mkostemp() {
+    let tempdir = tempdir().unwrap();
+    let template = tempdir.path().join("nix_tempfile.XXXXXX");
+
+    let (fd, path) = mkostemp(&template, OFlag::O_CLOEXEC).unwrap();
+    assert_ne!(path, template);
+    assert!(path.starts_with(tempdir.path()));
+    let flags = FdFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFD).unwrap());
+    assert!(flags.contains(FdFlag::FD_CLOEXEC));
+    close(fd).unwrap();
+}
+
+#[test]
+fn test_mkdtemp() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tempdir = tempdir().unwrap();
+    let template = tempdir.path().join("nix_tempdir.XXXXXX");
+
+    let path = mkdtemp(&template).unwrap();
+    assert_ne!(path, template);
+    let metadata = fs::metadata(&path).unwrap();
+    assert!(metadata.is_dir());
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o700);
+
+    // The template must end with XXXXXX
+    assert_eq!(mkdtemp(&tempdir.path().join("nix_tempdir")),
+               Err(Error::Sys(Errno::EINVAL)));
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_mkfifo() {
@@ -187,6 +223,89 @@ fn test_getsid() {
     assert_eq!(none_sid, pid_sid);
 }
 
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_daemonize() {
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let (rd, wr) = pipe().unwrap();
+    let our_sid = getsid(None).unwrap();
+    // Safe: the child and the daemon only make system calls, and don't
+    // allocate or panic.
+    match fork().expect("Error: Fork Failed") {
+        Child => {
+            let ok = daemonize(false, false).is_ok() && {
+                let sid = getsid(None);
+                let null = stat::stat("/dev/null");
+                let stdin = stat::fstat(0);
+                let cwd = stat::stat(".");
+                let root = stat::stat("/");
+                match (sid, null, stdin, cwd, root) {
+                    (Ok(sid), Ok(null), Ok(stdin), Ok(cwd), Ok(root)) => {
+                        sid != our_sid && sid != getpid() &&
+                            (cwd.st_dev, cwd.st_ino) == (root.st_dev, root.st_ino) &&
+                            stdin.st_rdev == null.st_rdev
+                    },
+                    _ => false,
+                }
+            };
+            let _ = write(wr, &[ok as u8]);
+            unsafe { _exit(0) };
+        },
+        Parent { child } => {
+            close(wr).unwrap();
+            // The intermediate process exits as soon as it has forked.
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+            // The pipe is closed once the daemon has exited.
+            let mut buf = [0u8; 2];
+            assert_eq!(read(rd, &mut buf), Ok(1));
+            assert_eq!(buf[0], 1);
+            close(rd).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_gethostname() {
+    let hostname = gethostname().unwrap();
+    assert!(!hostname.is_empty());
+    assert!(hostname.len() < 256);
+    assert!(!hostname.as_bytes().contains(&0));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_sethostname_setdomainname() {
+    use nix::sched::{unshare, CloneFlags};
+
+    skip_if_not_root!("test_sethostname_setdomainname");
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: the child only changes its own UTS namespace before exiting.
+    match fork().unwrap() {
+        Child => {
+            if unshare(CloneFlags::CLONE_NEWUTS).is_err() {
+                unsafe { _exit(2) };
+            }
+            let ok = sethostname("nix-test-host").is_ok()
+                && setdomainname("nix-test-domain").is_ok()
+                && gethostname().unwrap() == "nix-test-host"
+                && getdomainname().unwrap() == "nix-test-domain"
+                && nix::sys::utsname::uname().nodename() == "nix-test-host";
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            match waitpid(child, None) {
+                Ok(WaitStatus::Exited(_, 2)) => {
+                    skip!("Can't create a UTS namespace. Skipping test.");
+                },
+                status => assert_eq!(status, Ok(WaitStatus::Exited(child, 0))),
+            }
+        }
+    }
+}
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 mod linux_android {
     use nix::unistd::gettid;
@@ -221,6 +340,60 @@ fn test_setgroups() {
     setgroups(&old_groups).unwrap();
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_getgrouplist_many_groups() {
+    use nix::mount::{mount, MsFlags};
+    use nix::sched::{unshare, CloneFlags};
+
+    // Mounting requires root
+    skip_if_not_root!("test_getgrouplist_many_groups");
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // A user in more groups than twice the initial size of the buffer, in a
+    // group file that the child mounts over /etc/group in its own namespace.
+    let tempdir = tempdir().unwrap();
+    let group_file = tempdir.path().join("group");
+    let mut contents = String::new();
+    for i in 0..40 {
+        contents.push_str(&format!("nixtest{}:x:{}:nixtestuser\n", i, 60000 + i));
+    }
+    fs::write(&group_file, contents).unwrap();
+    let user = CString::new("nixtestuser").unwrap();
+    const NONE: Option<&'static [u8]> = None;
+
+    // Not async-signal-safe: getgrouplist goes through NSS and allocates, so
+    // the child can deadlock if another test thread held a libc lock when we
+    // forked.  We accept that risk, since the group file has to be mounted
+    // in a private namespace that only a child can enter.
+    match fork().unwrap() {
+        Child => {
+            let mounted = unshare(CloneFlags::CLONE_NEWNS).is_ok()
+                && mount(NONE, "/", NONE, MsFlags::MS_REC | MsFlags::MS_PRIVATE, NONE).is_ok()
+                && mount(Some(&group_file), "/etc/group", NONE, MsFlags::MS_BIND, NONE).is_ok();
+            if !mounted {
+                unsafe { _exit(2) };
+            }
+            let ok = match getgrouplist(&user, Gid::from_raw(123)) {
+                Ok(groups) => groups.len() == 41
+                    && groups.contains(&Gid::from_raw(123))
+                    && groups.contains(&Gid::from_raw(60039)),
+                Err(_) => false,
+            };
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            match waitpid(child, None) {
+                Ok(WaitStatus::Exited(_, 2)) => {
+                    skip!("Can't mount a group file. Skipping test.");
+                },
+                status => assert_eq!(status, Ok(WaitStatus::Exited(child, 0))),
+            }
+        }
+    }
+}
+
 #[test]
 // `getgroups()` and `setgroups()` do not behave as expected on Apple platforms
 #[cfg(not(any(target_os = "ios", target_os = "macos", target_os = "redox")))]
@@ -253,6 +426,46 @@ fn test_initgroups() {
     setgroups(&old_groups).unwrap();
 }
 
+#[test]
+#[cfg(any(target_os = "android", target_os = "freebsd",
+          target_os = "linux", target_os = "openbsd"))]
+fn test_getresuid() {
+    let ResUid { real, effective, .. } = getresuid().unwrap();
+    assert_eq!(real, getuid());
+    assert_eq!(effective, geteuid());
+    let ResGid { real, effective, .. } = getresgid().unwrap();
+    assert_eq!(real, getgid());
+    assert_eq!(effective, getegid());
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "freebsd",
+          target_os = "linux", target_os = "openbsd"))]
+fn test_setresuid_drop_privileges() {
+    skip_if_not_root!("test_setresuid_drop_privileges");
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    // Safe: The child only calls async-signal-safe functions before exiting
+    match fork().unwrap() {
+        Child => {
+            let nobody = Uid::from_raw(65534);
+            let nogroup = Gid::from_raw(65534);
+            let ok = setresgid(nogroup, nogroup, nogroup).is_ok()
+                && setresuid(nobody, nobody, nobody).is_ok()
+                && getresuid() == Ok(ResUid { real: nobody, effective: nobody, saved: nobody })
+                && getresgid() == Ok(ResGid { real: nogroup, effective: nogroup, saved: nogroup })
+                // The saved uid no longer allows getting root back
+                && setreuid(Uid::from_raw(0), Uid::from_raw(0)) == Err(Error::Sys(Errno::EPERM))
+                && seteuid(Uid::from_raw(0)) == Err(Error::Sys(Errno::EPERM));
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
 #[cfg(not(target_os = "redox"))]
 macro_rules! execve_test_factory(
     ($test_name:ident, $syscall:ident, $exe: expr $(, $pathname:expr, $flags:expr)*) => (
@@ -352,6 +565,13 @@ cfg_if!{
                              "./sh", AtFlags::empty());
         execve_test_factory!(test_execveat_absolute, execveat, File::open("/").unwrap().into_raw_fd(),
                              "/bin/sh", AtFlags::empty());
+        execve_test_factory!(test_execveat_memfd, execveat, {
+                                 use nix::sys::memfd::{memfd_create, MemFdCreateFlag};
+                                 let name = CString::new("sh").unwrap();
+                                 let fd = memfd_create(&name, MemFdCreateFlag::MFD_CLOEXEC).unwrap();
+                                 write(fd, &fs::read("/bin/sh").unwrap()).unwrap();
+                                 fd
+                             }, "", AtFlags::AT_EMPTY_PATH);
     }
 }
 
@@ -394,6 +614,29 @@ fn test_getcwd() {
     assert_eq!(getcwd().unwrap(), inner_tmp_dir.as_path());
 }
 
+#[test]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+fn test_getcwd_longer_than_path_max() {
+    let _dr = crate::DirRestore::new();
+
+    let tmpdir = tempdir().unwrap();
+    let tmpdir_path = tmpdir.path().canonicalize().unwrap();
+    assert!(chdir(&tmpdir_path).is_ok());
+
+    // Relative paths let the current directory get deeper than PATH_MAX
+    let name = "a".repeat(200);
+    let mut expected = tmpdir_path.clone();
+    while expected.as_os_str().len() <= libc::PATH_MAX as usize {
+        mkdir(name.as_str(), Mode::S_IRWXU).unwrap();
+        chdir(name.as_str()).unwrap();
+        expected.push(&name);
+    }
+    assert_eq!(getcwd().unwrap(), expected);
+
+    // Get out, so that the temporary directory can be removed
+    chdir(&tmpdir_path).unwrap();
+}
+
 #[test]
 fn test_chown() {
     // Testing for anything other than our own UID/GID is hard.
@@ -559,6 +802,39 @@ fn test_sysconf_limited() {
     assert!(open_max.expect("sysconf failed").expect("OPEN_MAX is unlimited") > 0);
 }
 
+#[test]
+fn test_pathconf_name_max() {
+    let name_max = pathconf("/", PathconfVar::NAME_MAX);
+    assert!(name_max.expect("pathconf failed").expect("NAME_MAX is unlimited") > 0);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_pathconf_error() {
+    // A nonexistent path is an error, not an unlimited value
+    let tempdir = tempdir().unwrap();
+    let path = tempdir.path().join("does_not_exist");
+    assert_eq!(pathconf(&path, PathconfVar::NAME_MAX),
+               Err(Error::Sys(Errno::ENOENT)));
+}
+
+#[test]
+fn test_sysconf_page_size() {
+    let page_size = sysconf(SysconfVar::PAGE_SIZE).unwrap().unwrap();
+    assert!(page_size > 0);
+    assert_eq!(page_size & (page_size - 1), 0);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_sysconf_nprocessors() {
+    let conf = sysconf(SysconfVar::_NPROCESSORS_CONF).unwrap().unwrap();
+    let onln = sysconf(SysconfVar::_NPROCESSORS_ONLN).unwrap().unwrap();
+    assert!(onln > 0);
+    assert!(onln <= conf);
+    assert!(sysconf(SysconfVar::_PHYS_PAGES).unwrap().unwrap() > 0);
+}
+
 #[cfg(target_os = "freebsd")]
 #[test]
 fn test_sysconf_unsupported() {
@@ -583,14 +859,6 @@ fn test_pipe() {
 
 // pipe2(2) is the same as pipe(2), except it allows setting some flags.  Check
 // that we can set a flag.
-#[cfg(any(target_os = "android",
-          target_os = "dragonfly",
-          target_os = "emscripten",
-          target_os = "freebsd",
-          target_os = "linux",
-          target_os = "netbsd",
-          target_os = "openbsd",
-          target_os = "redox"))]
 #[test]
 fn test_pipe2() {
     let (fd0, fd1) = pipe2(OFlag::O_CLOEXEC).unwrap();
@@ -600,6 +868,53 @@ fn test_pipe2() {
     assert!(f1.contains(FdFlag::FD_CLOEXEC));
 }
 
+#[test]
+fn test_pipe_struct() {
+    let Pipe { read, write } = Pipe::new(OFlag::O_NONBLOCK).unwrap();
+    for fd in &[&read, &write] {
+        let fl = OFlag::from_bits_truncate(fcntl(fd.as_raw_fd(), FcntlArg::F_GETFL).unwrap());
+        assert!(fl.contains(OFlag::O_NONBLOCK));
+        assert!(!fd.fd_flags().unwrap().contains(FdFlag::FD_CLOEXEC));
+    }
+
+    let mut buf = [0u8; 1];
+    assert_eq!(nix::unistd::read(read.as_raw_fd(), &mut buf),
+               Err(Error::Sys(Errno::EAGAIN)));
+}
+
+#[test]
+fn test_owned_fd() {
+    use nix::unistd::OwnedFd;
+    use std::io::Read;
+
+    let Pipe { read: mut r, write: mut w } = Pipe::new(OFlag::empty()).unwrap();
+    let mut w2 = w.try_clone().unwrap();
+    assert_ne!(w.as_raw_fd(), w2.as_raw_fd());
+    assert!(!w2.fd_flags().unwrap().contains(FdFlag::FD_CLOEXEC));
+    w2.set_fd_flags(FdFlag::FD_CLOEXEC).unwrap();
+    assert!(w2.fd_flags().unwrap().contains(FdFlag::FD_CLOEXEC));
+
+    // dup3 takes over a file descriptor that nothing else owns
+    let spare = r.try_clone().unwrap().into_raw_fd();
+    let w3 = OwnedFd::dup3(w.as_raw_fd(), spare, OFlag::O_CLOEXEC).unwrap();
+    assert_eq!(w3.as_raw_fd(), spare);
+    assert!(w3.fd_flags().unwrap().contains(FdFlag::FD_CLOEXEC));
+    drop(w3);
+
+    w.write_all(b"foo").unwrap();
+    w2.write_all(b"bar").unwrap();
+    drop(w);
+    drop(w2);
+
+    // Reading reaches EOF only if dropping closed every write end
+    let mut buf = String::new();
+    r.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "foobar");
+
+    let raw = r.into_raw_fd();
+    close(raw).unwrap();
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_truncate() {
@@ -637,6 +952,119 @@ fn test_ftruncate() {
     assert_eq!(2, metadata.len());
 }
 
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_truncate64() {
+    // Larger than a 32-bit off_t
+    const LEN: libc::off64_t = 5 << 30;
+
+    let tempdir = tempdir().unwrap();
+    let path = tempdir.path().join("file");
+    File::create(&path).unwrap();
+
+    truncate64(&path, LEN).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().len(), LEN as u64);
+
+    let fd = open(&path, OFlag::O_WRONLY, Mode::empty()).unwrap();
+    ftruncate64(fd, LEN + 1).unwrap();
+    close(fd).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().len(), LEN as u64 + 1);
+}
+
+#[test]
+#[cfg(not(target_os = "redox"))]
+fn test_chroot() {
+    skip_if_not_root!("test_chroot");
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let tempdir = tempdir().unwrap();
+    File::create(tempdir.path().join("marker")).unwrap();
+
+    // Safe: the child only makes system calls, and paths are converted to C
+    // strings on the stack
+    match fork().unwrap() {
+        Child => {
+            let ok = chroot(tempdir.path()).is_ok()
+                && chdir("/").is_ok()
+                && stat::stat("/marker").is_ok()
+                && match (stat::stat("."), stat::stat("/")) {
+                    (Ok(cwd), Ok(root)) => {
+                        (cwd.st_dev, cwd.st_ino) == (root.st_dev, root.st_ino)
+                    },
+                    _ => false,
+                };
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            assert_eq!(waitpid(child, None), Ok(WaitStatus::Exited(child, 0)));
+        }
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_pivot_root() {
+    use nix::mount::{mount, umount2, MntFlags, MsFlags};
+    use nix::sched::{unshare, CloneFlags};
+
+    skip_if_not_root!("test_pivot_root");
+
+    let _m = crate::FORK_MTX.lock().expect("Mutex got poisoned by another test");
+
+    let tempdir = tempdir().unwrap();
+    let marker = tempdir.path().join("marker");
+    const NONE: Option<&'static [u8]> = None;
+
+    // Safe: the child only makes system calls, and paths are converted to C
+    // strings on the stack
+    match fork().unwrap() {
+        Child => {
+            // The new root must be a private mount point
+            let mounted = unshare(CloneFlags::CLONE_NEWNS).is_ok()
+                && mount(NONE, "/", NONE, MsFlags::MS_REC | MsFlags::MS_PRIVATE, NONE).is_ok()
+                && mount(Some("tmpfs"), tempdir.path(), Some("tmpfs"), MsFlags::empty(),
+                         NONE).is_ok();
+            if !mounted {
+                unsafe { _exit(2) };
+            }
+            let ok = open(&marker, OFlag::O_CREAT | OFlag::O_WRONLY, Mode::S_IRUSR)
+                    .and_then(close).is_ok()
+                && chdir(tempdir.path()).is_ok()
+                && pivot_root(".", ".").is_ok()
+                && umount2(".", MntFlags::MNT_DETACH).is_ok()
+                && chdir("/").is_ok()
+                && stat::stat("/marker").is_ok()
+                && stat::stat("/proc").is_err();
+            unsafe { _exit(if ok { 0 } else { 1 }) };
+        },
+        Parent { child } => {
+            match waitpid(child, None) {
+                Ok(WaitStatus::Exited(_, 2)) => {
+                    skip!("Can't create a mount namespace. Skipping test.");
+                },
+                status => assert_eq!(status, Ok(WaitStatus::Exited(child, 0))),
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn test_sync_file() {
+    let tempdir = tempdir().unwrap();
+    let mut file = File::create(tempdir.path().join("file")).unwrap();
+    file.write_all(b"data").unwrap();
+
+    let fd = file.as_raw_fd();
+    fsync(fd).unwrap();
+    fdatasync(fd).unwrap();
+    syncfs(fd).unwrap();
+    sync();
+
+    assert_eq!(syncfs(-1), Err(Error::Sys(Errno::EBADF)));
+}
+
 // Used in `test_alarm`.
 #[cfg(not(target_os = "redox"))]
 static mut ALARM_CALLED: bool = false;
@@ -992,6 +1420,14 @@ fn test_ttyname() {
     assert!(name.starts_with("/dev"));
 }
 
+#[test]
+#[cfg(not(any(target_os = "android", target_os = "redox")))]
+fn test_ctermid() {
+    let path = ctermid();
+    assert!(path.is_absolute());
+    assert!(path.starts_with("/dev"));
+}
+
 #[test]
 #[cfg(not(target_os = "redox"))]
 fn test_ttyname_not_pty() {